@@ -0,0 +1,67 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use gambit::movegen::legal_moves;
+use gambit::{attacks, perft, Bitboard, Board, Square};
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+fn attack_lookup(c: &mut Criterion) {
+    let occupied = Board::from_fen(KIWIPETE).unwrap().occupied();
+
+    c.bench_function("queen attacks on every square", |b| {
+        b.iter(|| {
+            Square::iter().fold(Bitboard::EMPTY, |all, square| {
+                all | attacks::queen_attacks(square, black_box(occupied))
+            })
+        })
+    });
+}
+
+fn movegen(c: &mut Criterion) {
+    let board = Board::from_fen(KIWIPETE).unwrap();
+
+    c.bench_function("legal moves in kiwipete", |b| {
+        b.iter(|| legal_moves(black_box(&board)))
+    });
+}
+
+fn make_move(c: &mut Criterion) {
+    let board = Board::from_fen(KIWIPETE).unwrap();
+    let moves = legal_moves(&board);
+
+    c.bench_function("copy and make every move in kiwipete", |b| {
+        b.iter(|| {
+            for &mv in &moves {
+                let mut child = black_box(&board).clone();
+                child.make_move(mv);
+                black_box(&child);
+            }
+        })
+    });
+
+    c.bench_function("make and unmake every move in kiwipete", |b| {
+        let mut board = board.clone();
+
+        b.iter(|| {
+            for &mv in &moves {
+                let state = board.state();
+                black_box(&mut board).make_move(mv);
+                board.unmake_move(mv, state);
+            }
+        })
+    });
+}
+
+fn perft_5(c: &mut Criterion) {
+    let mut board = Board::default();
+
+    let mut group = c.benchmark_group("perft");
+    group.sample_size(10);
+    group.bench_function("startpos depth 5", |b| {
+        b.iter(|| perft(black_box(&mut board), 5))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, attack_lookup, movegen, make_move, perft_5);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use gambit::Board;
+use gambit_engine::eval::evaluate;
+use gambit_engine::{Engine, EngineOptions, SearchLimits};
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+fn evaluation(c: &mut Criterion) {
+    let board = Board::from_fen(KIWIPETE).unwrap();
+
+    c.bench_function("evaluate kiwipete", |b| {
+        b.iter(|| evaluate(black_box(&board)))
+    });
+}
+
+fn search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("search");
+    group.sample_size(10);
+    for (name, copy_make) in [("copy-make", true), ("make/unmake", false)] {
+        group.bench_function(format!("kiwipete depth 5 with {name}"), |b| {
+            b.iter(|| {
+                let mut engine = Engine::new(EngineOptions {
+                    copy_make,
+                    ..EngineOptions::default()
+                });
+                engine.set_position(KIWIPETE, [""; 0]).unwrap();
+
+                engine.search(SearchLimits {
+                    depth: Some(5),
+                    ..SearchLimits::default()
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, evaluation, search);
+criterion_main!(benches);
@@ -0,0 +1,120 @@
+//! Pinning search threads to processors.
+//!
+//! On machines with several NUMA nodes, a thread the scheduler moves between nodes loses its
+//! caches and reaches its memory through the slower interconnect. Pinned threads are spread
+//! over the nodes in turn, so that every node gets an equal share, and each stays on one
+//! processor of its node.
+//!
+//! Only Linux is supported; elsewhere pinning does nothing and threads are scheduled as usual.
+
+/// Parses a processor list as the kernel writes it, such as `0-3,8-11`, skipping malformed
+/// ranges.
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.trim()
+        .split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((first, last)) => Some(first.parse().ok()?..=last.parse().ok()?),
+            None => range.parse().ok().map(|cpu| cpu..=cpu),
+        })
+        .flatten()
+        .collect()
+}
+
+/// Orders the processors of `nodes` so that consecutive threads land on different nodes: the
+/// first processor of every node, then the second of every node, and so on.
+pub fn interleave(nodes: &[Vec<usize>]) -> Vec<usize> {
+    let longest = nodes.iter().map(Vec::len).max().unwrap_or(0);
+
+    (0..longest)
+        .flat_map(|index| {
+            nodes
+                .iter()
+                .filter_map(move |cpus| cpus.get(index).copied())
+        })
+        .collect()
+}
+
+/// Returns the processors this process may run on, in the order threads should be pinned to
+/// them, or nothing where pinning is not supported.
+pub fn cpu_order() -> Vec<usize> {
+    platform::cpu_order()
+}
+
+/// Pins the calling thread to the processor for the `index`th thread, returning whether it
+/// was pinned.
+pub fn pin_current_thread(index: usize) -> bool {
+    let order = cpu_order();
+
+    match order.get(index % order.len().max(1)) {
+        Some(&cpu) => platform::pin_current_thread(cpu),
+        None => false,
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::{fs, mem};
+
+    use super::{interleave, parse_cpu_list};
+
+    pub fn cpu_order() -> Vec<usize> {
+        let allowed = allowed_cpus();
+
+        // Machines without NUMA support have no node directories, and count as a single node.
+        let mut nodes: Vec<Vec<usize>> = (0..)
+            .map_while(|node| {
+                fs::read_to_string(format!("/sys/devices/system/node/node{node}/cpulist")).ok()
+            })
+            .map(|list| {
+                parse_cpu_list(&list)
+                    .into_iter()
+                    .filter(|cpu| allowed.contains(cpu))
+                    .collect()
+            })
+            .filter(|cpus: &Vec<usize>| !cpus.is_empty())
+            .collect();
+
+        if nodes.is_empty() {
+            nodes.push(allowed);
+        }
+
+        interleave(&nodes)
+    }
+
+    fn allowed_cpus() -> Vec<usize> {
+        // SAFETY: `cpu_set_t` is a plain bit set, for which all zeroes is the empty set, and
+        // the kernel writes at most `size_of::<cpu_set_t>()` bytes into it.
+        unsafe {
+            let mut set: libc::cpu_set_t = mem::zeroed();
+
+            if libc::sched_getaffinity(0, mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+                return Vec::new();
+            }
+
+            (0..libc::CPU_SETSIZE as usize)
+                .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+                .collect()
+        }
+    }
+
+    pub fn pin_current_thread(cpu: usize) -> bool {
+        // SAFETY: as above, and the kernel only reads the set.
+        unsafe {
+            let mut set: libc::cpu_set_t = mem::zeroed();
+            libc::CPU_SET(cpu, &mut set);
+
+            libc::sched_setaffinity(0, mem::size_of::<libc::cpu_set_t>(), &set) == 0
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    pub fn cpu_order() -> Vec<usize> {
+        Vec::new()
+    }
+
+    pub fn pin_current_thread(_: usize) -> bool {
+        false
+    }
+}
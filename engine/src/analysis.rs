@@ -0,0 +1,104 @@
+//! One-shot analysis of a single position, for scripts that would rather not speak UCI.
+//!
+//! Several lines are found by searching the position once per line, each time leaving out the
+//! first moves of the lines already found: the second line is the best the engine can do
+//! without the best move, the third without either of the first two, and so on.
+
+use gambit::{movegen, Board, Move};
+
+use crate::{Engine, SearchLimits, SearchProgress, SearchResult};
+
+/// Searches the engine's position for up to `lines` lines under `limits`, best first.
+///
+/// Fewer lines are returned when the position has fewer moves to choose from, counting only
+/// [`SearchLimits::search_moves`] when it is not empty. `progress` is called with the number
+/// of the line being searched, from one, and the progress of its search.
+pub fn analyse(
+    engine: &mut Engine,
+    limits: &SearchLimits,
+    lines: usize,
+    mut progress: impl FnMut(usize, SearchProgress),
+) -> Vec<SearchResult> {
+    let candidates: Vec<Move> = if limits.search_moves.is_empty() {
+        movegen::legal_moves(engine.board())
+            .iter()
+            .copied()
+            .collect()
+    } else {
+        limits.search_moves.clone()
+    };
+    let mut results: Vec<SearchResult> = Vec::new();
+
+    for line in 1..=lines {
+        let search_moves: Vec<Move> = candidates
+            .iter()
+            .copied()
+            .filter(|&mv| results.iter().all(|result| result.best_move != Some(mv)))
+            .collect();
+
+        if search_moves.is_empty() {
+            break;
+        }
+
+        let result = engine.search_with_progress(
+            SearchLimits {
+                search_moves,
+                ..limits.clone()
+            },
+            |update| progress(line, update),
+        );
+
+        if result.best_move.is_none() {
+            break;
+        }
+
+        results.push(result);
+    }
+
+    results
+}
+
+/// Formats `moves`, played in turn from `board`, in SAN.
+pub fn san_line<'a>(board: &Board, moves: impl IntoIterator<Item = &'a Move>) -> String {
+    let mut board = board.clone();
+    let mut sans = Vec::new();
+
+    for &mv in moves {
+        sans.push(board.san(mv));
+        board.make_move(mv);
+    }
+
+    sans.join(" ")
+}
+
+/// Formats line `line` of the analysis of `board` as
+/// `line <line> depth <depth> score <score> nodes <nodes> pv <moves in SAN>`.
+pub fn format_line(board: &Board, line: usize, result: &SearchResult) -> String {
+    format!(
+        "line {line} depth {} score {} nodes {} pv {}",
+        result.depth,
+        result.score,
+        result.nodes,
+        san_line(board, &result.pv)
+    )
+}
+
+/// Formats the completed iterations of line `line` of the analysis of `board`, ignoring the
+/// other progress.
+pub fn format_progress(board: &Board, line: usize, progress: &SearchProgress) -> Option<String> {
+    match progress {
+        SearchProgress::DepthCompleted {
+            depth,
+            score,
+            pv,
+            nodes,
+            elapsed,
+            ..
+        } => Some(format!(
+            "line {line} depth {depth} score {score} nodes {nodes} time {} pv {}",
+            elapsed.as_millis(),
+            san_line(board, pv)
+        )),
+        _ => None,
+    }
+}
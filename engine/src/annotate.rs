@@ -0,0 +1,125 @@
+//! Annotating games with the engine's opinion of every move.
+//!
+//! Every position of a game is searched once. The score of the position before a move is
+//! what the best move keeps, and the score of the position after it, seen from the side that
+//! played it, is what the move played keeps. The difference is what the move lost, which
+//! earns it `?!`, `?` or `??` once it passes the [`Thresholds`].
+
+use gambit::pgn::{self, Annotation, Game};
+use gambit::Colour;
+
+use crate::{Engine, Score, SearchLimits, SearchResult};
+
+/// The glyphs given to moves, by the numbers PGN knows them by.
+const INACCURACY: u8 = 6;
+const MISTAKE: u8 = 2;
+const BLUNDER: u8 = 4;
+
+/// The centipawns mate scores are capped at when working out what a move lost, so that
+/// choosing a slower mate, or a won ending over a mate, does not count as a blunder.
+const MATE_CENTIPAWNS: i32 = 1000;
+
+/// The centipawns a move must lose against the best move to be marked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Thresholds {
+    /// Marks a move `?!`.
+    pub inaccuracy: i32,
+    /// Marks a move `?`.
+    pub mistake: i32,
+    /// Marks a move `??`.
+    pub blunder: i32,
+}
+
+impl Default for Thresholds {
+    fn default() -> Thresholds {
+        Thresholds {
+            inaccuracy: 50,
+            mistake: 100,
+            blunder: 300,
+        }
+    }
+}
+
+/// Searches every position of `game` under `limits` and returns the game as PGN, with the
+/// evaluation after each move in a `[%eval]` comment and the moves that lose too much marked
+/// along with the move that was best.
+///
+/// The engine forgets earlier searches first, as the game is unrelated to them.
+pub fn annotate(
+    engine: &mut Engine,
+    game: &Game,
+    limits: &SearchLimits,
+    thresholds: &Thresholds,
+) -> String {
+    engine.new_game();
+
+    let fen = game.start().to_fen();
+    let moves: Vec<String> = game.moves().iter().map(ToString::to_string).collect();
+    let results: Vec<SearchResult> = (0..=moves.len())
+        .map(|ply| {
+            // The moves were read from the game, so they can be played again.
+            let _ = engine.set_position(&fen, &moves[..ply]);
+            engine.search(limits.clone())
+        })
+        .collect();
+
+    let annotations = game.positions().enumerate().map(|(ply, (board, mv))| {
+        let best = &results[ply];
+        let played = -results[ply + 1].score;
+
+        let lost = match best.best_move == Some(mv) {
+            true => 0,
+            false => centipawns(best.score) - centipawns(played),
+        };
+        let nag = if lost >= thresholds.blunder {
+            Some(BLUNDER)
+        } else if lost >= thresholds.mistake {
+            Some(MISTAKE)
+        } else if lost >= thresholds.inaccuracy {
+            Some(INACCURACY)
+        } else {
+            None
+        };
+
+        let white = match board.side_to_move() {
+            Colour::White => played,
+            Colour::Black => -played,
+        };
+        // A game that ends in mate or stalemate has nothing left to evaluate.
+        let mut comment = match results[ply + 1].best_move {
+            Some(_) => vec![format!("[%eval {}]", eval(white))],
+            None => Vec::new(),
+        };
+        if let (Some(_), Some(best_move)) = (nag, best.best_move) {
+            comment.push(format!("{} was best", board.san(best_move)));
+        }
+
+        (
+            mv,
+            Annotation {
+                nag,
+                comment: (!comment.is_empty()).then(|| comment.join(" ")),
+            },
+        )
+    });
+
+    pgn::write_game(
+        game.tags(),
+        game.start(),
+        annotations,
+        game.result().unwrap_or("*"),
+    )
+}
+
+fn centipawns(score: Score) -> i32 {
+    score.get().clamp(-MATE_CENTIPAWNS, MATE_CENTIPAWNS)
+}
+
+/// Formats `score` as `[%eval]` comments do: pawns with two decimals, or `#` and the moves
+/// to mate, negative when being mated.
+fn eval(score: Score) -> String {
+    match score.mate_moves() {
+        Some(moves) => format!("#{moves}"),
+        None => format!("{:.2}", f64::from(score.get()) / 100.0),
+    }
+}
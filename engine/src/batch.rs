@@ -0,0 +1,244 @@
+//! Batch analysis of many positions.
+//!
+//! Positions are read one per line, as FEN or as EPD so that test suites with `id` operations
+//! can be used directly. Each is searched under the same limits, spread over a number of
+//! threads that each own an [`Engine`], and the results are written as CSV or JSON in the
+//! order the positions were read.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::iter;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use gambit::{Board, Epd, FenError};
+
+use crate::affinity;
+use crate::{Engine, EngineOptions, Score, SearchLimits, SearchResult};
+
+/// A position to analyse.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Position {
+    /// The `id` operation of an EPD line, if it has one.
+    pub id: Option<String>,
+    pub board: Board,
+}
+
+/// A line that is neither a FEN nor an EPD record, counting lines from one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchError {
+    pub line: usize,
+    pub error: FenError,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// The formats results can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// A header row, then one row per position.
+    Csv,
+    /// An array with one object per position.
+    Json,
+}
+
+/// Reads one position per line of `text`, skipping blank lines and lines starting with `#`.
+pub fn read_positions(text: &str) -> Result<Vec<Position>, BatchError> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line, text)| {
+            if let Ok(board) = Board::from_fen(text) {
+                return Ok(Position { id: None, board });
+            }
+
+            let epd = Epd::from_str(text).map_err(|error| BatchError { line, error })?;
+
+            Ok(Position {
+                id: epd.id().map(str::to_owned),
+                board: epd.into_board(),
+            })
+        })
+        .collect()
+}
+
+/// Searches every position under `limits` on `threads` threads, returning the results in the
+/// order of `positions`.
+///
+/// Every thread has an engine of its own created with `options`, which forgets what it
+/// learned before each position so that the results do not depend on how the positions
+/// were shared out. With [`EngineOptions::numa`] the threads are pinned to processors.
+pub fn analyse(
+    positions: &[Position],
+    limits: &SearchLimits,
+    options: &EngineOptions,
+    threads: usize,
+) -> Vec<SearchResult> {
+    let next = AtomicUsize::new(0);
+    let mut results = vec![None; positions.len()];
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.clamp(1, positions.len().max(1)))
+            .map(|worker| {
+                let next = &next;
+
+                scope.spawn(move || {
+                    if options.numa {
+                        affinity::pin_current_thread(worker);
+                    }
+
+                    let mut engine = Engine::new(options.clone());
+                    let mut searched = Vec::new();
+
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(position) = positions.get(index) else {
+                            break searched;
+                        };
+
+                        engine.new_game();
+                        // The position came from a valid FEN, so its own FEN is valid too.
+                        let _ =
+                            engine.set_position(&position.board.to_fen(), iter::empty::<&str>());
+
+                        searched.push((index, engine.search(limits.clone())));
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            let searched = worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+
+            for (index, result) in searched {
+                results[index] = Some(result);
+            }
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|result| result.expect("every position was searched"))
+        .collect()
+}
+
+/// Writes the results of searching `positions` to `output` in `format`.
+///
+/// Each result has the position's id and FEN, the best move, the score, the depth reached,
+/// the nodes searched and the principal variation.
+pub fn write_results(
+    mut output: impl Write,
+    format: Format,
+    positions: &[Position],
+    results: &[SearchResult],
+) -> io::Result<()> {
+    let rows = positions.iter().zip(results);
+
+    match format {
+        Format::Csv => {
+            writeln!(output, "id,fen,bestmove,score,depth,nodes,pv")?;
+
+            for (position, result) in rows {
+                writeln!(
+                    output,
+                    "{},{},{},{},{},{},{}",
+                    csv_field(position.id.as_deref().unwrap_or("")),
+                    csv_field(&position.board.to_fen()),
+                    result
+                        .best_move
+                        .map(|mv| mv.to_string())
+                        .unwrap_or_default(),
+                    result.score,
+                    result.depth,
+                    result.nodes,
+                    result.pv
+                )?;
+            }
+        }
+        Format::Json => {
+            writeln!(output, "[")?;
+
+            for (index, (position, result)) in rows.enumerate() {
+                let id = position
+                    .id
+                    .as_deref()
+                    .map_or("null".to_owned(), json_string);
+                let best_move = result
+                    .best_move
+                    .map_or("null".to_owned(), |mv| json_string(&mv.to_string()));
+                let score = json_score(result.score);
+                let pv: Vec<_> = result
+                    .pv
+                    .iter()
+                    .map(|mv| json_string(&mv.to_string()))
+                    .collect();
+
+                write!(
+                    output,
+                    "  {{\"id\": {id}, \"fen\": {}, \"bestmove\": {best_move}, \"score\": {score}, \
+                     \"depth\": {}, \"nodes\": {}, \"pv\": [{}]}}",
+                    json_string(&position.board.to_fen()),
+                    result.depth,
+                    result.nodes,
+                    pv.join(", ")
+                )?;
+                writeln!(
+                    output,
+                    "{}",
+                    if index + 1 < results.len() { "," } else { "" }
+                )?;
+            }
+
+            writeln!(output, "]")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Writes `score` as an object with either a `cp` or a `mate` field, as UCI reports it.
+pub(crate) fn json_score(score: Score) -> String {
+    match score.mate_moves() {
+        Some(moves) => format!("{{\"mate\": {moves}}}"),
+        None => format!("{{\"cp\": {}}}", score.get()),
+    }
+}
+
+pub(crate) fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
@@ -0,0 +1,76 @@
+//! Correction of the static evaluation by what earlier searches found.
+//!
+//! The evaluation is systematically wrong about some pawn structures, and positions with the
+//! same pawns tend to be wrong in the same direction. After a search, the difference between
+//! its score and the static evaluation is folded into an entry keyed by the side to move and
+//! the pawn key, and that entry is added to later evaluations of positions sharing them.
+
+use gambit::Board;
+
+use crate::score::Score;
+
+/// The number of entries per side, a power of two.
+const SIZE: usize = 1 << 14;
+
+/// Entries hold centipawns scaled up by this much, so that small errors still register.
+const GRAIN: i32 = 256;
+
+/// The largest correction, in centipawns.
+const MAX_CORRECTION: i32 = 96;
+
+/// The weight of a single update out of [`WEIGHT_SCALE`] grows with depth up to this.
+const MAX_WEIGHT: i32 = 16;
+
+const WEIGHT_SCALE: i32 = 256;
+
+pub struct CorrectionHistory {
+    entries: Box<[[i16; SIZE]; 2]>,
+}
+
+impl CorrectionHistory {
+    pub fn new() -> CorrectionHistory {
+        CorrectionHistory {
+            entries: Box::new([[0; SIZE]; 2]),
+        }
+    }
+
+    /// Returns `eval`, the static evaluation of `board`, adjusted by what was learned about
+    /// its pawn structure. The result is kept clear of mate scores.
+    pub fn correct(&self, board: &Board, eval: Score) -> Score {
+        let correction = *self.entry(board) as i32 / GRAIN;
+        let bound = Score::MATE_BOUND.get() - 1;
+
+        Score::new((eval.get() + correction).clamp(-bound, bound))
+    }
+
+    /// Moves the entry for `board` towards the error of `eval`, the corrected static
+    /// evaluation, against `score`, the result of a search to `depth`. Deeper searches are
+    /// trusted more.
+    pub fn update(&mut self, board: &Board, depth: i32, eval: Score, score: Score) {
+        let entry = self.entry_mut(board);
+        let weight = (depth + 1).min(MAX_WEIGHT);
+        let target = *entry as i32 + (score.get() - eval.get()) * GRAIN;
+        let limit = MAX_CORRECTION * GRAIN;
+
+        *entry = ((*entry as i32 * (WEIGHT_SCALE - weight) + target * weight) / WEIGHT_SCALE)
+            .clamp(-limit, limit) as i16;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.iter_mut().for_each(|side| side.fill(0));
+    }
+
+    fn entry(&self, board: &Board) -> &i16 {
+        &self.entries[board.side_to_move().index()][board.pawn_key() as usize & (SIZE - 1)]
+    }
+
+    fn entry_mut(&mut self, board: &Board) -> &mut i16 {
+        &mut self.entries[board.side_to_move().index()][board.pawn_key() as usize & (SIZE - 1)]
+    }
+}
+
+impl Default for CorrectionHistory {
+    fn default() -> CorrectionHistory {
+        CorrectionHistory::new()
+    }
+}
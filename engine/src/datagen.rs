@@ -0,0 +1,309 @@
+//! Training data from self-play.
+//!
+//! Games are played by the engine against itself from the standard position after a few
+//! random moves, so that no two games are alike, and each position is kept with the score
+//! the search gave it and the result the game reached. Positions in check and positions where
+//! the move played was a capture or a promotion are left out, as their static evaluation says
+//! little about their score, and so are positions scored as mates.
+//!
+//! Each position is written as a 32-byte [`Record`]:
+//!
+//! | bytes | contents                                                                   |
+//! |-------|----------------------------------------------------------------------------|
+//! | 0-7   | occupied squares, little-endian, one bit per square from A1                |
+//! | 8-23  | a nibble per occupied square in square order, low nibble first: the piece  |
+//! |       | kind, pawn to king, plus 6 for Black                                       |
+//! | 24    | bit 0 set with Black to move, bits 1-4 the castling rights `KQkq`          |
+//! | 25    | the en passant square, or 64 if there is none                              |
+//! | 26    | the halfmove clock                                                         |
+//! | 27-28 | the fullmove number, little-endian                                         |
+//! | 29-30 | the score in centipawns from White's side, little-endian                   |
+//! | 31    | the result from White's side: 1 for a win, 0 for a draw and -1 for a loss  |
+
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use gambit::{
+    movegen, Bitboard, Board, CastlingPermissions, Colour, FenError, Piece, PieceKind, Square,
+};
+
+use crate::handicap::Rng;
+use crate::selfplay::{self, Adjudication, Outcome, TimeControl};
+use crate::{Engine, EngineOptions, SearchLimits};
+
+/// The size of an encoded [`Record`] in bytes.
+pub const RECORD_SIZE: usize = 32;
+
+/// A position with its score and the result of its game, both from White's side.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub board: Board,
+    pub score: i16,
+    pub result: i8,
+}
+
+/// A record that does not describe a valid position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// More than 32 squares are occupied, or a nibble is not a piece.
+    InvalidPieces,
+    InvalidPosition(FenError),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidPieces => f.write_str("invalid pieces"),
+            DecodeError::InvalidPosition(error) => write!(f, "invalid position: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl Record {
+    /// Encodes the record in the format described in the [module documentation](self).
+    pub fn encode(&self) -> [u8; RECORD_SIZE] {
+        let board = &self.board;
+        let mut bytes = [0; RECORD_SIZE];
+
+        bytes[0..8].copy_from_slice(&board.occupied().0.to_le_bytes());
+
+        for (index, square) in board.occupied().take(32).enumerate() {
+            let piece = board.piece_at(square).expect("the square is occupied");
+            let code = piece.kind.index() as u8 + 6 * piece.colour.index() as u8;
+
+            bytes[8 + index / 2] |= code << (4 * (index % 2));
+        }
+
+        bytes[24] = board.side_to_move().index() as u8 | board.castling().bits() << 1;
+        bytes[25] = board.en_passant().map_or(64, |square| square.index() as u8);
+        bytes[26] = board.halfmove_clock().min(u8::MAX as u16) as u8;
+        bytes[27..29].copy_from_slice(&board.fullmove_number().to_le_bytes());
+        bytes[29..31].copy_from_slice(&self.score.to_le_bytes());
+        bytes[31] = self.result as u8;
+
+        bytes
+    }
+
+    /// Decodes a record written by [`Record::encode`].
+    pub fn decode(bytes: &[u8; RECORD_SIZE]) -> Result<Record, DecodeError> {
+        let occupied = Bitboard(u64::from_le_bytes(bytes[0..8].try_into().expect("8 bytes")));
+        if occupied.count() > 32 {
+            return Err(DecodeError::InvalidPieces);
+        }
+
+        let mut pieces = [None; 64];
+        for (index, square) in occupied.enumerate() {
+            let code = (bytes[8 + index / 2] >> (4 * (index % 2))) & 0xF;
+            let (colour, kind) = match code {
+                0..=5 => (Colour::White, code),
+                6..=11 => (Colour::Black, code - 6),
+                _ => return Err(DecodeError::InvalidPieces),
+            };
+
+            pieces[square.index()] = Some(Piece::new(PieceKind::ALL[kind as usize], colour));
+        }
+
+        // The position is rebuilt through its FEN, which checks that it is legal.
+        let mut fen = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+
+            for file in 0..8 {
+                match pieces[rank * 8 + file] {
+                    Some(piece) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        fen.push(piece.to_char());
+                    }
+                    None => empty += 1,
+                }
+            }
+
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                fen.push('/');
+            }
+        }
+
+        let side = match bytes[24] & 1 {
+            0 => 'w',
+            _ => 'b',
+        };
+        let castling = CastlingPermissions::from_bits((bytes[24] >> 1) & 0xF);
+        let en_passant = Square::from_index(bytes[25] as usize)
+            .map_or_else(|| "-".to_owned(), |square| square.to_string());
+        let fullmove = u16::from_le_bytes([bytes[27], bytes[28]]);
+
+        let fen = format!(
+            "{fen} {side} {castling} {en_passant} {} {fullmove}",
+            bytes[26]
+        );
+        let board = Board::from_fen(&fen).map_err(DecodeError::InvalidPosition)?;
+
+        Ok(Record {
+            board,
+            score: i16::from_le_bytes([bytes[29], bytes[30]]),
+            result: bytes[31] as i8,
+        })
+    }
+}
+
+/// Decodes every record in `bytes`, ignoring a trailing partial record.
+pub fn decode_all(bytes: &[u8]) -> impl Iterator<Item = Result<Record, DecodeError>> + '_ {
+    bytes
+        .chunks_exact(RECORD_SIZE)
+        .map(|chunk| Record::decode(chunk.try_into().expect("a whole record")))
+}
+
+/// How training data is generated.
+#[derive(Clone, Debug)]
+pub struct DatagenSettings {
+    pub games: usize,
+    /// The limits every move is searched under, usually a small node count.
+    pub limits: SearchLimits,
+    /// The random moves played from the standard position before the engine takes over.
+    pub random_plies: usize,
+    /// The number of games played at the same time.
+    pub threads: usize,
+    /// The seed of the random moves, each thread's generator being seeded from it in turn.
+    pub seed: u64,
+}
+
+/// The games played and the positions kept by [`generate`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DatagenStats {
+    pub games: usize,
+    pub positions: usize,
+}
+
+/// Plays `settings.games` self-play games with engines created from `options`, writing the
+/// positions kept to `output` as they finish.
+pub fn generate(
+    options: &EngineOptions,
+    settings: &DatagenSettings,
+    mut output: impl Write,
+) -> io::Result<DatagenStats> {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let time_control = TimeControl::PerMove(settings.limits.clone());
+    let adjudication = Adjudication::default();
+    let mut stats = DatagenStats::default();
+
+    thread::scope(|scope| {
+        for worker in 0..settings.threads.clamp(1, settings.games.max(1)) {
+            let sender = sender.clone();
+            let (next, time_control, adjudication) = (&next, &time_control, &adjudication);
+
+            scope.spawn(move || {
+                let mut rng = Rng::new(settings.seed.wrapping_add(worker as u64));
+                let mut white = Engine::new(options.clone());
+                let mut black = Engine::new(options.clone());
+
+                while next.fetch_add(1, Ordering::Relaxed) < settings.games {
+                    let start = random_opening(&mut rng, settings.random_plies);
+                    let record = selfplay::play_game(
+                        &mut white,
+                        &mut black,
+                        &start,
+                        time_control,
+                        adjudication,
+                    );
+
+                    if sender.send(records(&record)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        let mut written = Ok(());
+
+        for records in receiver {
+            // After a failed write no more games are handed out, and those still being played
+            // are received so that no worker is left blocked, but nothing more is written.
+            if written.is_err() {
+                continue;
+            }
+
+            let bytes: Vec<u8> = records.iter().flat_map(Record::encode).collect();
+            written = output.write_all(&bytes);
+
+            match written {
+                Ok(()) => {
+                    stats.games += 1;
+                    stats.positions += records.len();
+                }
+                Err(_) => next.store(settings.games, Ordering::Relaxed),
+            }
+        }
+
+        written
+    })?;
+
+    output.flush()?;
+    Ok(stats)
+}
+
+/// Plays `plies` random moves from the standard position, starting again should the game end
+/// on the way.
+fn random_opening(rng: &mut Rng, plies: usize) -> Board {
+    'retry: loop {
+        let mut board = Board::default();
+
+        for _ in 0..plies {
+            let moves = movegen::legal_moves(&board);
+            if moves.is_empty() {
+                continue 'retry;
+            }
+
+            board.make_move(moves[rng.below(moves.len() as u64) as usize]);
+        }
+
+        if !movegen::legal_moves(&board).is_empty() {
+            return board;
+        }
+    }
+}
+
+/// Returns the positions of a game worth training on, with their scores and its result.
+fn records(game: &selfplay::GameRecord) -> Vec<Record> {
+    let result = match game.outcome {
+        Outcome::WhiteWins => 1,
+        Outcome::Draw => 0,
+        Outcome::BlackWins => -1,
+    };
+
+    let mut board = game.start.clone();
+    let mut records = Vec::new();
+
+    for played in &game.moves {
+        let quiet = !board.in_check() && !played.mv.is_tactical() && !played.score.is_mate();
+
+        if quiet {
+            let score = match board.side_to_move() {
+                Colour::White => played.score.get(),
+                Colour::Black => -played.score.get(),
+            };
+
+            records.push(Record {
+                board: board.clone(),
+                score: score.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+                result,
+            });
+        }
+
+        board.make_move(played.mv);
+    }
+
+    records
+}
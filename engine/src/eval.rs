@@ -0,0 +1,489 @@
+//! Static evaluation.
+//!
+//! Material and piece-square tables, with the king's table tapered between the middlegame and
+//! the endgame by the non-pawn material left on the board, and positional terms on top.
+//!
+//! The position is read once into an array of twelve bitboards, one per piece, and material is
+//! folded into the piece-square tables, so that the hot loop is a single table lookup per
+//! piece. The phase, the material imbalance and any specialised endgame evaluator come from
+//! the [material entry](crate::material) of the position. Attacks, king zones and mobility
+//! areas are gathered once into an [`EvalInfo`] that every positional term reads: mobility,
+//! king safety and threats. Those terms are the expensive stage of the evaluation, which
+//! [`evaluate_lazily`] skips when the cheap stage is already far outside the search window.
+//! The weights of every term, including the piece values and tables, come from [`Weights`],
+//! which a file can override; the constants here are the built-in weights.
+
+use gambit::{attacks, Bitboard, Board, Colour, Piece, PieceKind, Square};
+
+use crate::material::{self, MaterialEntry};
+use crate::score::Score;
+use crate::weights::Weights;
+
+/// Piece values in centipawns, indexed by [`PieceKind::index`].
+pub const PIECE_VALUES: [i32; 6] = [100, 320, 330, 500, 900, 0];
+
+/// The contribution of each piece kind to the game phase, indexed by [`PieceKind::index`].
+const PHASE_WEIGHTS: [i32; 6] = [0, 1, 1, 2, 4, 0];
+
+/// The phase of the starting position; a phase of zero is a pawn endgame.
+const MAX_PHASE: i32 = 24;
+
+/// [`PHASE_WEIGHTS`] by [`piece_index`].
+const PIECE_PHASES: [i32; 12] = by_piece(PHASE_WEIGHTS);
+
+/// The value of each square of the mobility area a piece attacks, indexed by
+/// [`PieceKind::index`].
+pub(crate) const MOBILITY_WEIGHTS: [i32; 6] = [0, 4, 3, 2, 1, 0];
+
+/// The number of squares of the mobility area a piece of each kind typically attacks, which
+/// scores nothing.
+const TYPICAL_MOBILITY: [i32; 6] = [0, 4, 6, 7, 12, 0];
+
+/// The value of each attack on a square of the enemy king's zone, indexed by
+/// [`PieceKind::index`].
+pub(crate) const KING_ATTACK_WEIGHTS: [i32; 6] = [0, 6, 6, 8, 12, 0];
+
+/// The bonus for each square of the enemy king's zone attacked twice and not defended twice.
+pub(crate) const KING_ZONE_WEAK_SQUARE: i32 = 6;
+
+/// The bonus for each enemy piece other than a pawn or the king that is attacked and not
+/// defended.
+pub(crate) const HANGING: i32 = 40;
+
+/// The bonus for each enemy piece other than a pawn or the king attacked by a pawn.
+pub(crate) const THREAT_BY_PAWN: i32 = 60;
+
+/// The bonus for each enemy piece other than a pawn or the king that a pawn would attack after
+/// a safe push.
+pub(crate) const THREAT_BY_PAWN_PUSH: i32 = 20;
+
+// Piece-square tables from White's point of view, written as the board is drawn: the first row
+// is the eighth rank.
+
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; 64] = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+    50,  50,  50,  50,  50,  50,  50,  50,
+    10,  10,  20,  30,  30,  20,  10,  10,
+     5,   5,  10,  25,  25,  10,   5,   5,
+     0,   0,   0,  20,  20,   0,   0,   0,
+     5,  -5, -10,   0,   0, -10,  -5,   5,
+     5,  10,  10, -20, -20,  10,  10,   5,
+     0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+   -50, -40, -30, -30, -30, -30, -40, -50,
+   -40, -20,   0,   0,   0,   0, -20, -40,
+   -30,   0,  10,  15,  15,  10,   0, -30,
+   -30,   5,  15,  20,  20,  15,   5, -30,
+   -30,   0,  15,  20,  20,  15,   0, -30,
+   -30,   5,  10,  15,  15,  10,   5, -30,
+   -40, -20,   0,   5,   5,   0, -20, -40,
+   -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+   -20, -10, -10, -10, -10, -10, -10, -20,
+   -10,   0,   0,   0,   0,   0,   0, -10,
+   -10,   0,   5,  10,  10,   5,   0, -10,
+   -10,   5,   5,  10,  10,   5,   5, -10,
+   -10,   0,  10,  10,  10,  10,   0, -10,
+   -10,  10,  10,  10,  10,  10,  10, -10,
+   -10,   5,   0,   0,   0,   0,   5, -10,
+   -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+     5,  10,  10,  10,  10,  10,  10,   5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+     0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+   -20, -10, -10,  -5,  -5, -10, -10, -20,
+   -10,   0,   0,   0,   0,   0,   0, -10,
+   -10,   0,   5,   5,   5,   5,   0, -10,
+    -5,   0,   5,   5,   5,   5,   0,  -5,
+     0,   0,   5,   5,   5,   5,   0,  -5,
+   -10,   5,   5,   5,   5,   5,   0, -10,
+   -10,   0,   5,   0,   0,   0,   0, -10,
+   -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const KING_MIDDLEGAME_TABLE: [i32; 64] = [
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -30, -40, -40, -50, -50, -40, -40, -30,
+   -20, -30, -30, -40, -40, -30, -30, -20,
+   -10, -20, -20, -20, -20, -20, -20, -10,
+    20,  20,   0,   0,   0,   0,  20,  20,
+    20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+#[rustfmt::skip]
+const KING_ENDGAME_TABLE: [i32; 64] = [
+   -50, -40, -30, -20, -20, -30, -40, -50,
+   -30, -20, -10,   0,   0, -10, -20, -30,
+   -30, -10,  20,  30,  30,  20, -10, -30,
+   -30, -10,  30,  40,  40,  30, -10, -30,
+   -30, -10,  30,  40,  40,  30, -10, -30,
+   -30, -10,  20,  30,  30,  20, -10, -30,
+   -30, -30,   0,   0,   0,   0, -30, -30,
+   -50, -30, -30, -30, -30, -30, -30, -50,
+];
+
+/// The built-in tables of the pawn to the queen, then the king's middlegame and endgame tables.
+pub(crate) const TABLES: [&[i32; 64]; 7] = [
+    &PAWN_TABLE,
+    &KNIGHT_TABLE,
+    &BISHOP_TABLE,
+    &ROOK_TABLE,
+    &QUEEN_TABLE,
+    &KING_MIDDLEGAME_TABLE,
+    &KING_ENDGAME_TABLE,
+];
+
+/// What the evaluation terms need to know about a position, computed once per evaluation and
+/// shared by every term instead of each working out the attacks it reads.
+pub struct EvalInfo {
+    /// The squares attacked by the pieces of each colour and kind.
+    attacks: [[Bitboard; 6]; 2],
+    /// The squares attacked by any piece of each colour.
+    attacked: [Bitboard; 2],
+    /// The squares attacked by at least two pieces of each colour.
+    attacked_by_two: [Bitboard; 2],
+    /// The squares around the king of each colour that matter to its safety.
+    king_zones: [Bitboard; 2],
+    /// The squares worth counting towards the mobility of each colour's pieces.
+    mobility_areas: [Bitboard; 2],
+    /// The number of squares of the mobility area attacked, summed over the pieces of each
+    /// colour and kind.
+    mobility: [[i32; 6]; 2],
+}
+
+impl EvalInfo {
+    pub fn new(board: &Board) -> EvalInfo {
+        let occupied = board.occupied();
+        let mut info = EvalInfo {
+            attacks: [[Bitboard::EMPTY; 6]; 2],
+            attacked: [Bitboard::EMPTY; 2],
+            attacked_by_two: [Bitboard::EMPTY; 2],
+            king_zones: [Bitboard::EMPTY; 2],
+            mobility_areas: [Bitboard::EMPTY; 2],
+            mobility: [[0; 6]; 2],
+        };
+
+        // Pawns come first, since the mobility area of each side excludes the squares the
+        // other side's pawns attack.
+        for colour in Colour::ALL {
+            info.add_attacks(board, colour, PieceKind::Pawn, occupied);
+        }
+
+        for colour in Colour::ALL {
+            let them = !colour;
+
+            info.king_zones[colour.index()] =
+                board.king_square(colour).map_or(Bitboard::EMPTY, |king| {
+                    attacks::extended_king_zone(king, colour)
+                });
+            info.mobility_areas[colour.index()] = !(board.pieces_of(colour, PieceKind::Pawn)
+                | board.pieces_of(colour, PieceKind::King)
+                | info.attacks(them, PieceKind::Pawn));
+        }
+
+        for colour in Colour::ALL {
+            for kind in &PieceKind::ALL[1..] {
+                info.add_attacks(board, colour, *kind, occupied);
+            }
+        }
+
+        info
+    }
+
+    fn add_attacks(&mut self, board: &Board, colour: Colour, kind: PieceKind, occupied: Bitboard) {
+        let piece = Piece::new(kind, colour);
+        let area = self.mobility_areas[colour.index()];
+
+        for square in board.pieces_of(colour, kind) {
+            let attacks = attacks::piece_attacks(piece, square, occupied);
+
+            self.attacked_by_two[colour.index()] |= self.attacked[colour.index()] & attacks;
+            self.attacked[colour.index()] |= attacks;
+            self.attacks[colour.index()][kind.index()] |= attacks;
+            self.mobility[colour.index()][kind.index()] += (attacks & area).count() as i32;
+        }
+    }
+
+    /// Returns the squares attacked by the pieces of `colour` of the given kind.
+    pub fn attacks(&self, colour: Colour, kind: PieceKind) -> Bitboard {
+        self.attacks[colour.index()][kind.index()]
+    }
+
+    /// Returns the squares attacked by any piece of `colour`.
+    pub fn attacked(&self, colour: Colour) -> Bitboard {
+        self.attacked[colour.index()]
+    }
+
+    /// Returns the squares attacked by at least two pieces of `colour`.
+    pub fn attacked_by_two(&self, colour: Colour) -> Bitboard {
+        self.attacked_by_two[colour.index()]
+    }
+
+    /// Returns the king of `colour`'s square and ring, extended by a rank towards the enemy.
+    pub fn king_zone(&self, colour: Colour) -> Bitboard {
+        self.king_zones[colour.index()]
+    }
+
+    /// Returns the squares that count towards the mobility of the pieces of `colour`: those
+    /// not occupied by its own pawns or king, nor attacked by enemy pawns.
+    pub fn mobility_area(&self, colour: Colour) -> Bitboard {
+        self.mobility_areas[colour.index()]
+    }
+}
+
+/// Returns the index into a piece-square table for a piece of `colour` on `square`.
+fn table_index(colour: Colour, square: Square) -> usize {
+    // The tables are written from White's side with the eighth rank first.
+    square.relative(colour).flipped().index()
+}
+
+/// Evaluates `board` in centipawns from the point of view of the side to move.
+///
+/// The evaluation shrinks as the halfmove clock runs towards the fifty-move rule, since an
+/// advantage that is not converted in time is worth nothing.
+pub fn evaluate(board: &Board) -> Score {
+    scale_for_halfmove_clock(evaluate_position(board), board.halfmove_clock())
+}
+
+/// Scales `eval` down linearly, to half its value once the halfmove clock reaches 100 plies.
+pub fn scale_for_halfmove_clock(eval: i32, halfmove_clock: u16) -> Score {
+    let clock = (halfmove_clock as i32).min(100);
+
+    Score::new(eval * (200 - clock) / 200)
+}
+
+/// Evaluates `board` like [`evaluate`] but ignoring the halfmove clock, so that the result
+/// depends only on what the Zobrist key covers.
+pub fn evaluate_position(board: &Board) -> i32 {
+    evaluate_with_material(board, &material::analyse(board))
+}
+
+/// Evaluates `board` like [`evaluate_position`], given its material entry.
+pub fn evaluate_with_material(board: &Board, material: &MaterialEntry) -> i32 {
+    evaluate_lazily(
+        board,
+        material,
+        Weights::standard(),
+        -Score::INFINITE.get(),
+        Score::INFINITE.get(),
+        i32::MAX,
+    )
+    .0
+}
+
+/// Evaluates `board` like [`evaluate_with_material`], but stops after the cheap stage of
+/// material, piece-square tables and king placement when its score is more than `margin`
+/// below `alpha` or above `beta`, where the positional terms are unlikely to bring it back
+/// into the window.
+///
+/// Returns the evaluation and whether it ran to completion.
+pub fn evaluate_lazily(
+    board: &Board,
+    material: &MaterialEntry,
+    weights: &Weights,
+    alpha: i32,
+    beta: i32,
+    margin: i32,
+) -> (i32, bool) {
+    if let Some(endgame) = material.endgame {
+        let score = (endgame.evaluate)(board, endgame.strong);
+        let score = if board.side_to_move() == endgame.strong {
+            score
+        } else {
+            -score
+        };
+
+        return (score, true);
+    }
+
+    let pieces = piece_bitboards(board);
+    let mut score = piece_square_sum(&pieces, weights) + material.imbalance;
+
+    for colour in Colour::ALL {
+        let sign = if colour == Colour::White { 1 } else { -1 };
+        score += sign * king_placement(&pieces, colour, material.phase, weights);
+    }
+
+    let cheap = from_side_to_move(board, score);
+    if cheap.saturating_add(margin) < alpha || cheap.saturating_sub(margin) > beta {
+        return (cheap, false);
+    }
+
+    let info = EvalInfo::new(board);
+
+    for colour in Colour::ALL {
+        let sign = if colour == Colour::White { 1 } else { -1 };
+        let terms = mobility(board, &info, colour, weights)
+            + king_safety(&info, colour, material.phase, weights)
+            + threats(board, &info, colour, weights);
+
+        score += sign * terms;
+    }
+
+    (from_side_to_move(board, score), true)
+}
+
+/// Converts a score from White's point of view to the side to move's.
+fn from_side_to_move(board: &Board, score: i32) -> i32 {
+    match board.side_to_move() {
+        Colour::White => score,
+        Colour::Black => -score,
+    }
+}
+
+/// Scores the king of `colour` on its table, tapered from the middlegame to the endgame.
+fn king_placement(pieces: &[u64; 12], colour: Colour, phase: i32, weights: &Weights) -> i32 {
+    let Some(king) = Bitboard(pieces[piece_index(colour, PieceKind::King)]).lsb() else {
+        return 0;
+    };
+
+    let index = table_index(colour, king);
+    let (middlegame, endgame) = weights.king_tables();
+
+    (middlegame[index] * phase + endgame[index] * (MAX_PHASE - phase)) / MAX_PHASE
+}
+
+/// Scores how many squares of its mobility area each piece of `colour` attacks, against what
+/// a piece of its kind typically reaches.
+pub fn mobility(board: &Board, info: &EvalInfo, colour: Colour, weights: &Weights) -> i32 {
+    PieceKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let pieces = board.pieces_of(colour, kind).count() as i32;
+            let squares = info.mobility[colour.index()][kind.index()];
+
+            weights.mobility[kind.index()] * (squares - pieces * TYPICAL_MOBILITY[kind.index()])
+        })
+        .sum()
+}
+
+/// Scores the attacks of `colour` on the enemy king's zone, which matter less as pieces come
+/// off the board.
+pub fn king_safety(info: &EvalInfo, colour: Colour, phase: i32, weights: &Weights) -> i32 {
+    let them = !colour;
+    let zone = info.king_zone(them);
+
+    let attacks: i32 = PieceKind::ALL
+        .into_iter()
+        .map(|kind| {
+            weights.king_attack[kind.index()] * (info.attacks(colour, kind) & zone).count() as i32
+        })
+        .sum();
+    let weak = zone & info.attacked_by_two(colour) & !info.attacked_by_two(them);
+
+    (attacks + weights.king_zone_weak_square * weak.count() as i32) * phase / MAX_PHASE
+}
+
+/// Scores the threats `colour` makes against enemy pieces: attacks on undefended pieces,
+/// attacks by pawns, and attacks a pawn would make after a push to a square it is safe on.
+pub fn threats(board: &Board, info: &EvalInfo, colour: Colour, weights: &Weights) -> i32 {
+    let them = !colour;
+    let targets =
+        board.colour(them) & !board.pieces(PieceKind::Pawn) & !board.pieces(PieceKind::King);
+
+    let hanging = targets & info.attacked(colour) & !info.attacked(them);
+    let by_pawn = targets & info.attacks(colour, PieceKind::Pawn);
+
+    let occupied = board.occupied();
+    let safe = !info.attacks(them, PieceKind::Pawn)
+        & (info.attacked_by_two(colour)
+            | (info.attacked(colour) & !info.attacked_by_two(them))
+            | !info.attacked(them));
+
+    let pushes = board
+        .pieces_of(colour, PieceKind::Pawn)
+        .into_iter()
+        .filter_map(|pawn| pawn.forward(colour))
+        .filter(|&to| !occupied.contains(to) && safe.contains(to))
+        .fold(Bitboard::EMPTY, |threatened, to| {
+            threatened | attacks::pawn_attacks(colour, to)
+        });
+    let by_push = targets & pushes & !by_pawn;
+
+    weights.hanging * hanging.count() as i32
+        + weights.threat_by_pawn * by_pawn.count() as i32
+        + weights.threat_by_pawn_push * by_push.count() as i32
+}
+
+/// Returns the game phase of `board`, from [`MAX_PHASE`] with every piece on the board down to
+/// zero in a pawn endgame.
+pub(crate) fn phase(board: &Board) -> i32 {
+    phase_of(&piece_bitboards(board))
+}
+
+/// Returns the index of a piece among the twelve: White's kinds in order, then Black's.
+const fn piece_index(colour: Colour, kind: PieceKind) -> usize {
+    colour.index() * 6 + kind.index()
+}
+
+/// Spreads weights by piece kind over both colours.
+const fn by_piece(weights: [i32; 6]) -> [i32; 12] {
+    let mut table = [0; 12];
+    let mut i = 0;
+
+    while i < 6 {
+        table[i] = weights[i];
+        table[i + 6] = weights[i];
+        i += 1;
+    }
+
+    table
+}
+
+fn piece_bitboards(board: &Board) -> [u64; 12] {
+    let mut pieces = [0; 12];
+
+    for colour in Colour::ALL {
+        for kind in PieceKind::ALL {
+            pieces[piece_index(colour, kind)] = board.pieces_of(colour, kind).0;
+        }
+    }
+
+    pieces
+}
+
+/// Sums the piece-square scores of every piece.
+fn piece_square_sum(pieces: &[u64; 12], weights: &Weights) -> i32 {
+    let mut score = 0;
+
+    for (piece, &pieces) in pieces.iter().enumerate() {
+        for square in Bitboard(pieces) {
+            score += weights.piece_square[piece][square.index()];
+        }
+    }
+
+    score
+}
+
+/// Sums the phase weights of every piece.
+fn phase_of(pieces: &[u64; 12]) -> i32 {
+    let phase: i32 = pieces
+        .iter()
+        .zip(PIECE_PHASES)
+        .map(|(&pieces, weight)| pieces.count_ones() as i32 * weight)
+        .sum();
+
+    phase.min(MAX_PHASE)
+}
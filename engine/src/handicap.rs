@@ -0,0 +1,157 @@
+//! Handicaps that let weaker players compete with the engine.
+//!
+//! Odds games start from the usual position with the engine, or whichever side gives the odds,
+//! missing a piece. The blunder model leaves the search as it is but now and then plays the
+//! second or third best move instead of the best. It finds them by searching again with the
+//! better moves excluded at the root, so a blunder is still the best of what is left rather
+//! than a random move.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use gambit::movegen;
+use gambit::{Board, Colour, File, Rank, Square};
+
+use crate::{SearchLimits, SearchResult};
+
+/// The material one side gives up before the game starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Odds {
+    /// The f-pawn, which leaves the king a little exposed.
+    Pawn,
+    /// The queen's knight.
+    Knight,
+    /// The queen's rook, along with castling on that side.
+    Rook,
+    Queen,
+}
+
+impl Odds {
+    pub const ALL: [Odds; 4] = [Odds::Pawn, Odds::Knight, Odds::Rook, Odds::Queen];
+
+    /// Returns the starting position with `giver` missing the piece.
+    pub fn board(self, giver: Colour) -> Board {
+        let file = match self {
+            Odds::Pawn => File::F,
+            Odds::Knight => File::B,
+            Odds::Rook => File::A,
+            Odds::Queen => File::D,
+        };
+        let rank = match self {
+            Odds::Pawn => Rank::Second,
+            _ => Rank::First,
+        };
+
+        let mut board = Board::default();
+        board.clear_square(Square::new(file, rank.relative(giver)));
+
+        board
+    }
+
+    /// Returns the starting position with `giver` missing the piece, as a FEN.
+    pub fn fen(self, giver: Colour) -> String {
+        self.board(giver).to_fen()
+    }
+}
+
+/// A small xorshift generator, seeded from the process's hash keys unless a seed is given so
+/// that games can be replayed.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // Xorshift never leaves zero.
+        Rng(seed.max(1))
+    }
+
+    /// Creates a generator with a seed that differs from run to run.
+    pub fn from_entropy() -> Rng {
+        Rng::new(RandomState::new().build_hasher().finish())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Returns a number below `bound`, which must not be zero.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+/// Plays a worse move than the best one with a fixed probability.
+#[derive(Clone, Debug)]
+pub struct Blunders {
+    /// The chance of a blunder on each move, in percent.
+    pub chance: u8,
+    rng: Rng,
+}
+
+impl Blunders {
+    /// Creates a model that blunders `chance` percent of the time.
+    pub fn new(chance: u8, rng: Rng) -> Blunders {
+        Blunders {
+            chance: chance.min(100),
+            rng,
+        }
+    }
+
+    /// Returns the move to play instead of `best`, which `search` found on `board` under
+    /// `limits`.
+    ///
+    /// Most of the time that is `best` itself. Otherwise it is the second best move, or one
+    /// time in three the third, found by calling `search` again with the better moves left
+    /// out of [`SearchLimits::search_moves`]. The searches that follow are held to the depth
+    /// `best` reached, which the transposition table makes quick, rather than to the time
+    /// limits, which would start again with each of them. Positions with too few moves to
+    /// choose from keep the worst move there is.
+    pub fn choose(
+        &mut self,
+        board: &Board,
+        limits: &SearchLimits,
+        best: SearchResult,
+        mut search: impl FnMut(SearchLimits) -> SearchResult,
+    ) -> SearchResult {
+        if self.rng.below(100) >= self.chance as u64 {
+            return best;
+        }
+
+        let rank = if self.rng.below(3) == 0 { 3 } else { 2 };
+        let mut allowed: Vec<_> = movegen::legal_moves(board)
+            .iter()
+            .copied()
+            .filter(|mv| limits.search_moves.is_empty() || limits.search_moves.contains(mv))
+            .collect();
+
+        let depth = limits
+            .depth
+            .map_or(best.depth, |depth| depth.min(best.depth));
+        let mut result = best;
+
+        for _ in 1..rank {
+            let Some(played) = result.best_move else {
+                break;
+            };
+
+            allowed.retain(|&mv| mv != played);
+            if allowed.is_empty() {
+                break;
+            }
+
+            result = search(SearchLimits {
+                depth: Some(depth.max(1)),
+                movetime: None,
+                easy_movetime: None,
+                stable_movetime: None,
+                search_moves: allowed.clone(),
+                ..limits.clone()
+            });
+        }
+
+        result
+    }
+}
@@ -0,0 +1,82 @@
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::{Engine, SearchResult};
+
+/// A search running on a background thread, started by [`Engine::start_search`].
+///
+/// Dropping the handle stops the search and waits for the thread to finish, so the engine is
+/// free to use again as soon as the handle is gone.
+pub struct SearchHandle<'a> {
+    stop: Arc<AtomicBool>,
+    receiver: Receiver<SearchResult>,
+    thread: Option<JoinHandle<()>>,
+    engine: PhantomData<&'a mut Engine>,
+}
+
+impl<'a> SearchHandle<'a> {
+    pub(crate) fn new(
+        stop: Arc<AtomicBool>,
+        receiver: Receiver<SearchResult>,
+        thread: JoinHandle<()>,
+    ) -> SearchHandle<'a> {
+        SearchHandle {
+            stop,
+            receiver,
+            thread: Some(thread),
+            engine: PhantomData,
+        }
+    }
+
+    /// Asks the search to stop as soon as possible. The result is still collected with
+    /// [`SearchHandle::await_result`].
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Waits for the search to reach one of its limits, or to be stopped, and returns its result.
+    pub fn await_result(mut self) -> SearchResult {
+        let result = self.receiver.recv();
+        self.join();
+
+        result.expect("the search thread panicked")
+    }
+
+    /// Waits at most `timeout` for the search to finish on its own, then stops it and returns
+    /// the best result found so far.
+    pub fn result_timeout(self, timeout: Duration) -> SearchResult {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(result) => {
+                let mut handle = self;
+                handle.join();
+
+                result
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                self.stop();
+                self.await_result()
+            }
+            Err(RecvTimeoutError::Disconnected) => panic!("the search thread panicked"),
+        }
+    }
+
+    fn join(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            // A panic is reported through the closed channel instead.
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for SearchHandle<'_> {
+    fn drop(&mut self) {
+        if self.thread.is_some() {
+            self.stop();
+            self.join();
+        }
+    }
+}
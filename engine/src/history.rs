@@ -0,0 +1,188 @@
+//! Move ordering statistics learned from earlier cutoffs.
+//!
+//! Every table is updated with the "gravity" formula, which shrinks a bonus the closer the
+//! entry already is to [`MAX_HISTORY`], so scores stay bounded however long the engine
+//! analyses and recent results count for more than old ones.
+
+use gambit::{Colour, Move, Piece, Square};
+
+use crate::search::MAX_PLY;
+
+/// The largest magnitude a history score can have.
+pub const MAX_HISTORY: i32 = 16_384;
+
+/// The largest bonus given for a single cutoff.
+const MAX_BONUS: i32 = 2048;
+
+/// A move as seen by the continuation history: the piece that moved and where it went.
+pub type PieceTo = (Piece, Square);
+
+/// Returns the bonus for a cutoff at the given remaining depth.
+pub fn bonus(depth: i32) -> i32 {
+    (32 * depth * depth).min(MAX_BONUS)
+}
+
+pub struct History {
+    /// Quiet moves, indexed by colour, origin and destination.
+    quiet: Box<[[[i16; 64]; 64]; 2]>,
+    /// Captures, indexed by piece, destination and the kind of piece captured.
+    capture: Box<[[[i16; 6]; 64]; 12]>,
+    /// Quiet moves, indexed by the previous move and then by piece and destination.
+    continuation: Box<[[[i16; 64]; 12]]>,
+}
+
+impl History {
+    pub fn new() -> History {
+        History {
+            quiet: Box::new([[[0; 64]; 64]; 2]),
+            capture: Box::new([[[0; 6]; 64]; 12]),
+            continuation: vec![[[0; 64]; 12]; 12 * 64].into_boxed_slice(),
+        }
+    }
+
+    /// Returns the score of the quiet move `mv` by `colour`, played in reply to `previous`.
+    pub fn quiet(&self, colour: Colour, mv: Move, previous: Option<PieceTo>) -> i32 {
+        let butterfly = self.quiet[colour.index()][mv.from().index()][mv.to().index()];
+        let continuation = previous.map_or(0, |previous| {
+            self.continuation[piece_to_index(previous)][piece_index(colour, mv)][mv.to().index()]
+        });
+
+        butterfly as i32 + continuation as i32
+    }
+
+    /// Returns the score of the capture `mv` by `colour`.
+    pub fn capture(&self, colour: Colour, mv: Move) -> i32 {
+        match mv.captured() {
+            Some(captured) => {
+                self.capture[piece_index(colour, mv)][mv.to().index()][captured.index()] as i32
+            }
+            None => 0,
+        }
+    }
+
+    /// Rewards the quiet move `mv` by `colour` with `bonus`, or punishes it if negative.
+    pub fn update_quiet(
+        &mut self,
+        colour: Colour,
+        mv: Move,
+        previous: Option<PieceTo>,
+        bonus: i32,
+    ) {
+        gravity(
+            &mut self.quiet[colour.index()][mv.from().index()][mv.to().index()],
+            bonus,
+        );
+
+        if let Some(previous) = previous {
+            gravity(
+                &mut self.continuation[piece_to_index(previous)][piece_index(colour, mv)]
+                    [mv.to().index()],
+                bonus,
+            );
+        }
+    }
+
+    /// Rewards the capture `mv` by `colour` with `bonus`, or punishes it if negative.
+    pub fn update_capture(&mut self, colour: Colour, mv: Move, bonus: i32) {
+        if let Some(captured) = mv.captured() {
+            gravity(
+                &mut self.capture[piece_index(colour, mv)][mv.to().index()][captured.index()],
+                bonus,
+            );
+        }
+    }
+
+    /// Halves every score, so that what was learned in earlier searches gradually gives way to
+    /// the current position.
+    pub fn age(&mut self) {
+        let entries = self
+            .quiet
+            .iter_mut()
+            .flatten()
+            .flatten()
+            .chain(self.capture.iter_mut().flatten().flatten())
+            .chain(self.continuation.iter_mut().flatten().flatten());
+
+        for entry in entries {
+            *entry /= 2;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = History::new();
+    }
+}
+
+impl Default for History {
+    fn default() -> History {
+        History::new()
+    }
+}
+
+/// The last two quiet moves that caused a cutoff at each ply from the root, most recent first.
+///
+/// The killers are kept between searches: when the next search starts a few plies further
+/// down the same game, [`Killers::shift`] lines them up with the new root.
+#[derive(Clone)]
+pub struct Killers([[Option<Move>; 2]; MAX_PLY]);
+
+impl Killers {
+    pub fn new() -> Killers {
+        Killers([[None; 2]; MAX_PLY])
+    }
+
+    pub fn get(&self, ply: usize) -> [Option<Move>; 2] {
+        self.0[ply]
+    }
+
+    /// Records a quiet move that caused a cutoff at `ply`.
+    pub fn add(&mut self, ply: usize, mv: Move) {
+        let killers = &mut self.0[ply];
+
+        if killers[0] != Some(mv) {
+            *killers = [Some(mv), killers[0]];
+        }
+    }
+
+    /// Forgets the killers at `ply`, which may be beyond the deepest ply.
+    pub fn clear_ply(&mut self, ply: usize) {
+        if let Some(killers) = self.0.get_mut(ply) {
+            *killers = [None; 2];
+        }
+    }
+
+    /// Moves the killers `plies` plies closer to the root, for a root that many plies further
+    /// down the line.
+    pub fn shift(&mut self, plies: usize) {
+        let plies = plies.min(MAX_PLY);
+
+        self.0.copy_within(plies.., 0);
+        self.0[MAX_PLY - plies..].fill([None; 2]);
+    }
+
+    pub fn clear(&mut self) {
+        *self = Killers::new();
+    }
+}
+
+impl Default for Killers {
+    fn default() -> Killers {
+        Killers::new()
+    }
+}
+
+/// Moves `entry` towards `bonus`, by less the closer it already is to the bound.
+fn gravity(entry: &mut i16, bonus: i32) {
+    let bonus = bonus.clamp(-MAX_HISTORY, MAX_HISTORY);
+    let value = *entry as i32;
+
+    *entry = (value + bonus - value * bonus.abs() / MAX_HISTORY) as i16;
+}
+
+fn piece_index(colour: Colour, mv: Move) -> usize {
+    colour.index() * 6 + mv.piece().index()
+}
+
+fn piece_to_index((piece, to): PieceTo) -> usize {
+    (piece.colour.index() * 6 + piece.kind.index()) * 64 + to.index()
+}
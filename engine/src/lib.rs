@@ -0,0 +1,629 @@
+//! An embeddable chess engine built on [`gambit`].
+//!
+//! [`Engine`] owns a position and a transposition table and searches in the calling thread,
+//! so applications can analyse positions without spawning a subprocess and talking UCI to it.
+//!
+//! ```
+//! use gambit_engine::{Engine, EngineOptions, SearchLimits};
+//!
+//! let mut engine = Engine::new(EngineOptions::default());
+//! engine.set_position(gambit::STARTING_POSITION_FEN, ["e2e4", "e7e5"]).unwrap();
+//!
+//! let result = engine.search(SearchLimits {
+//!     depth: Some(4),
+//!     ..SearchLimits::default()
+//! });
+//!
+//! assert!(result.best_move.is_some());
+//! ```
+
+pub mod affinity;
+pub mod analysis;
+pub mod annotate;
+pub mod batch;
+pub mod correction;
+pub mod datagen;
+pub mod eval;
+pub mod handicap;
+mod handle;
+pub mod history;
+pub mod material;
+pub mod meta;
+pub mod movepick;
+pub mod openings;
+pub mod pages;
+pub mod puzzles;
+pub mod pv;
+pub mod repertoire;
+pub mod root;
+pub mod score;
+pub mod search;
+pub mod see;
+pub mod selfplay;
+pub mod thread;
+pub mod tt;
+pub mod uci;
+pub mod weights;
+
+pub use gambit;
+pub use handle::SearchHandle;
+pub use pv::Pv;
+pub use score::Score;
+
+use std::fmt;
+use std::iter;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use gambit::movegen;
+use gambit::{Board, FenError, Move};
+
+use crate::movepick::MovePicker;
+use crate::search::Search;
+use crate::thread::ThreadData;
+use crate::tt::TranspositionTable;
+use crate::weights::Weights;
+
+/// Settings an [`Engine`] is created with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EngineOptions {
+    /// The size of the transposition table in megabytes.
+    pub hash: usize,
+    /// Whether the search copies the board for every move it makes, rather than making and
+    /// unmaking moves on a single board.
+    pub copy_make: bool,
+    /// Whether searches report [`SearchProgress::Stats`] diagnostics.
+    pub debug: bool,
+    /// How far in centipawns the cheap stage of the evaluation must be outside the window for
+    /// quiescence search to skip the positional terms.
+    pub lazy_eval_margin: i32,
+    /// Whether threads that search several positions at once, as batch analysis does, are
+    /// pinned to processors spread over the NUMA nodes.
+    pub numa: bool,
+    /// Whether the transposition table asks for 2 MB pages, which cut the cost of TLB misses
+    /// on large tables. Where huge pages are unavailable the table uses ordinary pages.
+    pub large_pages: bool,
+    /// The weights the evaluation scores positions with.
+    pub weights: Arc<Weights>,
+    /// How many nodes are searched between checks of the clock and the stop flag, where 0
+    /// checks at every node like 1. Smaller intervals stop sooner at the cost of looking more
+    /// often.
+    pub check_interval: u64,
+}
+
+impl Default for EngineOptions {
+    fn default() -> EngineOptions {
+        EngineOptions {
+            hash: 16,
+            copy_make: true,
+            debug: false,
+            lazy_eval_margin: 600,
+            numa: false,
+            large_pages: false,
+            weights: Arc::new(Weights::standard().clone()),
+            check_interval: 2048,
+        }
+    }
+}
+
+/// Limits on a single search. The search stops as soon as any of them is reached; with none
+/// set it only stops at the maximum depth.
+///
+/// The time limits never interrupt the first iteration, which is quick and guarantees a
+/// searched move to play.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: Option<u8>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<Duration>,
+    /// The time after which the search stops early if the move looks easy: it is the only
+    /// legal move, recaptures the piece just taken, or has stayed best for several
+    /// iterations while no other move comes close.
+    pub easy_movetime: Option<Duration>,
+    /// The time after which the search stops between iterations once the best move has held
+    /// for several iterations without its score falling.
+    pub stable_movetime: Option<Duration>,
+    /// The moves the root may play, as UCI's `searchmoves` gives them. When empty, or when
+    /// none of them is legal, every legal move is searched.
+    pub search_moves: Vec<Move>,
+}
+
+/// The outcome of a search, taken from the deepest completed iteration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchResult {
+    /// The move to play, or `None` if the position has no legal moves.
+    pub best_move: Option<Move>,
+    /// The score from the point of view of the side to move.
+    pub score: Score,
+    /// The principal variation, starting with the best move.
+    pub pv: Pv,
+    pub depth: u8,
+    pub nodes: u64,
+}
+
+/// Counters kept over a whole search, for tuning and debugging rather than for play.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// How many times the transposition table was probed.
+    pub tt_probes: u64,
+    /// How many probes found an entry for the position.
+    pub tt_hits: u64,
+    /// How many probes ended the search of their node with the stored score.
+    pub tt_cutoffs: u64,
+    /// How many nodes razoring dropped into quiescence and returned from.
+    pub razored: u64,
+    /// How many quiet moves were skipped by futility pruning.
+    pub futility_pruned: u64,
+    /// How many moves were skipped for losing material by static exchange evaluation.
+    pub see_pruned: u64,
+    /// How many evaluations stopped before the positional terms.
+    pub lazy_evals: u64,
+    /// How many moves after the first at a PV node were searched with a zero window.
+    pub scouts: u64,
+    /// How many of those scout searches failed high and were searched again with the full
+    /// window.
+    pub researches: u64,
+    /// How full the transposition table is, in permille.
+    pub hashfull: u32,
+}
+
+/// An event reported while a search is running, for streaming analysis to a user interface.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SearchProgress {
+    /// An iteration of the given depth finished; `pv` is the principal variation it settled on.
+    DepthCompleted {
+        depth: u8,
+        score: Score,
+        pv: Pv,
+        nodes: u64,
+        elapsed: Duration,
+        /// How full the transposition table is, in permille.
+        hashfull: u32,
+    },
+    /// A new best line was found at the root during the iteration of the given depth.
+    PvChanged { depth: u8, score: Score, pv: Pv },
+    /// The iteration of the given depth started on `mv`, the root move searched `number`th,
+    /// counting from one. Only reported once the search has run for [`search::CURRMOVE_DELAY`],
+    /// since the moves of short searches go by too quickly to show.
+    CurrMove { depth: u8, mv: Move, number: usize },
+    /// Diagnostics for the iteration of the given depth, only reported when
+    /// [`EngineOptions::debug`] is set.
+    Stats { depth: u8, stats: SearchStats },
+    /// Another [`search::NODE_MILESTONE`] nodes have been searched.
+    Nodes {
+        nodes: u64,
+        elapsed: Duration,
+        hashfull: u32,
+    },
+    /// The search panicked with `message` and was abandoned. Its result is the last line it
+    /// reported, or any legal move, and the engine forgets what it learned in case the tables
+    /// were left inconsistent.
+    Crashed { message: String },
+}
+
+/// The error returned when a position cannot be set up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PositionError {
+    Fen(FenError),
+    /// A move in UCI notation that is not legal in the position it is played from.
+    IllegalMove(String),
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PositionError::Fen(error) => write!(f, "invalid FEN: {error}"),
+            PositionError::IllegalMove(mv) => write!(f, "illegal move: {mv}"),
+        }
+    }
+}
+
+impl std::error::Error for PositionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PositionError::Fen(error) => Some(error),
+            PositionError::IllegalMove(_) => None,
+        }
+    }
+}
+
+/// The memory for a transposition table could not be allocated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllocationError {
+    pub megabytes: usize,
+}
+
+impl fmt::Display for AllocationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot allocate {} MB for the hash table",
+            self.megabytes
+        )
+    }
+}
+
+impl std::error::Error for AllocationError {}
+
+impl From<FenError> for PositionError {
+    fn from(error: FenError) -> PositionError {
+        PositionError::Fen(error)
+    }
+}
+
+pub struct Engine {
+    options: EngineOptions,
+    board: Board,
+    /// The keys of the positions played before `board`, oldest first.
+    history: Vec<u64>,
+    /// The move that reached `board`, if it was played rather than set up.
+    last_move: Option<Move>,
+    tt: Arc<Mutex<TranspositionTable>>,
+    thread: Arc<Mutex<ThreadData>>,
+    stop: Arc<AtomicBool>,
+    /// Raised while a search ponders, holding its time limits back.
+    ponder: Arc<AtomicBool>,
+}
+
+impl Engine {
+    /// Creates an engine set up at the starting position.
+    pub fn new(options: EngineOptions) -> Engine {
+        Engine {
+            tt: Arc::new(Mutex::new(TranspositionTable::new(
+                options.hash,
+                options.large_pages,
+            ))),
+            thread: Arc::new(Mutex::new(thread_data(&options.weights))),
+            options,
+            board: Board::default(),
+            history: Vec::new(),
+            last_move: None,
+            stop: Arc::new(AtomicBool::new(false)),
+            ponder: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn options(&self) -> &EngineOptions {
+        &self.options
+    }
+
+    /// Returns the position that will be searched.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Sets up the position reached by playing `moves`, in UCI notation, from `fen`.
+    ///
+    /// On error the previous position is kept.
+    pub fn set_position<I>(&mut self, fen: &str, moves: I) -> Result<(), PositionError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut board = Board::from_fen(fen)?;
+        let mut history = Vec::new();
+        let mut last_move = None;
+
+        for uci in moves {
+            let uci = uci.as_ref();
+            let mv = board
+                .parse_uci(uci)
+                .ok_or_else(|| PositionError::IllegalMove(uci.to_owned()))?;
+
+            history.push(board.key());
+            board.make_move(mv);
+            last_move = Some(mv);
+        }
+
+        self.board = board;
+        self.history = history;
+        self.last_move = last_move;
+
+        Ok(())
+    }
+
+    /// Sets up `board` with no moves played before it, as after editing a position by hand.
+    pub fn set_board(&mut self, board: Board) {
+        self.board = board;
+        self.history.clear();
+        self.last_move = None;
+    }
+
+    /// Turns [`SearchProgress::Stats`] diagnostics on or off for later searches.
+    pub fn set_debug(&mut self, debug: bool) {
+        self.options.debug = debug;
+    }
+
+    /// Sets the margin for lazy evaluation, see [`EngineOptions::lazy_eval_margin`].
+    pub fn set_lazy_eval_margin(&mut self, margin: i32) {
+        self.options.lazy_eval_margin = margin;
+    }
+
+    /// Evaluates with `weights` in later searches.
+    pub fn set_weights(&mut self, weights: Weights) {
+        self.options.weights = Arc::new(weights);
+        lock(&self.thread)
+            .eval_cache
+            .set_weights(Arc::clone(&self.options.weights));
+    }
+
+    /// Replaces the transposition table with an empty one of `megabytes` megabytes.
+    ///
+    /// If the memory cannot be allocated the current table is kept.
+    pub fn set_hash(&mut self, megabytes: usize) -> Result<(), AllocationError> {
+        self.replace_tt(megabytes, self.options.large_pages)
+    }
+
+    /// Replaces the transposition table with an empty one of the same size, asking for huge
+    /// pages if `large_pages` is set.
+    ///
+    /// If the memory cannot be allocated the current table is kept.
+    pub fn set_large_pages(&mut self, large_pages: bool) -> Result<(), AllocationError> {
+        self.replace_tt(self.options.hash, large_pages)
+    }
+
+    fn replace_tt(&mut self, megabytes: usize, large_pages: bool) -> Result<(), AllocationError> {
+        let tt = TranspositionTable::try_new(megabytes, large_pages)
+            .ok_or(AllocationError { megabytes })?;
+
+        *lock(&self.tt) = tt;
+        self.options.hash = megabytes;
+        self.options.large_pages = large_pages;
+
+        Ok(())
+    }
+
+    /// Empties the transposition table, keeping its size. Unlike [`Engine::new_game`], what
+    /// else was learned from earlier searches is kept.
+    pub fn clear_hash(&mut self) {
+        lock(&self.tt).clear();
+    }
+
+    /// Returns how full the transposition table is, in permille.
+    pub fn hashfull(&self) -> u32 {
+        lock(&self.tt).hashfull()
+    }
+
+    /// Returns whether the transposition table is backed by huge pages.
+    pub fn uses_large_pages(&self) -> bool {
+        lock(&self.tt).large_pages()
+    }
+
+    /// Returns the legal moves of the current position in the order a search would first try
+    /// them, with their ordering scores, using what was learned from earlier searches.
+    pub fn ordered_moves(&self) -> Vec<(Move, i32)> {
+        let tt_move = lock(&self.tt)
+            .probe(self.board.key())
+            .and_then(|entry| entry.mv.unpack(&self.board));
+        let thread = lock(&self.thread);
+        let mut picker = MovePicker::new(
+            &self.board,
+            movegen::legal_moves(&self.board),
+            tt_move,
+            thread.killers.get(0),
+            &thread.history,
+            None,
+        );
+
+        iter::from_fn(|| picker.next_scored()).collect()
+    }
+
+    /// Prepares for an unrelated game by forgetting everything learned from earlier searches.
+    pub fn new_game(&mut self) {
+        lock(&self.tt).clear();
+        lock(&self.thread).clear();
+    }
+
+    /// Searches the current position in the calling thread until a limit is reached.
+    pub fn search(&mut self, limits: SearchLimits) -> SearchResult {
+        self.search_with_progress(limits, |_| {})
+    }
+
+    /// Searches like [`Engine::search`], calling `progress` from the search as it goes.
+    pub fn search_with_progress(
+        &mut self,
+        limits: SearchLimits,
+        mut progress: impl FnMut(SearchProgress),
+    ) -> SearchResult {
+        self.stop.store(false, Ordering::Relaxed);
+        self.ponder.store(false, Ordering::Relaxed);
+        self.run(limits, &mut progress)
+    }
+
+    /// Searches without clearing the stop and ponder flags first, for callers that manage them
+    /// themselves.
+    pub(crate) fn run(
+        &mut self,
+        limits: SearchLimits,
+        progress: &mut dyn FnMut(SearchProgress),
+    ) -> SearchResult {
+        contain_panics(
+            &self.board,
+            progress,
+            |progress| {
+                Search::new(
+                    &mut lock(&self.tt),
+                    &mut lock(&self.thread),
+                    &self.options,
+                    &limits,
+                    &self.stop,
+                    self.history.clone(),
+                    progress,
+                )
+                .with_last_move(self.last_move)
+                .with_ponder(&self.ponder)
+                .run(&self.board)
+            },
+            || reset(&self.tt, &self.thread, &self.options.weights),
+        )
+    }
+
+    /// Searches like [`Engine::run`] from the position reached by playing `moves`, which must
+    /// be legal, leaving the current position set up.
+    pub(crate) fn run_after(
+        &mut self,
+        moves: &[Move],
+        limits: SearchLimits,
+        progress: &mut dyn FnMut(SearchProgress),
+    ) -> SearchResult {
+        let board = self.board.clone();
+        let history_len = self.history.len();
+        let last_move = self.last_move;
+
+        for &mv in moves {
+            self.history.push(self.board.key());
+            self.board.make_move(mv);
+            self.last_move = Some(mv);
+        }
+
+        let result = self.run(limits, progress);
+
+        self.board = board;
+        self.history.truncate(history_len);
+        self.last_move = last_move;
+
+        result
+    }
+
+    pub(crate) fn stop_flag(&self) -> &Arc<AtomicBool> {
+        &self.stop
+    }
+
+    pub(crate) fn ponder_flag(&self) -> &Arc<AtomicBool> {
+        &self.ponder
+    }
+
+    /// Starts searching the current position on a background thread.
+    ///
+    /// The engine stays borrowed until the returned handle is consumed or dropped, and dropping
+    /// the handle aborts the search.
+    pub fn start_search(&mut self, limits: SearchLimits) -> SearchHandle<'_> {
+        self.start_search_with_progress(limits, |_| {})
+    }
+
+    /// Starts a background search like [`Engine::start_search`], calling `progress` from the
+    /// search thread as it goes.
+    pub fn start_search_with_progress(
+        &mut self,
+        limits: SearchLimits,
+        mut progress: impl FnMut(SearchProgress) + Send + 'static,
+    ) -> SearchHandle<'_> {
+        self.stop.store(false, Ordering::Relaxed);
+
+        let (sender, receiver) = mpsc::channel();
+        let tt = Arc::clone(&self.tt);
+        let data = Arc::clone(&self.thread);
+        let stop = Arc::clone(&self.stop);
+        let board = self.board.clone();
+        let history = self.history.clone();
+        let last_move = self.last_move;
+        let options = self.options.clone();
+
+        let thread = std::thread::spawn(move || {
+            let result = contain_panics(
+                &board,
+                &mut progress,
+                |progress| {
+                    Search::new(
+                        &mut lock(&tt),
+                        &mut lock(&data),
+                        &options,
+                        &limits,
+                        &stop,
+                        history,
+                        progress,
+                    )
+                    .with_last_move(last_move)
+                    .run(&board)
+                },
+                || reset(&tt, &data, &options.weights),
+            );
+
+            // The handle may already have given up on the result.
+            let _ = sender.send(result);
+        });
+
+        SearchHandle::new(Arc::clone(&self.stop), receiver, thread)
+    }
+}
+
+/// Runs `search` on `board`, containing any panic in it so that an engine bug costs a bad move
+/// rather than the game.
+///
+/// After a panic, [`SearchProgress::Crashed`] is reported, `recover` is called, and the result
+/// is the last line the search reported, or failing that the first legal move.
+fn contain_panics(
+    board: &Board,
+    progress: &mut dyn FnMut(SearchProgress),
+    search: impl FnOnce(&mut dyn FnMut(SearchProgress)) -> SearchResult,
+    recover: impl FnOnce(),
+) -> SearchResult {
+    let mut fallback = SearchResult {
+        best_move: movegen::legal_moves(board).first().copied(),
+        score: Score::DRAW,
+        pv: Pv::new(),
+        depth: 0,
+        nodes: 0,
+    };
+
+    let searched = panic::catch_unwind(AssertUnwindSafe(|| {
+        search(&mut |event: SearchProgress| {
+            if let SearchProgress::DepthCompleted {
+                depth, score, pv, ..
+            }
+            | SearchProgress::PvChanged { depth, score, pv } = &event
+            {
+                if let Some(&mv) = pv.first() {
+                    fallback.best_move = Some(mv);
+                    fallback.score = *score;
+                    fallback.pv.clone_from(pv);
+                    fallback.depth = *depth;
+                }
+            }
+
+            progress(event);
+        })
+    }));
+
+    searched.unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_owned());
+
+        progress(SearchProgress::Crashed { message });
+        recover();
+
+        fallback
+    })
+}
+
+/// Forgets everything the search learned, after a panic that may have left it half-updated.
+fn reset(tt: &Mutex<TranspositionTable>, thread: &Mutex<ThreadData>, weights: &Arc<Weights>) {
+    lock(tt).clear();
+    *lock(thread) = thread_data(weights);
+}
+
+fn thread_data(weights: &Arc<Weights>) -> ThreadData {
+    let mut thread = ThreadData::default();
+    thread.eval_cache.set_weights(Arc::clone(weights));
+
+    thread
+}
+
+/// Locks state shared with search threads. A search that panicked leaves the tables usable,
+/// since every entry is checked against its key before use.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl Default for Engine {
+    fn default() -> Engine {
+        Engine::new(EngineOptions::default())
+    }
+}
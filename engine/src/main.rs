@@ -0,0 +1,653 @@
+//! The UCI engine binary.
+//!
+//! Standard input is read on its own thread so that `stop` and `quit` can interrupt a search
+//! that is running on the main thread.
+//!
+//! `gambit repertoire <games.pgn> <white|black> [statistics file]` instead trains the moves
+//! of an opening repertoire, `gambit puzzles <games.pgn>...` prints the puzzles found in
+//! games as EPD, `gambit batch <positions>` analyses a file of FEN or EPD positions,
+//! printing the results as CSV or JSON, `gambit analyze --fen <fen>` analyses a single
+//! position, printing each completed depth to stderr and the final lines to stdout, and
+//! `gambit annotate <games.pgn> <movetime>` writes games back out with an evaluation after
+//! every move and the inaccuracies, mistakes and blunders marked. `gambit match` plays two
+//! engine configurations against each other in pairs of games from the openings of an EPD or
+//! PGN suite, reporting the Elo difference and an SPRT. `gambit datagen <output>` writes
+//! positions from self-play as training data, which `gambit datagen --decode <file>` prints.
+
+use std::backtrace::Backtrace;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+use std::{env, fs, panic, process, thread};
+
+use gambit::pgn;
+use gambit::Colour;
+use gambit_engine::analysis;
+use gambit_engine::annotate::{self, Thresholds};
+use gambit_engine::batch::{self, Format};
+use gambit_engine::datagen::{self, DatagenSettings};
+use gambit_engine::handicap::Rng;
+use gambit_engine::openings::{self, Selection};
+use gambit_engine::repertoire::{self, Repertoire, Statistics};
+use gambit_engine::selfplay::{self, MatchSettings, Player, Sprt, TimeControl};
+use gambit_engine::uci::UciSession;
+use gambit_engine::weights;
+use gambit_engine::{puzzles, Engine, EngineOptions, SearchLimits};
+
+/// The depth analysis searches to when no limit is given.
+const DEFAULT_ANALYSIS_DEPTH: u8 = 10;
+
+/// The nodes each move of training data generation is searched to when no limit is given.
+const DEFAULT_DATAGEN_NODES: u64 = 5000;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("repertoire") => train_repertoire(&args[1..]),
+        Some("puzzles") => extract_puzzles(&args[1..]),
+        Some("batch") => analyse_batch(&args[1..]),
+        Some("analyze") => analyse_position(&args[1..]),
+        Some("annotate") => annotate_games(&args[1..]),
+        Some("match") => play_match(&args[1..]),
+        Some("datagen") => generate_data(&args[1..]),
+        _ => {
+            uci();
+            Ok(())
+        }
+    };
+
+    if let Err(error) = result {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
+
+fn train_repertoire(args: &[String]) -> Result<(), String> {
+    let usage = "usage: gambit repertoire <games.pgn> <white|black> [statistics file]";
+
+    let (path, colour) = match args {
+        [path, colour, ..] => (path, colour),
+        _ => return Err(usage.to_owned()),
+    };
+    let colour = match colour.as_str() {
+        "white" => Colour::White,
+        "black" => Colour::Black,
+        _ => return Err(usage.to_owned()),
+    };
+    let statistics_path = args.get(2).cloned().unwrap_or(format!("{path}.stats"));
+
+    let text = fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+    let games = pgn::parse_games(&text).map_err(|error| format!("{path}: {error}"))?;
+    let repertoire = Repertoire::new(&games);
+
+    // A missing file just means nothing has been trained yet.
+    let mut statistics =
+        Statistics::parse(&fs::read_to_string(&statistics_path).unwrap_or_default());
+
+    let trained = repertoire::train(
+        &repertoire,
+        &mut statistics,
+        colour,
+        io::stdin().lock(),
+        io::stdout(),
+    );
+
+    fs::write(&statistics_path, statistics.to_string())
+        .map_err(|error| format!("{statistics_path}: {error}"))?;
+
+    trained.map_err(|error| error.to_string())
+}
+
+fn extract_puzzles(paths: &[String]) -> Result<(), String> {
+    if paths.is_empty() {
+        return Err("usage: gambit puzzles <games.pgn>...".to_owned());
+    }
+
+    let mut engine = Engine::default();
+    let mut stdout = io::stdout();
+
+    for path in paths {
+        let text = fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+        let games = pgn::parse_games(&text).map_err(|error| format!("{path}: {error}"))?;
+
+        for game in &games {
+            engine.new_game();
+
+            for puzzle in puzzles::find_puzzles(&mut engine, game) {
+                writeln!(stdout, "{puzzle}").map_err(|error| error.to_string())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn analyse_batch(args: &[String]) -> Result<(), String> {
+    let usage = "usage: gambit batch <positions> [--depth N] [--nodes N] [--movetime MS] \
+                 [--threads N] [--hash MB] [--weights FILE] [--numa] [--large-pages] \
+                 [--csv | --json]";
+
+    let Some((path, mut flags)) = args.split_first() else {
+        return Err(usage.to_owned());
+    };
+
+    let mut limits = SearchLimits::default();
+    let mut options = EngineOptions::default();
+    let mut threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let mut format = Format::Csv;
+
+    while let Some((flag, rest)) = flags.split_first() {
+        flags = rest;
+
+        match flag.as_str() {
+            "--csv" => format = Format::Csv,
+            "--json" => format = Format::Json,
+            "--numa" => options.numa = true,
+            "--large-pages" => options.large_pages = true,
+            _ => {
+                let Some((value, rest)) = flags.split_first() else {
+                    return Err(usage.to_owned());
+                };
+                flags = rest;
+
+                let invalid = |_| format!("invalid value for {flag}: {value}");
+
+                match flag.as_str() {
+                    "--depth" => limits.depth = Some(value.parse().map_err(invalid)?),
+                    "--nodes" => limits.nodes = Some(value.parse().map_err(invalid)?),
+                    "--movetime" => {
+                        limits.movetime =
+                            Some(Duration::from_millis(value.parse().map_err(invalid)?))
+                    }
+                    "--threads" => threads = value.parse().map_err(invalid)?,
+                    "--weights" => {
+                        options.weights = Arc::new(
+                            weights::load(value).map_err(|error| format!("{value}: {error}"))?,
+                        )
+                    }
+                    "--hash" => options.hash = value.parse().map_err(invalid)?,
+                    _ => return Err(usage.to_owned()),
+                }
+            }
+        }
+    }
+
+    if limits == SearchLimits::default() {
+        limits.depth = Some(DEFAULT_ANALYSIS_DEPTH);
+    }
+
+    let text = fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+    let positions = batch::read_positions(&text).map_err(|error| format!("{path}: {error}"))?;
+    let results = batch::analyse(&positions, &limits, &options, threads);
+
+    batch::write_results(io::stdout().lock(), format, &positions, &results)
+        .map_err(|error| error.to_string())
+}
+
+fn analyse_position(args: &[String]) -> Result<(), String> {
+    let usage = "usage: gambit analyze [--fen FEN] [--moves MOVES] [--depth N] [--nodes N] \
+                 [--movetime MS] [--multipv N] [--hash MB] [--weights FILE]";
+
+    let mut fen = gambit::STARTING_POSITION_FEN.to_owned();
+    let mut moves = String::new();
+    let mut limits = SearchLimits::default();
+    let mut options = EngineOptions::default();
+    let mut lines = 1;
+    let mut flags = args;
+
+    while let Some(([flag, value], rest)) = flags.split_first_chunk() {
+        flags = rest;
+
+        let invalid = |_| format!("invalid value for {flag}: {value}");
+
+        match flag.as_str() {
+            "--fen" => fen.clone_from(value),
+            "--moves" => moves.clone_from(value),
+            "--depth" => limits.depth = Some(value.parse().map_err(invalid)?),
+            "--nodes" => limits.nodes = Some(value.parse().map_err(invalid)?),
+            "--movetime" => {
+                limits.movetime = Some(Duration::from_millis(value.parse().map_err(invalid)?))
+            }
+            "--multipv" => lines = value.parse().map_err(invalid)?,
+            "--weights" => {
+                options.weights =
+                    Arc::new(weights::load(value).map_err(|error| format!("{value}: {error}"))?)
+            }
+            "--hash" => options.hash = value.parse().map_err(invalid)?,
+            _ => return Err(usage.to_owned()),
+        }
+    }
+
+    if !flags.is_empty() {
+        return Err(usage.to_owned());
+    }
+
+    if limits == SearchLimits::default() {
+        limits.depth = Some(DEFAULT_ANALYSIS_DEPTH);
+    }
+
+    let mut engine = Engine::new(options);
+    engine
+        .set_position(&fen, moves.split_whitespace())
+        .map_err(|error| error.to_string())?;
+
+    let board = engine.board().clone();
+    let results = analysis::analyse(&mut engine, &limits, lines, |line, progress| {
+        if let Some(text) = analysis::format_progress(&board, line, &progress) {
+            eprintln!("{text}");
+        }
+    });
+
+    let mut stdout = io::stdout().lock();
+
+    for (index, result) in results.iter().enumerate() {
+        writeln!(
+            stdout,
+            "{}",
+            analysis::format_line(&board, index + 1, result)
+        )
+        .map_err(|error| error.to_string())?;
+    }
+
+    Ok(())
+}
+
+fn annotate_games(args: &[String]) -> Result<(), String> {
+    let usage = "usage: gambit annotate <games.pgn> <movetime> [--inaccuracy CP] \
+                 [--mistake CP] [--blunder CP] [--hash MB] [--weights FILE]";
+
+    let [path, movetime, flags @ ..] = args else {
+        return Err(usage.to_owned());
+    };
+    let mut flags = flags;
+    let movetime = movetime
+        .parse()
+        .map_err(|_| format!("invalid movetime: {movetime}"))?;
+    let limits = SearchLimits {
+        movetime: Some(Duration::from_millis(movetime)),
+        ..SearchLimits::default()
+    };
+
+    let mut thresholds = Thresholds::default();
+    let mut options = EngineOptions::default();
+
+    while let Some(([flag, value], rest)) = flags.split_first_chunk() {
+        flags = rest;
+
+        let invalid = |_| format!("invalid value for {flag}: {value}");
+
+        match flag.as_str() {
+            "--inaccuracy" => thresholds.inaccuracy = value.parse().map_err(invalid)?,
+            "--mistake" => thresholds.mistake = value.parse().map_err(invalid)?,
+            "--blunder" => thresholds.blunder = value.parse().map_err(invalid)?,
+            "--weights" => {
+                options.weights =
+                    Arc::new(weights::load(value).map_err(|error| format!("{value}: {error}"))?)
+            }
+            "--hash" => options.hash = value.parse().map_err(invalid)?,
+            _ => return Err(usage.to_owned()),
+        }
+    }
+
+    if !flags.is_empty() {
+        return Err(usage.to_owned());
+    }
+
+    let text = fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+    let games = pgn::parse_games(&text).map_err(|error| format!("{path}: {error}"))?;
+
+    let mut engine = Engine::new(options);
+    let mut stdout = io::stdout();
+
+    for (index, game) in games.iter().enumerate() {
+        if index > 0 {
+            writeln!(stdout).map_err(|error| error.to_string())?;
+        }
+
+        let annotated = annotate::annotate(&mut engine, game, &limits, &thresholds);
+        write!(stdout, "{annotated}")
+            .and_then(|()| stdout.flush())
+            .map_err(|error| error.to_string())?;
+    }
+
+    Ok(())
+}
+
+fn play_match(args: &[String]) -> Result<(), String> {
+    let usage = "usage: gambit match --first SPEC --second SPEC [--games N] \
+                 [--tc SECONDS+INCREMENT | --depth N | --nodes N | --movetime MS] \
+                 [--openings FILE] [--order sequential|random] [--seed N] \
+                 [--concurrency N] [--pgn FILE] [--sprt ELO0,ELO1]\n\
+                 where SPEC is name=NAME[,weights=FILE][,hash=MB][,lazy=CP]";
+
+    let mut players = [None, None];
+    let mut limits = SearchLimits::default();
+    let mut time_control = None;
+    let mut settings = MatchSettings {
+        games: 100,
+        time_control: TimeControl::PerMove(SearchLimits::default()),
+        adjudication: Default::default(),
+        concurrency: 1,
+        sprt: None,
+    };
+    let mut openings = Vec::new();
+    let mut random = false;
+    let mut seed = None;
+    let mut pgn_path = None;
+    let mut flags = args;
+
+    while let Some(([flag, value], rest)) = flags.split_first_chunk() {
+        flags = rest;
+
+        let invalid = || format!("invalid value for {flag}: {value}");
+
+        match flag.as_str() {
+            "--first" => players[0] = Some(parse_player(value)?),
+            "--second" => players[1] = Some(parse_player(value)?),
+            "--games" => settings.games = value.parse().map_err(|_| invalid())?,
+            "--concurrency" => settings.concurrency = value.parse().map_err(|_| invalid())?,
+            "--depth" => limits.depth = Some(value.parse().map_err(|_| invalid())?),
+            "--nodes" => limits.nodes = Some(value.parse().map_err(|_| invalid())?),
+            "--movetime" => {
+                limits.movetime = Some(Duration::from_millis(value.parse().map_err(|_| invalid())?))
+            }
+            "--tc" => {
+                let (base, increment) = value.split_once('+').unwrap_or((value, "0"));
+                let seconds = |text: &str| {
+                    text.parse()
+                        .ok()
+                        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                        .ok_or_else(invalid)
+                };
+
+                time_control = Some(TimeControl::Clock {
+                    base: seconds(base)?,
+                    increment: seconds(increment)?,
+                });
+            }
+            "--openings" => {
+                let text =
+                    fs::read_to_string(value).map_err(|error| format!("{value}: {error}"))?;
+                openings = openings::read_openings(Path::new(value), &text)
+                    .map_err(|error| format!("{value}: {error}"))?;
+            }
+            "--order" => {
+                random = match value.as_str() {
+                    "sequential" => false,
+                    "random" => true,
+                    _ => return Err(invalid()),
+                }
+            }
+            "--seed" => seed = Some(value.parse().map_err(|_| invalid())?),
+            "--pgn" => pgn_path = Some(value.clone()),
+            "--sprt" => {
+                let bounds = value
+                    .split_once(',')
+                    .and_then(|(elo0, elo1)| Some((elo0.parse().ok()?, elo1.parse().ok()?)))
+                    .ok_or_else(invalid)?;
+                settings.sprt = Some(Sprt::new(bounds.0, bounds.1));
+            }
+            _ => return Err(usage.to_owned()),
+        }
+    }
+
+    let ([Some(first), Some(second)], []) = (players, flags) else {
+        return Err(usage.to_owned());
+    };
+
+    let selection = match (random, seed) {
+        (false, _) => Selection::Sequential,
+        (true, Some(seed)) => Selection::Random(Rng::new(seed)),
+        (true, None) => Selection::Random(Rng::from_entropy()),
+    };
+    openings::select(&mut openings, selection);
+
+    settings.time_control = match time_control {
+        Some(clock) => clock,
+        None if limits == SearchLimits::default() => TimeControl::PerMove(SearchLimits {
+            depth: Some(DEFAULT_ANALYSIS_DEPTH),
+            ..SearchLimits::default()
+        }),
+        None => TimeControl::PerMove(limits),
+    };
+
+    let mut pgn = match &pgn_path {
+        Some(path) => Some(fs::File::create(path).map_err(|error| format!("{path}: {error}"))?),
+        None => None,
+    };
+    let mut stdout = io::stdout();
+    let mut failed = None;
+
+    let score = selfplay::play_match(
+        [&first, &second],
+        &openings,
+        &settings,
+        |game, record, score| {
+            let (white, black) = match game % 2 {
+                0 => (&first.name, &second.name),
+                _ => (&second.name, &first.name),
+            };
+
+            let _ = writeln!(
+                stdout,
+                "game {}: {white} - {black} {} ({}), score {}-{}-{}",
+                game + 1,
+                record.outcome.to_pgn(),
+                record.termination.describe(),
+                score.wins,
+                score.losses,
+                score.draws
+            );
+
+            if let Some(file) = &mut pgn {
+                let written = writeln!(file, "{}", record.to_pgn(white, black, game + 1));
+                if let (Err(error), Some(path)) = (written, &pgn_path) {
+                    failed.get_or_insert(format!("{path}: {error}"));
+                }
+            }
+        },
+    );
+
+    writeln!(
+        stdout,
+        "{} vs {}: {} games, {} wins, {} losses, {} draws",
+        first.name,
+        second.name,
+        score.games(),
+        score.wins,
+        score.losses,
+        score.draws
+    )
+    .map_err(|error| error.to_string())?;
+
+    if let Some((elo, margin)) = score.elo() {
+        writeln!(stdout, "elo {elo:+.1} +/- {margin:.1}").map_err(|error| error.to_string())?;
+    }
+
+    if let Some(sprt) = settings.sprt {
+        let (lower, upper) = sprt.bounds();
+        let verdict = match sprt.decide(score) {
+            Some(true) => "H1 accepted",
+            Some(false) => "H0 accepted",
+            None => "undecided",
+        };
+
+        writeln!(
+            stdout,
+            "sprt [{}, {}] llr {:.2} ({lower:.2}, {upper:.2}), {verdict}",
+            sprt.elo0,
+            sprt.elo1,
+            score.llr(sprt.elo0, sprt.elo1)
+        )
+        .map_err(|error| error.to_string())?;
+    }
+
+    failed.map_or(Ok(()), Err)
+}
+
+fn generate_data(args: &[String]) -> Result<(), String> {
+    let usage = "usage: gambit datagen <output> [--games N] [--nodes N | --depth N] \
+                 [--random-plies N] [--threads N] [--seed N] [--hash MB] [--weights FILE]\n       \
+                 gambit datagen --decode <file>";
+
+    if let [flag, path] = args {
+        if flag == "--decode" {
+            return decode_data(path);
+        }
+    }
+
+    let Some((path, mut flags)) = args.split_first() else {
+        return Err(usage.to_owned());
+    };
+
+    let mut options = EngineOptions {
+        hash: 16,
+        ..EngineOptions::default()
+    };
+    let mut settings = DatagenSettings {
+        games: 1000,
+        limits: SearchLimits::default(),
+        random_plies: 8,
+        threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+        seed: Rng::from_entropy().next_u64(),
+    };
+
+    while let Some(([flag, value], rest)) = flags.split_first_chunk() {
+        flags = rest;
+
+        let invalid = |_| format!("invalid value for {flag}: {value}");
+
+        match flag.as_str() {
+            "--games" => settings.games = value.parse().map_err(invalid)?,
+            "--nodes" => settings.limits.nodes = Some(value.parse().map_err(invalid)?),
+            "--depth" => settings.limits.depth = Some(value.parse().map_err(invalid)?),
+            "--random-plies" => settings.random_plies = value.parse().map_err(invalid)?,
+            "--threads" => settings.threads = value.parse().map_err(invalid)?,
+            "--seed" => settings.seed = value.parse().map_err(invalid)?,
+            "--weights" => {
+                options.weights =
+                    Arc::new(weights::load(value).map_err(|error| format!("{value}: {error}"))?)
+            }
+            "--hash" => options.hash = value.parse().map_err(invalid)?,
+            _ => return Err(usage.to_owned()),
+        }
+    }
+
+    if !flags.is_empty() {
+        return Err(usage.to_owned());
+    }
+
+    if settings.limits == SearchLimits::default() {
+        settings.limits.nodes = Some(DEFAULT_DATAGEN_NODES);
+    }
+
+    let file = fs::File::create(path).map_err(|error| format!("{path}: {error}"))?;
+    let stats = datagen::generate(&options, &settings, io::BufWriter::new(file))
+        .map_err(|error| format!("{path}: {error}"))?;
+
+    println!(
+        "{} games, {} positions written to {path}",
+        stats.games, stats.positions
+    );
+
+    Ok(())
+}
+
+/// Prints the records of a training data file as `<fen> | <score> | <result>`.
+fn decode_data(path: &str) -> Result<(), String> {
+    let bytes = fs::read(path).map_err(|error| format!("{path}: {error}"))?;
+    let mut stdout = io::stdout().lock();
+
+    for (index, record) in datagen::decode_all(&bytes).enumerate() {
+        let record = record.map_err(|error| format!("{path}: record {index}: {error}"))?;
+
+        writeln!(
+            stdout,
+            "{} | {} | {}",
+            record.board.to_fen(),
+            record.score,
+            record.result
+        )
+        .map_err(|error| error.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Parses a player given as `name=NAME,weights=FILE,hash=MB,lazy=CP`, where only the name is
+/// required.
+fn parse_player(spec: &str) -> Result<Player, String> {
+    let mut name = None;
+    let mut options = EngineOptions::default();
+
+    for setting in spec.split(',') {
+        let invalid = || format!("invalid player setting: {setting}");
+        let (key, value) = setting.split_once('=').ok_or_else(invalid)?;
+
+        match key {
+            "name" => name = Some(value.to_owned()),
+            "weights" => {
+                options.weights =
+                    Arc::new(weights::load(value).map_err(|error| format!("{value}: {error}"))?)
+            }
+            "hash" => options.hash = value.parse().map_err(|_| invalid())?,
+            "lazy" => options.lazy_eval_margin = value.parse().map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(Player {
+        name: name.ok_or_else(|| format!("player without a name: {spec}"))?,
+        options,
+    })
+}
+
+fn uci() {
+    // A panicking search is contained and the game goes on, so the backtrace is all that is
+    // left of the bug. GUIs keep what engines write to stderr in their logs.
+    panic::set_hook(Box::new(|info| {
+        eprintln!("{info}\n{}", Backtrace::force_capture());
+    }));
+
+    let mut session = UciSession::new();
+    let mut observer = session.observer();
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        // Lines are split as bytes, so that a line that is not UTF-8 is reported as an unknown
+        // command rather than ending the input.
+        for line in io::stdin().lock().split(b'\n') {
+            let Ok(line) = line else {
+                break;
+            };
+            let line = String::from_utf8_lossy(&line)
+                .trim_end_matches('\r')
+                .to_owned();
+
+            if let Some(reply) = observer.observe(&line) {
+                // Unlike `println!`, a closed stdout does not panic.
+                let _ = writeln!(io::stdout(), "{reply}");
+                continue;
+            }
+
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut stdout = io::stdout();
+
+    for line in receiver {
+        session.handle_with(&line, &mut |output| {
+            // There is nobody left to talk to if stdout is gone.
+            let _ = writeln!(stdout, "{output}").and_then(|()| stdout.flush());
+        });
+
+        if session.quit_requested() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! What the material on the board alone says about a position.
+//!
+//! Positions with the same material signature share their game phase, their material
+//! imbalance and whether a specialised endgame evaluator applies, so these are worked out once
+//! per signature and kept in a small table keyed by [`Board::material_key`].
+
+use gambit::{Board, Colour, PieceKind, Square};
+
+use crate::eval::{self, PIECE_VALUES};
+
+/// The number of entries in the material table, a power of two.
+const MATERIAL_TABLE_SIZE: usize = 1 << 12;
+
+// Second-order material terms after Kaufman, "The Evaluation of Material Imbalances". Knights
+// gain and rooks lose value as pawns are added, the two bishops are worth more as pawns come
+// off and open the board, and a second major piece adds less than the first.
+
+/// The bonus for the two bishops with all eight pawns on the board, in centipawns.
+const BISHOP_PAIR: i32 = 30;
+
+/// What the two bishops gain for each of their side's pawns that is missing.
+const BISHOP_PAIR_PER_MISSING_PAWN: i32 = 3;
+
+/// What a knight gains for each of its side's pawns beyond five, or loses for each short.
+const KNIGHT_PER_PAWN: i32 = 6;
+
+/// What a rook loses for each of its side's pawns beyond five, or gains for each short.
+const ROOK_PER_PAWN: i32 = 12;
+
+/// The penalty for a second rook.
+const ROOK_REDUNDANCY: i32 = 16;
+
+/// The penalty for a rook alongside a queen.
+const QUEEN_ROOK_REDUNDANCY: i32 = 8;
+
+/// Added to the evaluation of an endgame that is won by force, so that the search prefers
+/// reaching one to keeping more material in an unclear position.
+const KNOWN_WIN: i32 = 1000;
+
+/// A specialised evaluation, in centipawns from the point of view of the stronger side.
+pub type EndgameEval = fn(&Board, Colour) -> i32;
+
+/// An endgame whose evaluation replaces the general one.
+#[derive(Clone, Copy)]
+pub struct Endgame {
+    /// The side the evaluation favours.
+    pub strong: Colour,
+    pub evaluate: EndgameEval,
+}
+
+/// What is known about every position with a given material signature.
+#[derive(Clone, Copy)]
+pub struct MaterialEntry {
+    key: u64,
+    /// The game phase, from 24 with every piece on the board down to zero in a pawn endgame.
+    pub phase: i32,
+    /// The value of the combination of pieces beyond the sum of their values, from White's
+    /// point of view.
+    pub imbalance: i32,
+    pub endgame: Option<Endgame>,
+}
+
+/// Works out the material entry of `board` from scratch.
+pub fn analyse(board: &Board) -> MaterialEntry {
+    MaterialEntry {
+        key: board.material_key(),
+        phase: eval::phase(board),
+        imbalance: imbalance(board, Colour::White) - imbalance(board, Colour::Black),
+        endgame: endgame(board),
+    }
+}
+
+/// A direct-mapped cache of material entries, private to a search thread.
+pub struct MaterialTable {
+    entries: Box<[Option<MaterialEntry>]>,
+}
+
+impl MaterialTable {
+    pub fn new() -> MaterialTable {
+        MaterialTable {
+            entries: vec![None; MATERIAL_TABLE_SIZE].into_boxed_slice(),
+        }
+    }
+
+    /// Returns the material entry of `board`, working it out and caching it on a miss.
+    pub fn probe(&mut self, board: &Board) -> MaterialEntry {
+        let key = board.material_key();
+        let slot = &mut self.entries[key as usize & (MATERIAL_TABLE_SIZE - 1)];
+
+        match *slot {
+            Some(entry) if entry.key == key => entry,
+            _ => *slot.insert(analyse(board)),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+}
+
+impl Default for MaterialTable {
+    fn default() -> MaterialTable {
+        MaterialTable::new()
+    }
+}
+
+/// Returns the imbalance terms of the pieces of `colour`, which depend on its own pawns.
+fn imbalance(board: &Board, colour: Colour) -> i32 {
+    let count = |kind| board.pieces_of(colour, kind).count() as i32;
+    let pawns = count(PieceKind::Pawn).min(8);
+    let rooks = count(PieceKind::Rook);
+    let mut score = 0;
+
+    if count(PieceKind::Bishop) >= 2 {
+        score += BISHOP_PAIR + BISHOP_PAIR_PER_MISSING_PAWN * (8 - pawns);
+    }
+
+    score += count(PieceKind::Knight) * KNIGHT_PER_PAWN * (pawns - 5);
+    score -= rooks * ROOK_PER_PAWN * (pawns - 5);
+
+    if rooks >= 2 {
+        score -= ROOK_REDUNDANCY;
+    }
+
+    if rooks >= 1 && count(PieceKind::Queen) >= 1 {
+        score -= QUEEN_ROOK_REDUNDANCY;
+    }
+
+    score
+}
+
+/// Returns the specialised evaluator for the material on `board`, if there is one.
+fn endgame(board: &Board) -> Option<Endgame> {
+    if is_drawn(board) {
+        return Some(Endgame {
+            strong: Colour::White,
+            evaluate: drawn,
+        });
+    }
+
+    Colour::ALL
+        .into_iter()
+        .find(|&colour| {
+            board.colour(!colour) == board.pieces_of(!colour, PieceKind::King)
+                && can_force_mate(board, colour)
+        })
+        .map(|strong| Endgame {
+            strong,
+            evaluate: lone_king,
+        })
+}
+
+/// Whether neither side has the material to mate by force: no pawns, rooks or queens, and at
+/// most a single minor piece each, or two knights against a bare king.
+pub(crate) fn is_drawn(board: &Board) -> bool {
+    let heavy = board.pieces(PieceKind::Pawn)
+        | board.pieces(PieceKind::Rook)
+        | board.pieces(PieceKind::Queen);
+    if !heavy.is_empty() {
+        return false;
+    }
+
+    let minors = |colour| {
+        (board.pieces_of(colour, PieceKind::Knight) | board.pieces_of(colour, PieceKind::Bishop))
+            .count()
+    };
+
+    match (minors(Colour::White), minors(Colour::Black)) {
+        (0 | 1, 0 | 1) => true,
+        (2, 0) => board.pieces_of(Colour::White, PieceKind::Bishop).is_empty(),
+        (0, 2) => board.pieces_of(Colour::Black, PieceKind::Bishop).is_empty(),
+        _ => false,
+    }
+}
+
+/// Whether `colour` has pieces that mate a bare king by force, leaving pawns aside.
+fn can_force_mate(board: &Board, colour: Colour) -> bool {
+    let count = |kind| board.pieces_of(colour, kind).count();
+
+    count(PieceKind::Queen) > 0
+        || count(PieceKind::Rook) > 0
+        || (count(PieceKind::Bishop) > 0
+            && count(PieceKind::Bishop) + count(PieceKind::Knight) >= 2)
+}
+
+fn drawn(_: &Board, _: Colour) -> i32 {
+    0
+}
+
+/// Drives the bare king of the weaker side to the edge and brings the kings together, which
+/// is what every mate against a lone king needs.
+fn lone_king(board: &Board, strong: Colour) -> i32 {
+    let material: i32 = PieceKind::ALL
+        .into_iter()
+        .map(|kind| PIECE_VALUES[kind.index()] * board.pieces_of(strong, kind).count() as i32)
+        .sum();
+
+    let (Some(winning), Some(losing)) = (board.king_square(strong), board.king_square(!strong))
+    else {
+        return material;
+    };
+
+    KNOWN_WIN + material + 20 * centre_distance(losing) + 10 * (7 - winning.distance(losing) as i32)
+}
+
+/// The Manhattan distance from `square` to the nearest of the four centre squares.
+fn centre_distance(square: Square) -> i32 {
+    let file = square.file().index() as i32;
+    let rank = square.rank().index() as i32;
+
+    (2 * file - 7).abs() / 2 + (2 * rank - 7).abs() / 2
+}
@@ -0,0 +1,280 @@
+//! The engine's identity and what it can be configured with.
+//!
+//! Every frontend introduces the engine from here, so that the UCI handshake and any other
+//! protocol report the same name, version and options.
+
+use std::fmt;
+
+use crate::{uci, EngineOptions};
+
+pub const NAME: &str = "Gambit";
+
+pub const AUTHOR: &str = "Joshua Clements";
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// What this build of the engine was compiled with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildFeatures {
+    /// Whether the target has the BMI2 bit manipulation instructions enabled.
+    pub bmi2: bool,
+    /// Whether searches can run on a background thread, which hosts such as WebAssembly in
+    /// the browser cannot spawn.
+    pub threads: bool,
+}
+
+pub const FEATURES: BuildFeatures = BuildFeatures {
+    bmi2: cfg!(target_feature = "bmi2"),
+    threads: cfg!(not(all(target_arch = "wasm32", target_os = "unknown"))),
+};
+
+/// Lists the features that are enabled, such as `bmi2 threads`, or `none`.
+impl fmt::Display for BuildFeatures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let enabled: Vec<_> = [(self.bmi2, "bmi2"), (self.threads, "threads")]
+            .into_iter()
+            .filter_map(|(enabled, name)| enabled.then_some(name))
+            .collect();
+
+        match enabled.is_empty() {
+            true => f.write_str("none"),
+            false => f.write_str(&enabled.join(" ")),
+        }
+    }
+}
+
+/// The type and range of an option.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OptionType {
+    Check {
+        default: bool,
+    },
+    Spin {
+        default: i64,
+        min: i64,
+        max: i64,
+    },
+    /// One of a fixed list of values.
+    Combo {
+        default: &'static str,
+        values: &'static [&'static str],
+    },
+    /// An action rather than a setting, which takes no value.
+    Button,
+    String {
+        default: &'static str,
+    },
+}
+
+/// A value given to an option, checked against its type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OptionValue {
+    Check(bool),
+    Spin(i64),
+    /// The value as the option lists it, whatever case it was given in.
+    Combo(&'static str),
+    Button,
+    String(String),
+}
+
+/// The reasons a value can be refused by an option.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OptionError {
+    MissingValue {
+        name: &'static str,
+    },
+    /// The value does not parse as the option's type, or is out of its range.
+    InvalidValue {
+        name: &'static str,
+        value: String,
+    },
+}
+
+impl fmt::Display for OptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionError::MissingValue { name } => write!(f, "missing value for {name}"),
+            OptionError::InvalidValue { name, value } => {
+                write!(f, "invalid value for {name}: {value}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OptionError {}
+
+impl OptionInfo {
+    /// Checks the value of a `setoption` command against the option's type, where `value` is
+    /// everything after `value`, or `None` if it was left out.
+    ///
+    /// Buttons ignore any value, and strings take `<empty>` for the empty string.
+    pub fn parse(&self, value: Option<&str>) -> Result<OptionValue, OptionError> {
+        let value = match (&self.option_type, value) {
+            (OptionType::Button, _) => return Ok(OptionValue::Button),
+            (_, Some(value)) => value.trim(),
+            (_, None) => return Err(OptionError::MissingValue { name: self.name }),
+        };
+        let invalid = || OptionError::InvalidValue {
+            name: self.name,
+            value: value.to_owned(),
+        };
+
+        match self.option_type {
+            OptionType::Check { .. } => {
+                value.parse().map(OptionValue::Check).map_err(|_| invalid())
+            }
+            OptionType::Spin { min, max, .. } => match value.parse() {
+                Ok(number) if (min..=max).contains(&number) => Ok(OptionValue::Spin(number)),
+                _ => Err(invalid()),
+            },
+            OptionType::Combo { values, .. } => values
+                .iter()
+                .find(|listed| listed.eq_ignore_ascii_case(value))
+                .map(|&listed| OptionValue::Combo(listed))
+                .ok_or_else(invalid),
+            OptionType::Button => Ok(OptionValue::Button),
+            OptionType::String { .. } if value == "<empty>" => {
+                Ok(OptionValue::String(String::new()))
+            }
+            OptionType::String { .. } => Ok(OptionValue::String(value.to_owned())),
+        }
+    }
+}
+
+/// An option frontends can set, under the name UCI gives it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OptionInfo {
+    pub name: &'static str,
+    pub option_type: OptionType,
+}
+
+/// Formats the option as UCI advertises it, such as
+/// `option name Hash type spin default 16 min 1 max 65536`.
+impl fmt::Display for OptionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "option name {} type ", self.name)?;
+
+        match self.option_type {
+            OptionType::Check { default } => write!(f, "check default {default}"),
+            OptionType::Spin { default, min, max } => {
+                write!(f, "spin default {default} min {min} max {max}")
+            }
+            OptionType::Combo { default, values } => {
+                write!(f, "combo default {default}")?;
+                values
+                    .iter()
+                    .try_for_each(|value| write!(f, " var {value}"))
+            }
+            OptionType::Button => f.write_str("button"),
+            // UCI has no way to write an empty string, so GUIs agree on a placeholder.
+            OptionType::String { default: "" } => f.write_str("string default <empty>"),
+            OptionType::String { default } => write!(f, "string default {default}"),
+        }
+    }
+}
+
+/// Returns every option the engine accepts.
+pub fn options() -> Vec<OptionInfo> {
+    let defaults = EngineOptions::default();
+
+    vec![
+        OptionInfo {
+            name: "Hash",
+            option_type: OptionType::Spin {
+                default: defaults.hash as i64,
+                min: 1,
+                max: 65536,
+            },
+        },
+        OptionInfo {
+            name: "Clear Hash",
+            option_type: OptionType::Button,
+        },
+        OptionInfo {
+            name: "LargePages",
+            option_type: OptionType::Check {
+                default: defaults.large_pages,
+            },
+        },
+        OptionInfo {
+            name: "LazyEvalMargin",
+            option_type: OptionType::Spin {
+                default: defaults.lazy_eval_margin as i64,
+                min: 0,
+                max: 10000,
+            },
+        },
+        OptionInfo {
+            name: "WeightsFile",
+            option_type: OptionType::String { default: "" },
+        },
+        OptionInfo {
+            name: "BlunderChance",
+            option_type: OptionType::Spin {
+                default: 0,
+                min: 0,
+                max: 100,
+            },
+        },
+        OptionInfo {
+            name: "EasyMovePercent",
+            option_type: OptionType::Spin {
+                default: uci::DEFAULT_EASY_MOVE_PERCENT as i64,
+                min: 0,
+                max: 100,
+            },
+        },
+        // As in other engines, the clock is read as a node budget at this many nodes per
+        // millisecond when it is set, so that games on a clock replay exactly.
+        OptionInfo {
+            name: "nodestime",
+            option_type: OptionType::Spin {
+                default: 0,
+                min: 0,
+                max: 10000,
+            },
+        },
+        // Scripts can ask for search information as one JSON object per line rather than as
+        // UCI `info` lines.
+        OptionInfo {
+            name: "InfoFormat",
+            option_type: OptionType::Combo {
+                default: "uci",
+                values: &["uci", "json"],
+            },
+        },
+        // GUIs set this while analysing, when the engine gives its real best move and takes
+        // no shortcuts with the time.
+        OptionInfo {
+            name: "UCI_AnalyseMode",
+            option_type: OptionType::Check { default: false },
+        },
+        // GUIs set this when the engine may think on the opponent's time. Beyond `go ponder`,
+        // it then keeps searching after answering an easy move early.
+        OptionInfo {
+            name: "Ponder",
+            option_type: OptionType::Check { default: false },
+        },
+    ]
+}
+
+/// Finds the option called `name`, which UCI matches regardless of case.
+pub fn find_option(name: &str) -> Option<OptionInfo> {
+    options()
+        .into_iter()
+        .find(|option| option.name.eq_ignore_ascii_case(name))
+}
+
+/// Returns the lines that answer the `uci` command: the engine's identity, its options and
+/// `uciok`.
+pub fn uci_handshake() -> Vec<String> {
+    let mut lines = vec![
+        format!("id name {NAME} {VERSION}"),
+        format!("id author {AUTHOR}"),
+    ];
+
+    lines.extend(options().iter().map(ToString::to_string));
+    lines.push("uciok".to_owned());
+
+    lines
+}
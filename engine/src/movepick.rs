@@ -0,0 +1,113 @@
+//! Move ordering.
+
+use gambit::movegen::{MoveList, MAX_MOVES};
+use gambit::{Board, Move};
+
+use crate::eval::PIECE_VALUES;
+use crate::history::{History, PieceTo};
+use crate::see::see;
+
+/// The score of the first killer move, above any quiet history score; the second killer
+/// scores one less.
+const KILLER: i32 = 40_000;
+
+/// Hands out moves best first: the hash move, then captures that do not lose material by most
+/// valuable victim and least valuable attacker, then promotions, then the killer moves, then
+/// quiet moves by history, and finally losing captures.
+pub struct MovePicker {
+    moves: MoveList,
+    scores: [i32; MAX_MOVES],
+    index: usize,
+}
+
+impl MovePicker {
+    /// Scores `moves`, which are legal in `board`, reached by the move `previous`.
+    ///
+    /// The killers are quiet moves that caused a cutoff in a sibling node. They may well be
+    /// illegal here, and are only ever matched against `moves`, so one that is not among them
+    /// is ignored.
+    pub fn new(
+        board: &Board,
+        moves: MoveList,
+        tt_move: Option<Move>,
+        killers: [Option<Move>; 2],
+        history: &History,
+        previous: Option<PieceTo>,
+    ) -> MovePicker {
+        let colour = board.side_to_move();
+        let mut scores = [0; MAX_MOVES];
+
+        for (score, &mv) in scores.iter_mut().zip(moves.iter()) {
+            *score = if Some(mv) == tt_move {
+                i32::MAX
+            } else if let Some(captured) = mv.captured() {
+                let mvv_lva = 10 * PIECE_VALUES[captured.index()]
+                    - PIECE_VALUES[mv.piece().index()]
+                    + history.capture(colour, mv) / 32;
+
+                if see(board, mv, 0) {
+                    100_000 + mvv_lva
+                } else {
+                    -100_000 + mvv_lva
+                }
+            } else if let Some(promotion) = mv.promotion() {
+                50_000 + PIECE_VALUES[promotion.index()]
+            } else if Some(mv) == killers[0] {
+                KILLER
+            } else if Some(mv) == killers[1] {
+                KILLER - 1
+            } else {
+                history.quiet(colour, mv, previous)
+            };
+        }
+
+        MovePicker {
+            moves,
+            scores,
+            index: 0,
+        }
+    }
+
+    /// Hands out `moves` in the order given, as at the root, where the order is kept from the
+    /// previous iteration.
+    pub fn in_order(moves: MoveList) -> MovePicker {
+        let mut scores = [0; MAX_MOVES];
+
+        for (index, score) in scores.iter_mut().enumerate() {
+            *score = -(index as i32);
+        }
+
+        MovePicker {
+            moves,
+            scores,
+            index: 0,
+        }
+    }
+
+    /// Hands out the next move like [`Iterator::next`], together with its ordering score.
+    pub fn next_scored(&mut self) -> Option<(Move, i32)> {
+        let len = self.moves.len();
+
+        if self.index == len {
+            return None;
+        }
+
+        let best = (self.index..len).max_by_key(|&i| self.scores[i])?;
+
+        self.moves.swap(self.index, best);
+        self.scores.swap(self.index, best);
+        self.index += 1;
+
+        Some((self.moves[self.index - 1], self.scores[self.index - 1]))
+    }
+}
+
+impl Iterator for MovePicker {
+    type Item = Move;
+
+    /// Selects the best remaining move, which is cheaper than sorting the list up front since
+    /// most nodes cut off after a few moves.
+    fn next(&mut self) -> Option<Move> {
+        self.next_scored().map(|(mv, _)| mv)
+    }
+}
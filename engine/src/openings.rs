@@ -0,0 +1,93 @@
+//! Opening suites for matches.
+//!
+//! Self-play from the standard position alone repeats the same few games, so matches start
+//! from a suite of balanced openings instead. A suite is either a file of FEN or EPD records,
+//! one per line, or a PGN file whose games end where the opening does, as the usual eight-move
+//! books are published.
+
+use std::fmt;
+use std::path::Path;
+
+use gambit::pgn::{self, PgnError};
+use gambit::Board;
+
+use crate::batch::{self, BatchError};
+use crate::handicap::Rng;
+
+/// An opening suite that cannot be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OpeningsError {
+    Epd(BatchError),
+    Pgn(PgnError),
+}
+
+impl fmt::Display for OpeningsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpeningsError::Epd(error) => error.fmt(f),
+            OpeningsError::Pgn(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for OpeningsError {}
+
+/// The order openings are played in.
+#[derive(Clone, Debug)]
+pub enum Selection {
+    /// In the order of the suite.
+    Sequential,
+    /// Shuffled by the generator, each opening still played once before any is repeated.
+    Random(Rng),
+}
+
+/// Reads the openings of a suite from `text`, as PGN if `path` ends in `.pgn` and as FEN or
+/// EPD records otherwise.
+pub fn read_openings(path: &Path, text: &str) -> Result<Vec<Board>, OpeningsError> {
+    let pgn = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pgn"));
+
+    match pgn {
+        true => read_pgn(text),
+        false => read_epd(text),
+    }
+}
+
+/// Reads one opening per line of FEN or EPD, skipping blank lines and lines starting with `#`.
+pub fn read_epd(text: &str) -> Result<Vec<Board>, OpeningsError> {
+    let positions = batch::read_positions(text).map_err(OpeningsError::Epd)?;
+
+    Ok(positions
+        .into_iter()
+        .map(|position| position.board)
+        .collect())
+}
+
+/// Reads the position at the end of the main line of every game as an opening.
+pub fn read_pgn(text: &str) -> Result<Vec<Board>, OpeningsError> {
+    let games = pgn::parse_games(text).map_err(OpeningsError::Pgn)?;
+
+    Ok(games
+        .iter()
+        .map(|game| {
+            let mut board = game.start().clone();
+            for &mv in game.moves() {
+                board.make_move(mv);
+            }
+
+            board
+        })
+        .collect())
+}
+
+/// Puts `openings` in the order `selection` plays them.
+pub fn select(openings: &mut [Board], selection: Selection) {
+    if let Selection::Random(mut rng) = selection {
+        // A Fisher-Yates shuffle.
+        for last in (1..openings.len()).rev() {
+            let other = rng.below(last as u64 + 1) as usize;
+            openings.swap(last, other);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Allocation of large tables, optionally backed by huge pages.
+//!
+//! A multi-gigabyte transposition table is probed at random, so nearly every probe misses the
+//! TLB when the table is made of ordinary 4 KB pages. Backing it with 2 MB pages makes the
+//! mapping small enough to stay cached. On Linux the table is aligned to 2 MB and the kernel is
+//! asked to back it with transparent huge pages; elsewhere, or when the kernel declines, the
+//! table lives in ordinary pages.
+
+use std::alloc::{self, Layout};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+/// The size of a huge page.
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// A fixed-length slice on the heap, optionally backed by huge pages.
+pub struct PageBuffer<T: Copy> {
+    ptr: NonNull<T>,
+    len: usize,
+    layout: Layout,
+    huge_pages: bool,
+}
+
+impl<T: Copy> PageBuffer<T> {
+    /// Allocates `len` copies of `value`, asking for huge pages if `huge_pages` is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer would be empty, because `len` is zero or `T` is zero-sized, or if
+    /// its size does not fit in the address space. Aborts if the memory cannot be had.
+    pub fn new(len: usize, value: T, huge_pages: bool) -> PageBuffer<T> {
+        let layout = layout::<T>(len, huge_pages).expect("page buffer size overflows");
+
+        PageBuffer::try_new(len, value, huge_pages)
+            .unwrap_or_else(|| alloc::handle_alloc_error(layout))
+    }
+
+    /// Allocates like [`PageBuffer::new`], but returns `None` when the memory cannot be had,
+    /// or the size does not fit in the address space, instead of aborting.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer would be empty, because `len` is zero or `T` is zero-sized.
+    pub fn try_new(len: usize, value: T, huge_pages: bool) -> Option<PageBuffer<T>> {
+        assert!(
+            len > 0 && size_of::<T>() > 0,
+            "page buffers cannot be empty"
+        );
+
+        let layout = layout::<T>(len, huge_pages)?;
+
+        // SAFETY: the layout is not zero-sized, as checked above.
+        let ptr = NonNull::new(unsafe { alloc::alloc(layout) }.cast::<T>())?;
+
+        let huge_pages = huge_pages && advise_huge_pages(ptr.as_ptr().cast(), layout.size());
+
+        for index in 0..len {
+            // SAFETY: the allocation holds at least `len` values of `T`.
+            unsafe { ptr.as_ptr().add(index).write(value) };
+        }
+
+        Some(PageBuffer {
+            ptr,
+            len,
+            layout,
+            huge_pages,
+        })
+    }
+
+    /// Returns whether the kernel agreed to back the buffer with huge pages.
+    pub fn huge_pages(&self) -> bool {
+        self.huge_pages
+    }
+}
+
+impl<T: Copy> Deref for PageBuffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        // SAFETY: the first `len` values were initialised in `new`.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Copy> DerefMut for PageBuffer<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        // SAFETY: as for `deref`, and `&mut self` makes the borrow unique.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Copy> Drop for PageBuffer<T> {
+    fn drop(&mut self) {
+        // SAFETY: the pointer was allocated in `new` with this layout.
+        unsafe { alloc::dealloc(self.ptr.as_ptr().cast(), self.layout) };
+    }
+}
+
+// SAFETY: the buffer owns its values, like a `Box<[T]>`.
+unsafe impl<T: Copy + Send> Send for PageBuffer<T> {}
+
+// SAFETY: shared access only hands out shared references to the values.
+unsafe impl<T: Copy + Sync> Sync for PageBuffer<T> {}
+
+/// Returns the layout of `len` values of `T`, padded and aligned to whole huge pages if
+/// `huge_pages` is set, or `None` if the size overflows.
+fn layout<T>(len: usize, huge_pages: bool) -> Option<Layout> {
+    let array = Layout::array::<T>(len).ok()?;
+
+    match huge_pages {
+        true => Layout::from_size_align(
+            array.size().checked_next_multiple_of(HUGE_PAGE_SIZE)?,
+            HUGE_PAGE_SIZE.max(array.align()),
+        )
+        .ok(),
+        false => Some(array),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn advise_huge_pages(ptr: *mut libc::c_void, len: usize) -> bool {
+    // SAFETY: the range is a live allocation aligned to a huge page, and the advice changes
+    // only how it is backed, not its contents.
+    unsafe { libc::madvise(ptr, len, libc::MADV_HUGEPAGE) == 0 }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn advise_huge_pages(_: *mut std::ffi::c_void, _: usize) -> bool {
+    false
+}
@@ -0,0 +1,169 @@
+//! Puzzle extraction from played games.
+//!
+//! Every position of a game is searched, and one becomes a puzzle when the move that led to it
+//! was a blunder: the side to move was not winning before it, but now has a move that mates
+//! or wins decisive material. Themes are read off the solution with static exchange
+//! evaluation and the attack and pin queries of the library.
+
+use gambit::pgn::Game;
+use gambit::{attacks, movegen, Board, Epd, Move, PieceKind, Rank};
+
+use crate::eval::PIECE_VALUES;
+use crate::see::see;
+use crate::{Engine, Score, SearchLimits, SearchResult};
+
+/// The depth of the search that finds and verifies solutions.
+const SOLUTION_DEPTH: u8 = 6;
+
+/// The gain in centipawns that counts as decisive.
+const DECISIVE: i32 = 300;
+
+/// Searches every position of `game`, returning a puzzle for each one that a blunder gave a
+/// decisive solution.
+///
+/// Each puzzle is an EPD record with the solution as `bm` and `pv` in SAN, `dm` or `ce` for
+/// its value, `id` naming the game and ply, and the themes as a `c0` comment.
+pub fn find_puzzles(engine: &mut Engine, game: &Game) -> Vec<Epd> {
+    let start = game.start().to_fen();
+    let mut puzzles = Vec::new();
+    // The score before the previous move, from the point of view of the side now to move.
+    let mut before: Option<Score> = None;
+
+    for (ply, (board, _)) in game.positions().enumerate() {
+        let played: Vec<String> = game.moves()[..ply]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        // Every move was legal when the game was read.
+        let _ = engine.set_position(&start, &played);
+
+        let result = engine.search(SearchLimits {
+            depth: Some(SOLUTION_DEPTH),
+            ..SearchLimits::default()
+        });
+        let baseline = before.replace(-result.score);
+
+        let Some(baseline) =
+            baseline.filter(|&score| !score.is_mate() && score < Score::new(DECISIVE))
+        else {
+            continue;
+        };
+        let Some(solution) = result.best_move else {
+            continue;
+        };
+
+        let wins_mate = result.score.mate_moves().is_some_and(|moves| moves > 0);
+        if !wins_mate && result.score < baseline + DECISIVE {
+            continue;
+        }
+
+        let mut epd = Epd::new(board.clone());
+        epd.set("bm", vec![board.san(solution)]);
+        epd.set("pv", san_line(&board, &result.pv));
+
+        match result.score.mate_moves() {
+            Some(moves) => epd.set("dm", vec![moves.to_string()]),
+            None => epd.set("ce", vec![result.score.get().to_string()]),
+        }
+
+        let event = game.tag("Event").unwrap_or("?");
+        epd.set("id", vec![format!("{event} ply {ply}")]);
+        epd.set("c0", vec![themes(&board, solution, &result).join(" ")]);
+
+        puzzles.push(epd);
+    }
+
+    puzzles
+}
+
+fn san_line(board: &Board, line: &[Move]) -> Vec<String> {
+    let mut board = board.clone();
+
+    line.iter()
+        .map(|&mv| {
+            let san = board.san(mv);
+            board.make_move(mv);
+
+            san
+        })
+        .collect()
+}
+
+/// Names what the solution `mv` does, most specific first.
+pub fn themes(board: &Board, mv: Move, result: &SearchResult) -> Vec<&'static str> {
+    let mut themes = Vec::new();
+    let us = board.side_to_move();
+
+    if let Some(moves) = result.score.mate_moves() {
+        themes.push("mate");
+        themes.push(match moves {
+            1 => "mateIn1",
+            2 => "mateIn2",
+            3 => "mateIn3",
+            _ => "mateInMore",
+        });
+
+        if is_back_rank_mate(board, &result.pv) {
+            themes.push("backRankMate");
+        }
+    } else {
+        themes.push("material");
+    }
+
+    // A first move that loses material on the spot is only a solution because of what follows.
+    if !see(board, mv, 0) {
+        themes.push("sacrifice");
+    }
+
+    let mut after = board.clone();
+    after.make_move(mv);
+
+    if is_fork(&after, mv) {
+        themes.push("fork");
+    }
+
+    if !(after.pinned_pieces(!us) & !board.pinned_pieces(!us)).is_empty() {
+        themes.push("pin");
+    }
+
+    themes
+}
+
+/// Whether the piece that made `mv` attacks two or more pieces that are worth at least as
+/// much as it is, counting the king.
+fn is_fork(after: &Board, mv: Move) -> bool {
+    let Some(piece) = after.piece_at(mv.to()) else {
+        return false;
+    };
+
+    let targets =
+        attacks::piece_attacks(piece, mv.to(), after.occupied()) & after.colour(!piece.colour);
+    let value = PIECE_VALUES[piece.kind.index()];
+
+    targets
+        .into_iter()
+        .filter_map(|square| after.piece_at(square))
+        .filter(|target| {
+            target.kind == PieceKind::King || PIECE_VALUES[target.kind.index()] >= value
+        })
+        .count()
+        >= 2
+}
+
+/// Whether `line` ends in mate with the mated king still on its first rank.
+fn is_back_rank_mate(board: &Board, line: &[Move]) -> bool {
+    let mut board = board.clone();
+    for &mv in line {
+        board.make_move(mv);
+    }
+
+    let mated = board.side_to_move();
+    let back_rank = Rank::First.relative(mated);
+
+    board.in_check()
+        && movegen::legal_moves(&board).is_empty()
+        && board
+            .king_square(mated)
+            .is_some_and(|king| king.rank() == back_rank)
+}
@@ -0,0 +1,128 @@
+//! Principal variations, the line of best play the search expects from a position.
+
+use std::fmt;
+use std::ops::Deref;
+
+use gambit::movegen;
+use gambit::{Board, Move, PieceKind, Square};
+
+use crate::search::MAX_PLY;
+use crate::tt::TranspositionTable;
+
+/// A principal variation that lives on the stack, holding at most [`MAX_PLY`] moves.
+#[derive(Clone)]
+pub struct Pv {
+    moves: [Move; MAX_PLY],
+    len: usize,
+}
+
+impl Pv {
+    pub const fn new() -> Pv {
+        Pv {
+            moves: [Move::new(Square::A1, Square::A1, PieceKind::Pawn); MAX_PLY],
+            len: 0,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Replaces the line with `mv` followed by `child`, the principal variation of the position
+    /// after it. Moves beyond the capacity are dropped from the end.
+    pub fn push_front(&mut self, mv: Move, child: &Pv) {
+        let len = (child.len + 1).min(MAX_PLY);
+
+        self.moves[0] = mv;
+        self.moves[1..len].copy_from_slice(&child.moves[..len - 1]);
+        self.len = len;
+    }
+
+    /// Extends the line, played from `root`, with the moves stored in `tt` for the positions
+    /// after it until it is `len` moves long.
+    ///
+    /// The search leaves a line short where it ended in a draw or a position it had already
+    /// searched. A stored move is only followed if it is legal, since its entry may belong to
+    /// another position whose key collided, and the walk stops rather than return to a
+    /// position already on the line.
+    pub fn complete(&mut self, root: &Board, tt: &TranspositionTable, len: usize) {
+        let mut board = root.clone();
+        let mut keys = vec![board.key()];
+
+        for &mv in self.iter() {
+            board.make_move(mv);
+            keys.push(board.key());
+        }
+
+        while self.len < len.min(MAX_PLY) {
+            let Some(mv) = tt
+                .probe(board.key())
+                .and_then(|entry| entry.mv.unpack(&board))
+                .filter(|mv| movegen::legal_moves(&board).contains(mv))
+            else {
+                break;
+            };
+
+            board.make_move(mv);
+            if keys.contains(&board.key()) {
+                break;
+            }
+
+            keys.push(board.key());
+            self.moves[self.len] = mv;
+            self.len += 1;
+        }
+    }
+}
+
+impl Default for Pv {
+    fn default() -> Pv {
+        Pv::new()
+    }
+}
+
+impl Deref for Pv {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl<'a> IntoIterator for &'a Pv {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl PartialEq for Pv {
+    fn eq(&self, other: &Pv) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for Pv {}
+
+impl fmt::Debug for Pv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Formats the line as UCI moves separated by spaces.
+impl fmt::Display for Pv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, mv) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+
+            write!(f, "{mv}")?;
+        }
+
+        Ok(())
+    }
+}
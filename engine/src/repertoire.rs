@@ -0,0 +1,265 @@
+//! Opening repertoire training.
+//!
+//! A repertoire is a set of lines read from PGN. The trainer walks each line and asks for the
+//! moves of the side being trained; a legal answer that leaves the repertoire is compared with
+//! the repertoire's move by a short search. How each line went is kept in a small text file,
+//! so that the weakest lines come up first in the next session.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use gambit::pgn::Game;
+use gambit::{Board, Colour, Move};
+
+use crate::{Engine, Score, SearchLimits};
+
+/// The depth of the search that judges answers which leave the repertoire.
+const JUDGE_DEPTH: u8 = 6;
+
+/// A line of the repertoire, from one game of the PGN.
+pub struct Line {
+    name: String,
+    start: Board,
+    moves: Vec<Move>,
+    /// The moves in SAN, which identify the line in the statistics file.
+    key: String,
+}
+
+impl Line {
+    /// The `Opening` or `Event` tag of the game, or the moves if it has neither.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+}
+
+pub struct Repertoire {
+    lines: Vec<Line>,
+    /// Every move the repertoire plays from each position it reaches, by key.
+    book: HashMap<u64, Vec<Move>>,
+}
+
+impl Repertoire {
+    /// Builds a repertoire from the main lines of `games`, skipping games without moves.
+    pub fn new(games: &[Game]) -> Repertoire {
+        let mut lines = Vec::new();
+        let mut book: HashMap<u64, Vec<Move>> = HashMap::new();
+
+        for game in games.iter().filter(|game| !game.moves().is_empty()) {
+            let mut sans = Vec::new();
+
+            for (board, mv) in game.positions() {
+                sans.push(board.san(mv));
+
+                let moves = book.entry(board.key()).or_default();
+                if !moves.contains(&mv) {
+                    moves.push(mv);
+                }
+            }
+
+            let key = sans.join(" ");
+            let name = game
+                .tag("Opening")
+                .or(game.tag("Event"))
+                .filter(|name| !name.is_empty() && *name != "?")
+                .map_or_else(|| key.clone(), str::to_owned);
+
+            lines.push(Line {
+                name,
+                start: game.start().clone(),
+                moves: game.moves().to_vec(),
+                key,
+            });
+        }
+
+        Repertoire { lines, book }
+    }
+
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    /// Returns whether `mv` is played from `board` in any line, so that transpositions between
+    /// lines are accepted.
+    pub fn is_book_move(&self, board: &Board, mv: Move) -> bool {
+        self.book
+            .get(&board.key())
+            .is_some_and(|moves| moves.contains(&mv))
+    }
+
+    /// Returns the indices of the lines in the order they should be trained: the least
+    /// accurate first, then the least practised.
+    pub fn schedule(&self, statistics: &Statistics) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.lines.len()).collect();
+
+        order.sort_by_key(|&index| {
+            let stats = statistics.get(&self.lines[index]);
+            let accuracy = match stats.asked {
+                0 => 0,
+                asked => stats.correct * 1000 / asked,
+            };
+
+            (accuracy, stats.asked)
+        });
+
+        order
+    }
+}
+
+/// How often the moves of a line were asked for, and how often they were answered correctly.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LineStats {
+    pub asked: u32,
+    pub correct: u32,
+}
+
+/// The statistics of every line trained so far, stored as one line per repertoire line:
+/// the number of moves asked, the number answered correctly, and the line's moves in SAN.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    lines: HashMap<String, LineStats>,
+}
+
+impl Statistics {
+    /// Reads statistics written by [`Statistics`]'s `Display`, skipping malformed lines.
+    pub fn parse(text: &str) -> Statistics {
+        let lines = text
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, ' ');
+                let asked = fields.next()?.parse().ok()?;
+                let correct = fields.next()?.parse().ok()?;
+                let key = fields.next()?.trim();
+
+                Some((key.to_owned(), LineStats { asked, correct }))
+            })
+            .collect();
+
+        Statistics { lines }
+    }
+
+    pub fn get(&self, line: &Line) -> LineStats {
+        self.lines.get(&line.key).copied().unwrap_or_default()
+    }
+
+    /// Counts one question from `line`.
+    pub fn record(&mut self, line: &Line, correct: bool) {
+        let stats = self.lines.entry(line.key.clone()).or_default();
+
+        stats.asked += 1;
+        stats.correct += correct as u32;
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines: Vec<_> = self.lines.iter().collect();
+        lines.sort_by_key(|(key, _)| *key);
+
+        for (key, stats) in lines {
+            writeln!(f, "{} {} {key}", stats.asked, stats.correct)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Quizzes the moves `colour` plays in every line of `repertoire`, reading answers in SAN from
+/// `input` and recording the outcome of each in `statistics`.
+///
+/// Training stops early at the end of the input or when the answer is `quit`.
+pub fn train(
+    repertoire: &Repertoire,
+    statistics: &mut Statistics,
+    colour: Colour,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut engine = Engine::default();
+
+    for index in repertoire.schedule(statistics) {
+        let line = &repertoire.lines[index];
+        let mut board = line.start.clone();
+
+        writeln!(output, "Line: {}", line.name)?;
+
+        for &expected in &line.moves {
+            if board.side_to_move() == colour {
+                writeln!(output, "\n{board}\n{}", board.to_fen())?;
+
+                let Some(answer) = ask(&board, &mut input, &mut output)? else {
+                    return Ok(());
+                };
+
+                let correct = answer == expected || repertoire.is_book_move(&board, answer);
+                statistics.record(line, correct);
+
+                if correct {
+                    writeln!(output, "Correct.")?;
+                } else {
+                    let ours = judge(&mut engine, &board, answer);
+                    let theirs = judge(&mut engine, &board, expected);
+
+                    writeln!(
+                        output,
+                        "The repertoire plays {}. Yours scores {ours}, the repertoire's {theirs}.",
+                        board.san(expected)
+                    )?;
+                }
+            }
+
+            board.make_move(expected);
+        }
+
+        let stats = statistics.get(line);
+        writeln!(
+            output,
+            "Line complete: {} of {} correct overall.\n",
+            stats.correct, stats.asked
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Prompts until a legal move is given, returning `None` at the end of the input or on `quit`.
+fn ask(
+    board: &Board,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Option<Move>> {
+    loop {
+        write!(output, "Your move: ")?;
+        output.flush()?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            return Ok(None);
+        }
+
+        match answer.trim() {
+            "quit" => return Ok(None),
+            answer => match board.parse_san(answer) {
+                Ok(mv) => return Ok(Some(mv)),
+                Err(error) => writeln!(output, "{error}: {answer}")?,
+            },
+        }
+    }
+}
+
+/// Scores `mv` from the point of view of the side playing it.
+fn judge(engine: &mut Engine, board: &Board, mv: Move) -> Score {
+    // The move is legal, so the position is too.
+    let _ = engine.set_position(&board.to_fen(), [mv.to_string()]);
+
+    -engine
+        .search(SearchLimits {
+            depth: Some(JUDGE_DEPTH),
+            ..SearchLimits::default()
+        })
+        .score
+}
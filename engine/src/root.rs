@@ -0,0 +1,109 @@
+//! The moves of the root position, kept from one iteration to the next.
+//!
+//! Each iteration searches the root moves in the order the last one left them: the moves
+//! whose exact score it learned, best first, then the rest by the nodes it spent refuting
+//! them, since a move that took long to refute came close to being best.
+
+use gambit::movegen::MoveList;
+use gambit::Move;
+
+use crate::pv::Pv;
+use crate::score::Score;
+
+/// What the search has learned about one root move.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RootMove {
+    pub mv: Move,
+    /// The exact score of the move in the iteration in progress, or `-Score::INFINITE` if it
+    /// has not been found better than the moves before it.
+    pub score: Score,
+    /// The exact score the move had in the last completed iteration, if it had one.
+    pub previous_score: Score,
+    /// The nodes spent searching the move in the iteration in progress.
+    pub nodes: u64,
+    /// The principal variation starting with the move, when its score is exact.
+    pub pv: Pv,
+}
+
+/// The root moves in the order they are searched.
+#[derive(Clone, Debug, Default)]
+pub struct RootMoves {
+    moves: Vec<RootMove>,
+}
+
+impl RootMoves {
+    /// Creates the list with the moves in their first search order.
+    pub fn new(moves: impl IntoIterator<Item = Move>) -> RootMoves {
+        RootMoves {
+            moves: moves
+                .into_iter()
+                .map(|mv| RootMove {
+                    mv,
+                    score: -Score::INFINITE,
+                    previous_score: -Score::INFINITE,
+                    nodes: 0,
+                    pv: Pv::new(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the moves in the order they are to be searched.
+    pub fn order(&self) -> MoveList {
+        let mut order = MoveList::new();
+        for root in &self.moves {
+            order.push(root.mv);
+        }
+
+        order
+    }
+
+    /// Records that `mv` scored exactly `score`, with the principal variation `pv`.
+    pub fn set_score(&mut self, mv: Move, score: Score, pv: &Pv) {
+        if let Some(root) = self.find(mv) {
+            root.score = score;
+            root.pv.clone_from(pv);
+        }
+    }
+
+    /// Adds `nodes` to the effort spent on `mv`.
+    pub fn add_nodes(&mut self, mv: Move, nodes: u64) {
+        if let Some(root) = self.find(mv) {
+            root.nodes += nodes;
+        }
+    }
+
+    /// Orders the moves for the next iteration and starts it with no scores or nodes.
+    pub fn finish_iteration(&mut self) {
+        // The sort is stable, so moves that tie keep their order.
+        self.moves
+            .sort_by_key(|root| std::cmp::Reverse((root.score, root.nodes)));
+
+        for root in &mut self.moves {
+            root.previous_score = root.score;
+            root.score = -Score::INFINITE;
+            root.nodes = 0;
+        }
+    }
+
+    /// Returns the move that will be searched first, which after an iteration is its best.
+    pub fn best(&self) -> Option<&RootMove> {
+        self.moves.first()
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RootMove> {
+        self.moves.iter()
+    }
+
+    fn find(&mut self, mv: Move) -> Option<&mut RootMove> {
+        self.moves.iter_mut().find(|root| root.mv == mv)
+    }
+}
@@ -0,0 +1,134 @@
+//! Scores as seen by the search: centipawns from the side to move's point of view, or a
+//! distance to mate.
+
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+
+use crate::search::MAX_PLY;
+
+/// A score from the side to move's point of view.
+///
+/// Mates are encoded as [`Score::MATE`] minus the distance to mate in plies, negated when being
+/// mated, so that shorter mates always compare better. Arithmetic saturates at
+/// [`Score::INFINITE`], so no margin can push a score past the search's outermost bounds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Score(i32);
+
+impl Score {
+    /// The score of a drawn position.
+    pub const DRAW: Score = Score(0);
+
+    /// The score of delivering mate on the board.
+    pub const MATE: Score = Score(32_000);
+
+    /// A score outside any reachable bound.
+    pub const INFINITE: Score = Score(32_001);
+
+    /// Scores at or beyond this magnitude are mates found within [`MAX_PLY`] plies.
+    pub const MATE_BOUND: Score = Score(Score::MATE.0 - MAX_PLY as i32);
+
+    /// Returns a score of `centipawns`, clamped to the range of scores.
+    pub const fn new(centipawns: i32) -> Score {
+        Score(clamp(centipawns))
+    }
+
+    /// Returns the score in centipawns, or the raw mate encoding for mates.
+    pub const fn get(self) -> i32 {
+        self.0
+    }
+
+    /// Returns the score of mating the opponent `ply` plies from the root.
+    pub const fn mate_in(ply: usize) -> Score {
+        Score(Score::MATE.0 - ply as i32)
+    }
+
+    /// Returns the score of being mated `ply` plies from the root.
+    pub const fn mated_in(ply: usize) -> Score {
+        Score(ply as i32 - Score::MATE.0)
+    }
+
+    /// Returns whether the score is a forced mate for either side.
+    pub const fn is_mate(self) -> bool {
+        self.0.abs() >= Score::MATE_BOUND.0 && self.0.abs() <= Score::MATE.0
+    }
+
+    /// Returns the number of moves until mate, negative when being mated, or `None` if the
+    /// score is not a mate.
+    pub const fn mate_moves(self) -> Option<i32> {
+        if !self.is_mate() {
+            return None;
+        }
+
+        let moves = (Score::MATE.0 - self.0.abs() + 1) / 2;
+        Some(if self.0 > 0 { moves } else { -moves })
+    }
+
+    /// Converts a mate score relative to the root into one relative to the node `ply` plies
+    /// away, so that it stays correct when the position is reached again at a different ply.
+    pub const fn to_node(self, ply: usize) -> Score {
+        if self.0 >= Score::MATE_BOUND.0 {
+            Score(self.0 + ply as i32)
+        } else if self.0 <= -Score::MATE_BOUND.0 {
+            Score(self.0 - ply as i32)
+        } else {
+            self
+        }
+    }
+
+    /// Converts a mate score relative to a node `ply` plies from the root back into one
+    /// relative to the root. The inverse of [`Score::to_node`].
+    pub const fn from_node(self, ply: usize) -> Score {
+        if self.0 >= Score::MATE_BOUND.0 {
+            Score(self.0 - ply as i32)
+        } else if self.0 <= -Score::MATE_BOUND.0 {
+            Score(self.0 + ply as i32)
+        } else {
+            self
+        }
+    }
+}
+
+const fn clamp(value: i32) -> i32 {
+    if value > Score::INFINITE.0 {
+        Score::INFINITE.0
+    } else if value < -Score::INFINITE.0 {
+        -Score::INFINITE.0
+    } else {
+        value
+    }
+}
+
+impl Neg for Score {
+    type Output = Score;
+
+    fn neg(self) -> Score {
+        Score(-self.0)
+    }
+}
+
+impl Add<i32> for Score {
+    type Output = Score;
+
+    fn add(self, centipawns: i32) -> Score {
+        Score(clamp(self.0.saturating_add(centipawns)))
+    }
+}
+
+impl Sub<i32> for Score {
+    type Output = Score;
+
+    fn sub(self, centipawns: i32) -> Score {
+        Score(clamp(self.0.saturating_sub(centipawns)))
+    }
+}
+
+/// Formats the score as UCI does: `cp <centipawns>`, or `mate <moves>` with negative moves
+/// when being mated.
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mate_moves() {
+            Some(moves) => write!(f, "mate {moves}"),
+            None => write!(f, "cp {}", self.0),
+        }
+    }
+}
@@ -0,0 +1,912 @@
+//! Iterative deepening principal variation search with quiescence.
+//!
+//! Only the first move of a PV node is searched with the full window. The rest are scouted
+//! with a zero window around alpha, which is cheap when they fail low as expected, and are
+//! searched again with the full window only if they fail high.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
+use gambit::cuckoo;
+use gambit::movegen::{self, MoveList};
+use gambit::{Board, Move, Piece};
+
+use crate::history::{self, PieceTo};
+use crate::movepick::MovePicker;
+use crate::pv::Pv;
+use crate::root::RootMoves;
+use crate::score::Score;
+use crate::see::see;
+use crate::thread::ThreadData;
+use crate::tt::{Bound, Entry, TranspositionTable};
+use crate::{EngineOptions, SearchLimits, SearchProgress, SearchResult, SearchStats};
+
+/// The deepest the search goes from the root, including extensions.
+pub const MAX_PLY: usize = 128;
+
+/// How many nodes are searched between [`SearchProgress::Nodes`] reports.
+pub const NODE_MILESTONE: u64 = 1 << 20;
+
+/// How long the search runs before it reports each root move it starts on.
+pub const CURRMOVE_DELAY: Duration = Duration::from_secs(1);
+
+/// The shallowest iteration whose best move can be judged easy, other than an only move.
+const EASY_MOVE_DEPTH: u8 = 6;
+
+/// How many iterations in a row must agree on the best move before it can be judged easy
+/// without being a recapture.
+const EASY_MOVE_ITERATIONS: u8 = 4;
+
+/// How far every other root move must fall short of the best for it to be judged easy.
+const EASY_MOVE_MARGIN: i32 = 150;
+
+/// How many iterations in a row must agree on the best move for the search to stop once the
+/// stable move time has passed.
+const STABLE_ITERATIONS: u8 = 3;
+
+/// How far the score may fall from one iteration to the next while the search is still
+/// considered stable.
+const STABLE_SCORE_DROP: i32 = 10;
+
+/// The deepest remaining depth at which quiet moves are pruned by futility.
+const FUTILITY_DEPTH: i32 = 3;
+
+/// How far below alpha, per ply of remaining depth, the static evaluation must be before quiet
+/// moves are considered unable to raise it.
+const FUTILITY_MARGIN: i32 = 100;
+
+/// The extra futility margin while the side to move is improving.
+const FUTILITY_IMPROVING: i32 = 50;
+
+/// The deepest remaining depth at which razoring drops straight into quiescence.
+const RAZOR_DEPTH: i32 = 2;
+
+/// How far below alpha, per ply of remaining depth, the static evaluation must be to razor.
+const RAZOR_MARGIN: i32 = 300;
+
+/// The deepest remaining depth at which moves that lose material by SEE are pruned.
+const SEE_PRUNING_DEPTH: i32 = 6;
+
+/// The material a quiet move may lose, per ply of remaining depth, before it is pruned.
+const SEE_QUIET_MARGIN: i32 = 60;
+
+/// The material a capture may lose, per ply of remaining depth squared, before it is pruned.
+const SEE_CAPTURE_MARGIN: i32 = 20;
+
+pub struct Search<'a> {
+    tt: &'a mut TranspositionTable,
+    thread: &'a mut ThreadData,
+    options: &'a EngineOptions,
+    limits: &'a SearchLimits,
+    stop: &'a AtomicBool,
+    /// The keys of the positions played before the root, oldest first.
+    history: Vec<u64>,
+    /// The move that reached the root, if known, to recognise recaptures.
+    last_move: Option<Move>,
+    progress: &'a mut dyn FnMut(SearchProgress),
+    /// Raised while the search ponders on the opponent's time.
+    ponder: Option<&'a AtomicBool>,
+    /// Whether the search was pondering when the flag was last looked at.
+    pondering: bool,
+    start: Instant,
+    /// When the time limits started to run: the start of the search, or the moment pondering
+    /// was found to have ended.
+    clock: Instant,
+    nodes: u64,
+    next_milestone: u64,
+    /// The depth of the iteration in progress.
+    depth: u8,
+    /// The static evaluation at each ply of the current line, or `None` where the side to move
+    /// was in check.
+    evals: [Option<Score>; MAX_PLY],
+    /// The key of the position at each ply of the current line.
+    keys: [u64; MAX_PLY],
+    /// The move played at each ply of the current line.
+    played: [Option<PieceTo>; MAX_PLY],
+    /// The moves of the root, in the order the next iteration searches them.
+    root_moves: RootMoves,
+    stats: SearchStats,
+    stopped: bool,
+}
+
+impl<'a> Search<'a> {
+    pub fn new(
+        tt: &'a mut TranspositionTable,
+        thread: &'a mut ThreadData,
+        options: &'a EngineOptions,
+        limits: &'a SearchLimits,
+        stop: &'a AtomicBool,
+        history: Vec<u64>,
+        progress: &'a mut dyn FnMut(SearchProgress),
+    ) -> Search<'a> {
+        Search {
+            tt,
+            thread,
+            options,
+            limits,
+            stop,
+            history,
+            last_move: None,
+            progress,
+            ponder: None,
+            pondering: false,
+            start: Instant::now(),
+            clock: Instant::now(),
+            nodes: 0,
+            next_milestone: NODE_MILESTONE,
+            depth: 0,
+            evals: [None; MAX_PLY],
+            keys: [0; MAX_PLY],
+            played: [None; MAX_PLY],
+            root_moves: RootMoves::default(),
+            stats: SearchStats::default(),
+            stopped: false,
+        }
+    }
+
+    /// Tells the search which move reached the root, so that recapturing can be played
+    /// quickly as an easy move.
+    pub fn with_last_move(mut self, last_move: Option<Move>) -> Search<'a> {
+        self.last_move = last_move;
+        self
+    }
+
+    /// Holds the time limits back while `ponder` is raised, and runs them from when it is
+    /// lowered, so that a ponder search that is hit goes on with the whole budget of the move.
+    pub fn with_ponder(mut self, ponder: &'a AtomicBool) -> Search<'a> {
+        self.ponder = Some(ponder);
+        self.pondering = ponder.load(Ordering::Relaxed);
+        self
+    }
+
+    /// Searches `board` to increasing depths until a limit is hit, returning the result of the
+    /// deepest completed iteration.
+    pub fn run(&mut self, board: &Board) -> SearchResult {
+        let mut result = SearchResult {
+            best_move: self.legal_moves(board, 0).first().copied(),
+            score: Score::DRAW,
+            pv: Pv::new(),
+            depth: 0,
+            nodes: 0,
+        };
+
+        let max_depth = self.limits.depth.unwrap_or(u8::MAX).min(MAX_PLY as u8 - 1);
+        let mut root = board.clone();
+        let mut pv = Pv::new();
+        // How many iterations in a row have ended with the current best move.
+        let mut stable = 0;
+
+        self.thread.continue_from(board.key(), &self.history);
+        self.thread.history.age();
+        self.tt.new_search();
+
+        let tt_move = self
+            .tt
+            .probe(board.key())
+            .and_then(|entry| entry.mv.unpack(board));
+        self.root_moves = RootMoves::new(MovePicker::new(
+            board,
+            self.legal_moves(board, 0),
+            tt_move,
+            self.thread.killers.get(0),
+            &self.thread.history,
+            None,
+        ));
+
+        for depth in 1..=max_depth {
+            // Short searches can finish an iteration between two checks of the clock.
+            if depth > 1 && (self.out_of_time() || self.stop.load(Ordering::Relaxed)) {
+                break;
+            }
+
+            self.depth = depth;
+            let score = self.negamax::<true>(
+                &mut root,
+                depth as i32,
+                0,
+                -Score::INFINITE,
+                Score::INFINITE,
+                &mut pv,
+            );
+
+            if self.stopped {
+                break;
+            }
+
+            pv.complete(board, self.tt, depth as usize);
+            self.root_moves.finish_iteration();
+
+            let best_move = pv.first().copied().or(result.best_move);
+            stable = if best_move == result.best_move {
+                stable + 1
+            } else {
+                1
+            };
+            let falling = depth > 1 && score < result.score - STABLE_SCORE_DROP;
+
+            result.best_move = best_move;
+            result.score = score;
+            result.pv.clone_from(&pv);
+            result.depth = depth;
+
+            (self.progress)(SearchProgress::DepthCompleted {
+                depth,
+                score,
+                pv: pv.clone(),
+                nodes: self.nodes,
+                elapsed: self.start.elapsed(),
+                hashfull: self.tt.hashfull(),
+            });
+
+            if self.options.debug {
+                (self.progress)(SearchProgress::Stats {
+                    depth,
+                    stats: SearchStats {
+                        hashfull: self.tt.hashfull(),
+                        ..self.stats
+                    },
+                });
+            }
+
+            if self.is_easy(&mut root, &result, stable) || self.is_stable(stable, falling) {
+                break;
+            }
+        }
+
+        // A search stopped during its first iteration plays the first root move, the one the
+        // move ordering put first.
+        if result.depth == 0 {
+            if let Some(first) = self.root_moves.best() {
+                result.best_move = Some(first.mv);
+                result.pv.push_front(first.mv, &Pv::new());
+            }
+        }
+
+        result.nodes = self.nodes;
+        result
+    }
+
+    /// Whether `result`, from an iteration whose best move has held for `stable` iterations,
+    /// can be played without searching deeper, once the easy move time has passed.
+    fn is_easy(&mut self, root: &mut Board, result: &SearchResult, stable: u8) -> bool {
+        let Some(best) = result.best_move else {
+            return false;
+        };
+
+        if self.pondering()
+            || self
+                .limits
+                .easy_movetime
+                .is_none_or(|easy| self.clock.elapsed() < easy)
+        {
+            return false;
+        }
+
+        if self.legal_moves(root, 0).len() == 1 {
+            return true;
+        }
+
+        if result.depth < EASY_MOVE_DEPTH {
+            return false;
+        }
+
+        let recapture = self
+            .last_move
+            .is_some_and(|last| last.is_capture() && best.is_capture() && last.to() == best.to());
+
+        (recapture && see(root, best, 0))
+            || (stable >= EASY_MOVE_ITERATIONS
+                && self.dominates(root, best, result.score, result.depth))
+    }
+
+    /// Whether the best move has held for `stable` iterations, enough to stop once the stable
+    /// move time has passed, without the score `falling`.
+    fn is_stable(&mut self, stable: u8, falling: bool) -> bool {
+        stable >= STABLE_ITERATIONS
+            && !falling
+            && !self.pondering()
+            && self
+                .limits
+                .stable_movetime
+                .is_some_and(|movetime| self.clock.elapsed() >= movetime)
+    }
+
+    /// Whether every root move other than `best` scores at least [`EASY_MOVE_MARGIN`] below
+    /// `score`, checked by zero-window searches at half of `depth`.
+    fn dominates(&mut self, root: &mut Board, best: Move, score: Score, depth: u8) -> bool {
+        let threshold = score - EASY_MOVE_MARGIN;
+        let us = root.side_to_move();
+
+        for &mv in &self.legal_moves(root, 0) {
+            if mv == best {
+                continue;
+            }
+
+            self.played[0] = Some((Piece::new(mv.piece(), us), mv.to()));
+            let score = -self.with_move(root, mv, |search, child| {
+                search.negamax::<false>(
+                    child,
+                    depth as i32 / 2,
+                    1,
+                    -threshold,
+                    -threshold + 1,
+                    &mut Pv::new(),
+                )
+            });
+
+            if self.stopped || score >= threshold {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Searches `board` with the window `alpha..beta`, which must be a zero window unless `PV`
+    /// is set for a node on the principal variation, whose exact score is wanted.
+    fn negamax<const PV: bool>(
+        &mut self,
+        board: &mut Board,
+        mut depth: i32,
+        ply: usize,
+        mut alpha: Score,
+        beta: Score,
+        pv: &mut Pv,
+    ) -> Score {
+        debug_assert!(PV || alpha + 1 == beta);
+        pv.clear();
+
+        if self.should_stop() {
+            return Score::DRAW;
+        }
+
+        if ply > 0 && self.is_draw(board, ply) {
+            return Score::DRAW;
+        }
+
+        if ply >= MAX_PLY {
+            return self.thread.eval_cache.evaluate(board);
+        }
+
+        self.keys[ply] = board.key();
+
+        // A side that can return to an earlier position in one move can hold the draw, so it
+        // scores at least that much even before the repetition is on the board.
+        if ply > 0
+            && alpha < Score::DRAW
+            && cuckoo::has_upcoming_repetition(board, &self.history, &self.keys[..ply])
+        {
+            alpha = Score::DRAW;
+
+            if alpha >= beta {
+                return alpha;
+            }
+        }
+
+        // The grandchildren are siblings of each other but not of earlier grandchildren, whose
+        // killers would be stale.
+        self.thread.killers.clear_ply(ply + 2);
+
+        let in_check = board.in_check();
+        if in_check {
+            depth += 1;
+        }
+
+        if depth <= 0 {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+
+        self.nodes += 1;
+
+        // A stored move that cannot be played here gives away an entry for another position
+        // whose key collided with this one, so none of the entry is trusted.
+        let entry = self.tt.probe(board.key());
+        let tt_move = entry.and_then(|entry| entry.mv.unpack(board));
+        let entry = entry.filter(|entry| entry.mv.is_none() || tt_move.is_some());
+
+        self.stats.tt_probes += 1;
+        self.stats.tt_hits += entry.is_some() as u64;
+
+        // PV nodes search on rather than cut off, so that the principal variation is complete.
+        let usable = |entry: &Entry| {
+            !PV && entry.depth as i32 >= depth && !near_fifty_move_rule(board, entry.depth as i32)
+        };
+
+        if let Some(entry) = entry.filter(usable) {
+            let score = entry.score.from_node(ply);
+
+            if entry.bound.cuts_off(score, alpha, beta) {
+                self.stats.tt_cutoffs += 1;
+                return score;
+            }
+        }
+
+        let moves = self.legal_moves(board, ply);
+        if moves.is_empty() {
+            return if in_check {
+                Score::mated_in(ply)
+            } else {
+                Score::DRAW
+            };
+        }
+
+        let eval = (!in_check).then(|| self.static_eval(board));
+        self.evals[ply] = eval;
+
+        // A position is improving if its evaluation is better than the last time this side
+        // was to move, in which case quiet moves are more likely to raise alpha.
+        let improving = match (eval, ply.checked_sub(2).and_then(|ply| self.evals[ply])) {
+            (Some(eval), Some(previous)) => eval > previous,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+
+        let prunable = ply > 0 && !alpha.is_mate() && !beta.is_mate();
+        let mut futile = false;
+
+        if let Some(eval) = eval.filter(|_| prunable) {
+            if depth <= RAZOR_DEPTH && !improving && eval + RAZOR_MARGIN * depth <= alpha {
+                let score = self.quiescence(board, ply, alpha, alpha + 1);
+
+                if score <= alpha {
+                    self.stats.razored += 1;
+                    return score;
+                }
+            }
+
+            let margin = FUTILITY_MARGIN * depth + if improving { FUTILITY_IMPROVING } else { 0 };
+            futile = depth <= FUTILITY_DEPTH && eval + margin <= alpha;
+        }
+
+        let original_alpha = alpha;
+        let mut best_score = -Score::INFINITE;
+        let mut best_move = None;
+        let mut child_pv = Pv::new();
+
+        let previous = self.previous(ply);
+        let picker = match ply {
+            0 => MovePicker::in_order(self.root_moves.order()),
+            _ => MovePicker::new(
+                &*board,
+                moves,
+                tt_move,
+                self.thread.killers.get(ply),
+                &self.thread.history,
+                previous,
+            ),
+        };
+        let mut quiets_tried = MoveList::new();
+        let mut captures_tried = MoveList::new();
+
+        for (index, mv) in picker.enumerate() {
+            if ply == 0 && self.start.elapsed() >= CURRMOVE_DELAY {
+                (self.progress)(SearchProgress::CurrMove {
+                    depth: self.depth,
+                    mv,
+                    number: index + 1,
+                });
+            }
+
+            // At least one move is always searched so that there is a score to return.
+            if best_move.is_some() && prunable && !in_check && !board.gives_check(mv) {
+                // Quiet moves cannot make up the futility margin.
+                if futile && mv.is_quiet() {
+                    self.stats.futility_pruned += 1;
+                    continue;
+                }
+
+                let threshold = if mv.is_quiet() {
+                    -SEE_QUIET_MARGIN * depth
+                } else {
+                    -SEE_CAPTURE_MARGIN * depth * depth
+                };
+
+                if depth <= SEE_PRUNING_DEPTH && !see(board, mv, threshold) {
+                    self.stats.see_pruned += 1;
+                    continue;
+                }
+            }
+
+            self.played[ply] = Some((Piece::new(mv.piece(), board.side_to_move()), mv.to()));
+            // The first move of a PV node is expected to be best and gets the full window; the
+            // others only have to be shown no better than it.
+            let first = best_move.is_none();
+            let nodes = self.nodes;
+            let score = -self.with_move(board, mv, |search, child| {
+                search.tt.prefetch(child.key());
+
+                if !PV || first {
+                    return search.negamax::<PV>(
+                        child,
+                        depth - 1,
+                        ply + 1,
+                        -beta,
+                        -alpha,
+                        &mut child_pv,
+                    );
+                }
+
+                search.stats.scouts += 1;
+                let score = -search.negamax::<false>(
+                    child,
+                    depth - 1,
+                    ply + 1,
+                    -alpha - 1,
+                    -alpha,
+                    &mut child_pv,
+                );
+
+                if score <= alpha || score >= beta || search.stopped {
+                    return -score;
+                }
+
+                search.stats.researches += 1;
+                search.negamax::<true>(child, depth - 1, ply + 1, -beta, -alpha, &mut child_pv)
+            });
+
+            if ply == 0 {
+                self.root_moves.add_nodes(mv, self.nodes - nodes);
+            }
+
+            if self.stopped {
+                return Score::DRAW;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(mv);
+
+                if score > alpha {
+                    alpha = score;
+
+                    pv.push_front(mv, &child_pv);
+
+                    if ply == 0 {
+                        self.root_moves.set_score(mv, score, pv);
+                        (self.progress)(SearchProgress::PvChanged {
+                            depth: self.depth,
+                            score,
+                            pv: pv.clone(),
+                        });
+                    }
+
+                    if alpha >= beta {
+                        if mv.is_quiet() {
+                            self.thread.killers.add(ply, mv);
+                        }
+
+                        self.reward_cutoff(
+                            board,
+                            mv,
+                            depth,
+                            previous,
+                            &quiets_tried,
+                            &captures_tried,
+                        );
+                        break;
+                    }
+                }
+            }
+
+            if mv.is_quiet() {
+                quiets_tried.push(mv);
+            } else if mv.is_capture() {
+                captures_tried.push(mv);
+            }
+        }
+
+        let bound = if best_score >= beta {
+            Bound::Lower
+        } else if best_score > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+
+        // Only quiet results teach the correction history: a capture's score says more about
+        // the exchange than about the position, and a bound on the wrong side of the
+        // evaluation says nothing about how far off it was.
+        if let Some(eval) = eval {
+            let quiet = best_move.is_none_or(|mv| mv.is_quiet());
+            let informative = match bound {
+                Bound::Exact => true,
+                Bound::Lower => best_score > eval,
+                Bound::Upper => best_score < eval,
+            };
+
+            if quiet && informative && !best_score.is_mate() {
+                self.thread
+                    .correction
+                    .update(board, depth, eval, best_score);
+            }
+        }
+
+        // A score that depends on the halfmove clock is wrong for the same position reached
+        // with a different clock, so only the move is kept for ordering.
+        let stored_depth = if near_fifty_move_rule(board, depth) {
+            0
+        } else {
+            depth as u8
+        };
+
+        self.tt.store(Entry {
+            key: board.key(),
+            mv: best_move.map(Move::pack).unwrap_or_default(),
+            score: best_score.to_node(ply),
+            depth: stored_depth,
+            bound,
+            pv: PV,
+        });
+
+        best_score
+    }
+
+    /// Resolves captures and promotions until the position is quiet, so that the static
+    /// evaluation is never taken in the middle of an exchange.
+    fn quiescence(
+        &mut self,
+        board: &mut Board,
+        ply: usize,
+        mut alpha: Score,
+        beta: Score,
+    ) -> Score {
+        if self.should_stop() {
+            return Score::DRAW;
+        }
+
+        self.nodes += 1;
+
+        if ply >= MAX_PLY {
+            return self.thread.eval_cache.evaluate(board);
+        }
+
+        let in_check = board.in_check();
+        let mut best_score = -Score::INFINITE;
+
+        if !in_check {
+            let (eval, complete) = self.thread.eval_cache.evaluate_lazily(
+                board,
+                alpha,
+                beta,
+                self.options.lazy_eval_margin,
+            );
+            self.stats.lazy_evals += u64::from(!complete);
+            best_score = self.thread.correction.correct(board, eval);
+
+            if best_score >= beta {
+                return best_score;
+            }
+
+            alpha = alpha.max(best_score);
+        }
+
+        let moves = movegen::legal_moves(board);
+        if moves.is_empty() && in_check {
+            return Score::mated_in(ply);
+        }
+
+        let previous = self.previous(ply);
+
+        let picker = MovePicker::new(
+            &*board,
+            moves,
+            None,
+            [None; 2],
+            &self.thread.history,
+            previous,
+        );
+
+        for mv in picker {
+            // Captures that lose material are not worth resolving when standing pat is allowed.
+            if !in_check && (mv.is_quiet() || !see(board, mv, 0)) {
+                continue;
+            }
+
+            self.played[ply] = Some((Piece::new(mv.piece(), board.side_to_move()), mv.to()));
+            let score = -self.with_move(board, mv, |search, child| {
+                search.quiescence(child, ply + 1, -beta, -alpha)
+            });
+
+            if self.stopped {
+                return Score::DRAW;
+            }
+
+            if score > best_score {
+                best_score = score;
+
+                if score > alpha {
+                    alpha = score;
+
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+
+        best_score
+    }
+
+    /// Calls `search` with the position after `mv`, either on a copy of `board` or by making the
+    /// move on `board` itself and unmaking it afterwards.
+    fn with_move<T>(
+        &mut self,
+        board: &mut Board,
+        mv: Move,
+        search: impl FnOnce(&mut Self, &mut Board) -> T,
+    ) -> T {
+        if self.options.copy_make {
+            let mut child = board.clone();
+            child.make_move(mv);
+
+            return search(self, &mut child);
+        }
+
+        let state = board.state();
+        board.make_move(mv);
+
+        let result = search(self, board);
+        board.unmake_move(mv, state);
+
+        result
+    }
+
+    /// Returns the move that led to the node at `ply`.
+    fn previous(&self, ply: usize) -> Option<PieceTo> {
+        ply.checked_sub(1).and_then(|ply| self.played[ply])
+    }
+
+    /// Updates the history tables after `mv` caused a beta cutoff, rewarding it and punishing
+    /// the moves of the same kind that were searched before it and failed to.
+    fn reward_cutoff(
+        &mut self,
+        board: &Board,
+        mv: Move,
+        depth: i32,
+        previous: Option<PieceTo>,
+        quiets_tried: &[Move],
+        captures_tried: &[Move],
+    ) {
+        let history = &mut self.thread.history;
+        let colour = board.side_to_move();
+        let bonus = history::bonus(depth);
+
+        if mv.is_quiet() {
+            history.update_quiet(colour, mv, previous, bonus);
+
+            for &quiet in quiets_tried {
+                history.update_quiet(colour, quiet, previous, -bonus);
+            }
+        } else if mv.is_capture() {
+            history.update_capture(colour, mv, bonus);
+        }
+
+        for &capture in captures_tried {
+            history.update_capture(colour, capture, -bonus);
+        }
+    }
+
+    /// Returns the legal moves of `board`, narrowed at the root to the moves the limits allow.
+    fn legal_moves(&self, board: &Board, ply: usize) -> MoveList {
+        let moves = movegen::legal_moves(board);
+        if ply > 0 || self.limits.search_moves.is_empty() {
+            return moves;
+        }
+
+        let mut allowed = MoveList::default();
+        for &mv in moves.iter() {
+            if self.limits.search_moves.contains(&mv) {
+                allowed.push(mv);
+            }
+        }
+
+        match allowed.is_empty() {
+            true => moves,
+            false => allowed,
+        }
+    }
+
+    /// Returns whether the position is drawn by the fifty-move rule or by repetition.
+    ///
+    /// Repeating a position from the game takes a threefold repetition, as in play. Repeating
+    /// one reached after the root already counts as a draw, since whichever side wanted the
+    /// repetition can simply keep repeating.
+    fn is_draw(&self, board: &Board, ply: usize) -> bool {
+        if board.halfmove_clock() >= 100 {
+            return true;
+        }
+
+        let distances = self.keys[..ply]
+            .iter()
+            .rev()
+            .chain(self.history.iter().rev())
+            .take(board.halfmove_clock() as usize)
+            .enumerate()
+            .skip(1)
+            .step_by(2)
+            .filter(|&(_, &key)| key == board.key())
+            .map(|(index, _)| index + 1);
+
+        let mut repetitions = 0;
+
+        for distance in distances {
+            if distance < ply {
+                return true;
+            }
+
+            repetitions += 1;
+        }
+
+        repetitions >= 2
+    }
+
+    /// Returns the static evaluation of `board`, corrected by the correction history.
+    fn static_eval(&mut self, board: &Board) -> Score {
+        let eval = self.thread.eval_cache.evaluate(board);
+        self.thread.correction.correct(board, eval)
+    }
+
+    fn should_stop(&mut self) -> bool {
+        if self.stopped {
+            return true;
+        }
+
+        if self.nodes >= self.next_milestone {
+            self.next_milestone += NODE_MILESTONE;
+
+            (self.progress)(SearchProgress::Nodes {
+                nodes: self.nodes,
+                elapsed: self.start.elapsed(),
+                hashfull: self.tt.hashfull(),
+            });
+        }
+
+        if self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes) {
+            self.stopped = true;
+        }
+
+        // The clock and the stop flag are looked at once every interval of nodes. The clock
+        // never cuts the first iteration short, so that there is a searched move to play
+        // however little time is left, but a stop or a node limit still does.
+        if (self.nodes + 1).is_multiple_of(self.options.check_interval.max(1)) {
+            self.stopped |=
+                (self.depth > 1 && self.out_of_time()) || self.stop.load(Ordering::Relaxed);
+        }
+
+        self.stopped
+    }
+
+    fn out_of_time(&mut self) -> bool {
+        !self.pondering()
+            && self
+                .limits
+                .movetime
+                .is_some_and(|movetime| self.clock.elapsed() >= movetime)
+    }
+
+    /// Returns whether the search is still pondering, starting the clock for the time limits
+    /// once it has stopped.
+    fn pondering(&mut self) -> bool {
+        if self.pondering
+            && self
+                .ponder
+                .is_some_and(|ponder| !ponder.load(Ordering::Relaxed))
+        {
+            self.pondering = false;
+            self.clock = Instant::now();
+        }
+
+        self.pondering
+    }
+}
+
+/// Returns whether the fifty-move rule can come into play within `depth` plies of `board`, so
+/// that a search score depends on the halfmove clock as well as the position.
+fn near_fifty_move_rule(board: &Board, depth: i32) -> bool {
+    board.halfmove_clock() as i32 + depth >= 100
+}
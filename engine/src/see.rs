@@ -0,0 +1,127 @@
+//! Static exchange evaluation.
+//!
+//! Plays out the captures on a move's destination square, least valuable attacker first, to
+//! estimate the material it wins or loses without searching. Pins are ignored.
+
+use gambit::attacks;
+use gambit::{Bitboard, Board, Move, PieceKind, Square};
+
+use crate::eval::PIECE_VALUES;
+
+/// Returns whether `mv` wins at least `threshold` centipawns of material once every exchange
+/// on its destination square has been played out.
+///
+/// Either side may stop capturing when continuing would lose material, so this is a lower
+/// bound for the side moving and an upper bound for its opponent.
+pub fn see(board: &Board, mv: Move, threshold: i32) -> bool {
+    if mv.is_castle() {
+        return threshold <= 0;
+    }
+
+    let (from, to) = (mv.from(), mv.to());
+
+    let mut swap = mv.captured().map_or(0, value) - threshold;
+    if let Some(promotion) = mv.promotion() {
+        swap += value(promotion) - value(PieceKind::Pawn);
+    }
+
+    if swap < 0 {
+        return false;
+    }
+
+    // The piece now standing on the square is the next to be captured.
+    swap = value(mv.promotion().unwrap_or(mv.piece())) - swap;
+    if swap <= 0 {
+        return true;
+    }
+
+    let mut occupied = board.occupied() ^ Bitboard::from_square(from);
+    if mv.is_en_passant() {
+        occupied ^= Bitboard::from_square(Square::new(to.file(), from.rank()));
+    }
+
+    let queens = board.pieces(PieceKind::Queen);
+    let diagonal = board.pieces(PieceKind::Bishop) | queens;
+    let orthogonal = board.pieces(PieceKind::Rook) | queens;
+
+    let mut attackers = board.attackers_to(to, occupied);
+    let mut side = board.side_to_move();
+    let mut wins = true;
+
+    loop {
+        side = !side;
+        attackers &= occupied;
+
+        let ours = attackers & board.colour(side);
+        if ours.is_empty() {
+            break;
+        }
+
+        wins = !wins;
+
+        let Some(kind) = PieceKind::ALL
+            .into_iter()
+            .find(|&kind| !(ours & board.pieces(kind)).is_empty())
+        else {
+            break;
+        };
+
+        if kind == PieceKind::King {
+            // The king may only recapture if the square is no longer defended.
+            return if (attackers & !board.colour(side)).is_empty() {
+                wins
+            } else {
+                !wins
+            };
+        }
+
+        swap = value(kind) - swap;
+        if swap < wins as i32 {
+            break;
+        }
+
+        if let Some(square) = (ours & board.pieces(kind)).lsb() {
+            occupied ^= Bitboard::from_square(square);
+        }
+
+        // Removing the capturer may uncover a slider behind it.
+        if matches!(kind, PieceKind::Pawn | PieceKind::Bishop | PieceKind::Queen) {
+            attackers |= attacks::bishop_attacks(to, occupied) & diagonal;
+        }
+        if matches!(kind, PieceKind::Rook | PieceKind::Queen) {
+            attackers |= attacks::rook_attacks(to, occupied) & orthogonal;
+        }
+    }
+
+    wins
+}
+
+/// Returns the material `mv` wins, or loses if negative, once every exchange on its
+/// destination square has been played out.
+///
+/// The value is found by bisecting [`see`] between losing the moving piece and winning the
+/// captured piece and the promotion, so it is meant for debugging rather than the search.
+pub fn see_value(board: &Board, mv: Move) -> i32 {
+    let promotion = mv
+        .promotion()
+        .map_or(0, |kind| value(kind) - value(PieceKind::Pawn));
+    let mut low = -value(mv.promotion().unwrap_or(mv.piece()));
+    let mut high = mv.captured().map_or(0, value) + promotion;
+
+    // `see` holds at `low` and is decided within the bounds, so the answer stays in them.
+    while low < high {
+        let middle = low + (high - low + 1) / 2;
+
+        if see(board, mv, middle) {
+            low = middle;
+        } else {
+            high = middle - 1;
+        }
+    }
+
+    low
+}
+
+fn value(kind: PieceKind) -> i32 {
+    PIECE_VALUES[kind.index()]
+}
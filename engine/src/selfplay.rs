@@ -0,0 +1,591 @@
+//! Games between two engine configurations played in one process, and the statistics that
+//! tell whether one of them is stronger.
+//!
+//! Each game starts from an opening of a suite read by [`crate::openings`], with the engines
+//! moving on a clock or under fixed limits per move. Games end by the rules of chess, or are
+//! adjudicated once both engines agree on the result for a few moves, which saves playing out
+//! positions whose result is already clear. Positions without the material to force mate are
+//! adjudicated as draws, standing in for a tablebase.
+//!
+//! A match is scored from the first player's side and can be cut short by a sequential
+//! probability ratio test, which stops as soon as the games played are enough to tell whether
+//! the first player is stronger by a given margin.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use gambit::pgn::{self, Annotation};
+use gambit::{movegen, Board, Colour, Move};
+
+use crate::material;
+use crate::uci::{self, DEFAULT_EASY_MOVE_PERCENT, STABLE_MOVE_PERCENT};
+use crate::{Engine, EngineOptions, Score, SearchLimits};
+
+/// An engine configuration taking part in a match.
+#[derive(Clone, Debug)]
+pub struct Player {
+    pub name: String,
+    pub options: EngineOptions,
+}
+
+/// How long the engines may think.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimeControl {
+    /// Each side has `base` for the game and gains `increment` after every move. A side that
+    /// runs out of time loses.
+    Clock { base: Duration, increment: Duration },
+    /// Every move is searched under the same limits.
+    PerMove(SearchLimits),
+}
+
+/// When a game is decided before it ends by the rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Adjudication {
+    /// A game is won once both engines have scored it at least this many centipawns for the
+    /// same side over their last `win_moves` moves each.
+    pub win_score: i32,
+    pub win_moves: usize,
+    /// A game is drawn once both engines have scored it within this many centipawns of equal
+    /// over their last `draw_moves` moves each, from move `draw_from` on.
+    pub draw_score: i32,
+    pub draw_moves: usize,
+    pub draw_from: u16,
+    /// A game still going after this many moves by each side is drawn.
+    pub max_moves: usize,
+}
+
+impl Default for Adjudication {
+    fn default() -> Adjudication {
+        Adjudication {
+            win_score: 1000,
+            win_moves: 4,
+            draw_score: 10,
+            draw_moves: 8,
+            draw_from: 40,
+            max_moves: 200,
+        }
+    }
+}
+
+/// How a game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+impl Outcome {
+    /// Returns the result as PGN writes it.
+    pub fn to_pgn(self) -> &'static str {
+        match self {
+            Outcome::WhiteWins => "1-0",
+            Outcome::BlackWins => "0-1",
+            Outcome::Draw => "1/2-1/2",
+        }
+    }
+}
+
+/// Why a game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Termination {
+    Checkmate,
+    Stalemate,
+    Repetition,
+    FiftyMoves,
+    /// Neither side has the material to force mate.
+    Material,
+    /// Both engines agreed on the result.
+    Adjudication,
+    /// The game reached [`Adjudication::max_moves`].
+    MoveLimit,
+    TimeForfeit,
+}
+
+impl Termination {
+    /// Describes the termination in a few words, such as `checkmate`.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Termination::Checkmate => "checkmate",
+            Termination::Stalemate => "stalemate",
+            Termination::Repetition => "threefold repetition",
+            Termination::FiftyMoves => "fifty-move rule",
+            Termination::Material => "insufficient material",
+            Termination::Adjudication => "adjudication",
+            Termination::MoveLimit => "move limit",
+            Termination::TimeForfeit => "time forfeit",
+        }
+    }
+}
+
+/// A move of a game and what the engine that played it thought of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlayedMove {
+    pub mv: Move,
+    /// The score from the side of the engine that played the move.
+    pub score: Score,
+    pub depth: u8,
+    pub time: Duration,
+}
+
+/// A finished game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameRecord {
+    pub start: Board,
+    pub moves: Vec<PlayedMove>,
+    pub outcome: Outcome,
+    pub termination: Termination,
+}
+
+impl GameRecord {
+    /// Writes the game as PGN, with the score, depth and time of every move in a comment and
+    /// the termination after the last.
+    pub fn to_pgn(&self, white: &str, black: &str, round: usize) -> String {
+        let round = round.to_string();
+        let fen = self.start.to_fen();
+        let termination = match self.termination {
+            Termination::Adjudication | Termination::Material | Termination::MoveLimit => {
+                "adjudication"
+            }
+            Termination::TimeForfeit => "time forfeit",
+            _ => "normal",
+        };
+
+        let mut tags = vec![
+            ("Event", "Gambit match"),
+            ("Site", "?"),
+            ("Date", "????.??.??"),
+            ("Round", round.as_str()),
+            ("White", white),
+            ("Black", black),
+            ("Result", self.outcome.to_pgn()),
+        ];
+        if self.start != Board::default() {
+            tags.extend([("SetUp", "1"), ("FEN", fen.as_str())]);
+        }
+        tags.push(("Termination", termination));
+
+        let last = self.moves.len().saturating_sub(1);
+        let moves = self.moves.iter().enumerate().map(|(ply, played)| {
+            let mut comment = format!(
+                "{}/{} {:.3}s",
+                score_text(played.score),
+                played.depth,
+                played.time.as_secs_f64()
+            );
+            if ply == last {
+                comment.push_str(&format!(", {}", self.termination.describe()));
+            }
+
+            (
+                played.mv,
+                Annotation {
+                    nag: None,
+                    comment: Some(comment),
+                },
+            )
+        });
+
+        pgn::write_game(tags, &self.start, moves, self.outcome.to_pgn())
+    }
+}
+
+/// Formats a score in pawns with a sign, or `+M` or `-M` and the moves to mate.
+fn score_text(score: Score) -> String {
+    match score.mate_moves() {
+        Some(moves) if moves < 0 => format!("-M{}", -moves),
+        Some(moves) => format!("+M{moves}"),
+        None => format!("{:+.2}", f64::from(score.get()) / 100.0),
+    }
+}
+
+/// Plays a game from `start` between `white` and `black`, which forget their earlier games
+/// first.
+pub fn play_game(
+    white: &mut Engine,
+    black: &mut Engine,
+    start: &Board,
+    time_control: &TimeControl,
+    adjudication: &Adjudication,
+) -> GameRecord {
+    white.new_game();
+    black.new_game();
+
+    let fen = start.to_fen();
+    let mut board = start.clone();
+    let mut keys = vec![board.key()];
+    let mut uci_moves = Vec::new();
+    let mut moves = Vec::new();
+    let mut clocks = match time_control {
+        TimeControl::Clock { base, .. } => [*base; 2],
+        TimeControl::PerMove(_) => [Duration::MAX; 2],
+    };
+
+    let (outcome, termination) = loop {
+        if let Some(end) = rules_ending(&board, &keys) {
+            break end;
+        }
+        if let Some(end) = adjudicate(&board, &moves, adjudication) {
+            break end;
+        }
+
+        let side = board.side_to_move();
+        let engine = match side {
+            Colour::White => &mut *white,
+            Colour::Black => &mut *black,
+        };
+        let clock = &mut clocks[side.index()];
+
+        let limits = match time_control {
+            TimeControl::Clock { increment, .. } => {
+                let movetime = uci::allocate_time(*clock, *increment, None);
+
+                SearchLimits {
+                    movetime: Some(movetime),
+                    easy_movetime: Some(movetime * DEFAULT_EASY_MOVE_PERCENT / 100),
+                    stable_movetime: Some(movetime * STABLE_MOVE_PERCENT / 100),
+                    ..SearchLimits::default()
+                }
+            }
+            TimeControl::PerMove(limits) => limits.clone(),
+        };
+
+        // The moves were played on the same position, so they can be played again.
+        let _ = engine.set_position(&fen, &uci_moves);
+
+        let started = Instant::now();
+        let result = engine.search(limits);
+        let time = started.elapsed();
+
+        if let TimeControl::Clock { increment, .. } = time_control {
+            if time > *clock {
+                break (win_for(!side), Termination::TimeForfeit);
+            }
+
+            *clock = *clock - time + *increment;
+        }
+
+        let mv = result
+            .best_move
+            .expect("a position that is not over has a legal move");
+
+        moves.push(PlayedMove {
+            mv,
+            score: result.score,
+            depth: result.depth,
+            time,
+        });
+        uci_moves.push(mv.to_string());
+        board.make_move(mv);
+        keys.push(board.key());
+    };
+
+    GameRecord {
+        start: start.clone(),
+        moves,
+        outcome,
+        termination,
+    }
+}
+
+fn win_for(colour: Colour) -> Outcome {
+    match colour {
+        Colour::White => Outcome::WhiteWins,
+        Colour::Black => Outcome::BlackWins,
+    }
+}
+
+/// Returns how the game ends in `board`, reached through the positions with `keys`, if the
+/// rules end it there.
+fn rules_ending(board: &Board, keys: &[u64]) -> Option<(Outcome, Termination)> {
+    if movegen::legal_moves(board).is_empty() {
+        return Some(match board.in_check() {
+            true => (win_for(!board.side_to_move()), Termination::Checkmate),
+            false => (Outcome::Draw, Termination::Stalemate),
+        });
+    }
+
+    if board.halfmove_clock() >= 100 {
+        return Some((Outcome::Draw, Termination::FiftyMoves));
+    }
+
+    let repetitions = keys
+        .iter()
+        .rev()
+        .take(board.halfmove_clock() as usize + 1)
+        .filter(|&&key| key == board.key())
+        .count();
+    if repetitions >= 3 {
+        return Some((Outcome::Draw, Termination::Repetition));
+    }
+
+    material::is_drawn(board).then_some((Outcome::Draw, Termination::Material))
+}
+
+/// Returns the adjudicated result of a game that has reached `board` through `moves`, if
+/// the engines' scores agree on one.
+fn adjudicate(
+    board: &Board,
+    moves: &[PlayedMove],
+    adjudication: &Adjudication,
+) -> Option<(Outcome, Termination)> {
+    if moves.len() >= 2 * adjudication.max_moves {
+        return Some((Outcome::Draw, Termination::MoveLimit));
+    }
+
+    // The scores of the last plies from White's side, the latest move first.
+    let white_scores = |plies: usize| {
+        let side = board.side_to_move();
+
+        moves
+            .iter()
+            .rev()
+            .take(plies)
+            .enumerate()
+            .map(move |(index, played)| {
+                // The latest move was played by the side not to move.
+                let white = (side == Colour::White) == (index % 2 == 1);
+                match white {
+                    true => played.score.get(),
+                    false => -played.score.get(),
+                }
+            })
+    };
+
+    let plies = 2 * adjudication.win_moves;
+    if plies > 0 && moves.len() >= plies {
+        if white_scores(plies).all(|score| score >= adjudication.win_score) {
+            return Some((Outcome::WhiteWins, Termination::Adjudication));
+        }
+        if white_scores(plies).all(|score| score <= -adjudication.win_score) {
+            return Some((Outcome::BlackWins, Termination::Adjudication));
+        }
+    }
+
+    let plies = 2 * adjudication.draw_moves;
+    if plies > 0
+        && moves.len() >= plies
+        && board.fullmove_number() >= adjudication.draw_from
+        && white_scores(plies).all(|score| score.abs() <= adjudication.draw_score)
+    {
+        return Some((Outcome::Draw, Termination::Adjudication));
+    }
+
+    None
+}
+
+/// The games of a match won, drawn and lost by its first player.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MatchScore {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl MatchScore {
+    pub fn games(self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Counts a game that ended in `outcome` with the first player on White or not.
+    pub fn add(&mut self, outcome: Outcome, first_is_white: bool) {
+        match (outcome, first_is_white) {
+            (Outcome::Draw, _) => self.draws += 1,
+            (Outcome::WhiteWins, true) | (Outcome::BlackWins, false) => self.wins += 1,
+            _ => self.losses += 1,
+        }
+    }
+
+    /// Returns the share of the points the first player scored, and the variance of the
+    /// points of a single game.
+    fn mean_and_variance(self) -> Option<(f64, f64)> {
+        let games = f64::from(self.games());
+        if games == 0.0 {
+            return None;
+        }
+
+        let (wins, draws, losses) = (
+            f64::from(self.wins) / games,
+            f64::from(self.draws) / games,
+            f64::from(self.losses) / games,
+        );
+        let mean = wins + draws / 2.0;
+        let variance = wins * (1.0 - mean).powi(2)
+            + draws * (0.5 - mean).powi(2)
+            + losses * (0.0 - mean).powi(2);
+
+        Some((mean, variance))
+    }
+
+    /// Returns the Elo difference the score suggests in the first player's favour, with the
+    /// margin of its 95% confidence interval, or `None` while either is unbounded.
+    pub fn elo(self) -> Option<(f64, f64)> {
+        let (mean, variance) = self.mean_and_variance()?;
+        let deviation = (variance / f64::from(self.games())).sqrt();
+
+        let low = elo_difference(mean - 1.96 * deviation)?;
+        let high = elo_difference(mean + 1.96 * deviation)?;
+
+        Some((elo_difference(mean)?, (high - low) / 2.0))
+    }
+
+    /// Returns the log-likelihood ratio of the first player being `elo1` rather than `elo0`
+    /// Elo stronger, by the normal approximation to the trinomial distribution of results.
+    pub fn llr(self, elo0: f64, elo1: f64) -> f64 {
+        let Some((mean, variance)) = self.mean_and_variance() else {
+            return 0.0;
+        };
+        if variance == 0.0 {
+            return 0.0;
+        }
+
+        let (score0, score1) = (expected_score(elo0), expected_score(elo1));
+
+        f64::from(self.games()) * (score1 - score0) * (2.0 * mean - score0 - score1)
+            / (2.0 * variance)
+    }
+}
+
+/// Returns the expected score of a player `elo` stronger than its opponent.
+fn expected_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/// Returns the Elo difference a share of the points corresponds to, if it is finite.
+fn elo_difference(score: f64) -> Option<f64> {
+    (score > 0.0 && score < 1.0).then(|| -400.0 * (1.0 / score - 1.0).log10())
+}
+
+/// A sequential probability ratio test of whether the first player is `elo1` rather than
+/// `elo0` Elo stronger, wrongly accepting the first with probability `alpha` and the second
+/// with probability `beta`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Sprt {
+    /// Creates a test between `elo0` and `elo1` with the usual error rates of 5%.
+    pub fn new(elo0: f64, elo1: f64) -> Sprt {
+        Sprt {
+            elo0,
+            elo1,
+            alpha: 0.05,
+            beta: 0.05,
+        }
+    }
+
+    /// Returns the log-likelihood ratios below which `elo0` and above which `elo1` is
+    /// accepted.
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// Returns `Some(true)` once `score` accepts `elo1`, `Some(false)` once it accepts
+    /// `elo0`, and `None` while the test goes on.
+    pub fn decide(&self, score: MatchScore) -> Option<bool> {
+        let llr = score.llr(self.elo0, self.elo1);
+        let (lower, upper) = self.bounds();
+
+        if llr >= upper {
+            Some(true)
+        } else if llr <= lower {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// How a match is played.
+#[derive(Clone, Debug)]
+pub struct MatchSettings {
+    pub games: usize,
+    pub time_control: TimeControl,
+    pub adjudication: Adjudication,
+    /// The number of games played at the same time.
+    pub concurrency: usize,
+    /// A test that ends the match once it is decided.
+    pub sprt: Option<Sprt>,
+}
+
+/// Plays a match between `players`, passing each game to `on_game` as it finishes, with its
+/// number from zero and the score so far, and returns the final score.
+///
+/// Games are played in pairs from the same opening, the first player having White in the
+/// first game of each pair and Black in the second, so that neither benefits from an opening
+/// that favours one side. Pair `n` starts from opening `n` of `openings`, going round again
+/// when they run out, or from the standard position when there are none. Games still being
+/// played when the test of [`MatchSettings::sprt`] is decided are finished and counted.
+pub fn play_match(
+    players: [&Player; 2],
+    openings: &[Board],
+    settings: &MatchSettings,
+    mut on_game: impl FnMut(usize, &GameRecord, MatchScore),
+) -> MatchScore {
+    let next = AtomicUsize::new(0);
+    let decided = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+    let mut score = MatchScore::default();
+
+    thread::scope(|scope| {
+        for _ in 0..settings.concurrency.clamp(1, settings.games.max(1)) {
+            let sender = sender.clone();
+            let (next, decided) = (&next, &decided);
+
+            scope.spawn(move || {
+                let mut first = Engine::new(players[0].options.clone());
+                let mut second = Engine::new(players[1].options.clone());
+
+                while !decided.load(Ordering::Relaxed) {
+                    let game = next.fetch_add(1, Ordering::Relaxed);
+                    if game >= settings.games {
+                        break;
+                    }
+
+                    let start = match openings.is_empty() {
+                        true => Board::default(),
+                        false => openings[game / 2 % openings.len()].clone(),
+                    };
+                    let (white, black) = match game % 2 {
+                        0 => (&mut first, &mut second),
+                        _ => (&mut second, &mut first),
+                    };
+                    let record = play_game(
+                        white,
+                        black,
+                        &start,
+                        &settings.time_control,
+                        &settings.adjudication,
+                    );
+
+                    if sender.send((game, record)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        for (game, record) in receiver {
+            score.add(record.outcome, game % 2 == 0);
+            on_game(game, &record, score);
+
+            if let Some(sprt) = settings.sprt {
+                if sprt.decide(score).is_some() {
+                    decided.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+    });
+
+    score
+}
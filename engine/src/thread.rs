@@ -0,0 +1,156 @@
+//! State owned by a single search thread that outlives individual searches.
+
+use std::sync::Arc;
+
+use gambit::Board;
+
+use crate::correction::CorrectionHistory;
+use crate::eval;
+use crate::history::{History, Killers};
+use crate::material::MaterialTable;
+use crate::score::Score;
+use crate::weights::Weights;
+
+/// The number of entries in the eval cache, a power of two.
+const EVAL_CACHE_SIZE: usize = 1 << 16;
+
+/// A direct-mapped cache of static evaluations keyed by Zobrist key.
+///
+/// Unlike the transposition table it holds an entry for every evaluated position, including
+/// those the search never stores, and it is private to its thread so probing needs no locking.
+pub struct EvalCache {
+    entries: Box<[Option<(u64, i32)>]>,
+    material: MaterialTable,
+    weights: Arc<Weights>,
+}
+
+impl EvalCache {
+    /// Creates an empty cache evaluating with the built-in weights.
+    pub fn new() -> EvalCache {
+        EvalCache {
+            entries: vec![None; EVAL_CACHE_SIZE].into_boxed_slice(),
+            material: MaterialTable::new(),
+            weights: Arc::new(Weights::standard().clone()),
+        }
+    }
+
+    /// Evaluates with `weights` from now on, forgetting the evaluations made with others.
+    pub fn set_weights(&mut self, weights: Arc<Weights>) {
+        if weights != self.weights {
+            self.weights = weights;
+            self.entries.fill(None);
+        }
+    }
+
+    /// Returns the static evaluation of `board`, computing and caching it on a miss.
+    pub fn evaluate(&mut self, board: &Board) -> Score {
+        self.evaluate_lazily(board, -Score::INFINITE, Score::INFINITE, i32::MAX)
+            .0
+    }
+
+    /// Returns the static evaluation of `board` like [`EvalCache::evaluate`], except that a
+    /// miss may stop after the cheap stage as [`eval::evaluate_lazily`] does. Incomplete
+    /// evaluations are not cached.
+    ///
+    /// Returns the evaluation and whether it is complete.
+    pub fn evaluate_lazily(
+        &mut self,
+        board: &Board,
+        alpha: Score,
+        beta: Score,
+        margin: i32,
+    ) -> (Score, bool) {
+        let key = board.key();
+        let slot = &mut self.entries[key as usize & (EVAL_CACHE_SIZE - 1)];
+
+        // The halfmove clock is not part of the key, so its scaling is applied on the way out.
+        let (eval, complete) = match *slot {
+            Some((cached, eval)) if cached == key => (eval, true),
+            _ => {
+                let material = self.material.probe(board);
+                let (eval, complete) = eval::evaluate_lazily(
+                    board,
+                    &material,
+                    &self.weights,
+                    alpha.get(),
+                    beta.get(),
+                    margin,
+                );
+
+                if complete {
+                    *slot = Some((key, eval));
+                }
+
+                (eval, complete)
+            }
+        };
+
+        (
+            eval::scale_for_halfmove_clock(eval, board.halfmove_clock()),
+            complete,
+        )
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+        self.material.clear();
+    }
+}
+
+impl Default for EvalCache {
+    fn default() -> EvalCache {
+        EvalCache::new()
+    }
+}
+
+/// The furthest a new root can be from the previous one, in plies, for the killers to be
+/// carried over.
+const MAX_KILLER_SHIFT: usize = 8;
+
+/// The tables a search thread keeps between searches.
+#[derive(Default)]
+pub struct ThreadData {
+    pub eval_cache: EvalCache,
+    pub history: History,
+    pub correction: CorrectionHistory,
+    pub killers: Killers,
+    /// The key of the position the last search started from.
+    last_root: Option<u64>,
+}
+
+impl ThreadData {
+    /// Prepares for a search of the position with key `root`, reached through the positions
+    /// with keys `history`, oldest first.
+    ///
+    /// If the last search was of the same game a few plies back, its killers are shifted to
+    /// line up with the new root, so that the moves that refuted the expected replies are
+    /// tried early again. Otherwise the killers belong to another line and are dropped.
+    pub fn continue_from(&mut self, root: u64, history: &[u64]) {
+        let plies = match self.last_root {
+            Some(last) if last == root => Some(0),
+            Some(last) => history
+                .iter()
+                .rev()
+                .take(MAX_KILLER_SHIFT)
+                .position(|&key| key == last)
+                .map(|index| index + 1),
+            None => None,
+        };
+
+        match plies {
+            Some(plies) => self.killers.shift(plies),
+            None => self.killers.clear(),
+        }
+
+        self.last_root = Some(root);
+    }
+
+    /// Forgets everything, for the start of an unrelated game.
+    pub fn clear(&mut self) {
+        self.eval_cache.clear();
+        self.history.clear();
+        self.correction.clear();
+        self.killers.clear();
+        self.last_root = None;
+    }
+}
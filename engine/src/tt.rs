@@ -0,0 +1,154 @@
+//! The transposition table, caching search results by Zobrist key.
+
+use gambit::PackedMove;
+
+use crate::pages::PageBuffer;
+use crate::score::Score;
+
+/// How a stored score relates to the true score of the position, which follows from the type
+/// of node that stored it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is exact, as found at a PV node where some move raised alpha without
+    /// reaching beta.
+    Exact,
+    /// The search failed high at a cut node: the true score is at least the stored score.
+    Lower,
+    /// The search failed low at an all node: the true score is at most the stored score.
+    Upper,
+}
+
+impl Bound {
+    /// Returns whether a stored `score` with this bound settles a search with the window
+    /// `alpha..beta`: an exact score always does, a lower bound only at or above beta and an
+    /// upper bound only at or below alpha.
+    pub fn cuts_off(self, score: Score, alpha: Score, beta: Score) -> bool {
+        match self {
+            Bound::Exact => true,
+            Bound::Lower => score >= beta,
+            Bound::Upper => score <= alpha,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Entry {
+    pub key: u64,
+    pub mv: PackedMove,
+    pub score: Score,
+    pub depth: u8,
+    pub bound: Bound,
+    /// Whether the entry was stored by a node searched with an open window.
+    pub pv: bool,
+}
+
+/// An entry as it sits in the table, with the search that stored it.
+#[derive(Clone, Copy)]
+struct Slot {
+    entry: Entry,
+    generation: u8,
+}
+
+pub struct TranspositionTable {
+    entries: PageBuffer<Option<Slot>>,
+    /// Counts the searches the table has served, wrapping around.
+    generation: u8,
+}
+
+impl TranspositionTable {
+    /// Creates a table using about `megabytes` of memory, always with at least one entry,
+    /// backed by huge pages where the platform allows it if `large_pages` is set.
+    pub fn new(megabytes: usize, large_pages: bool) -> TranspositionTable {
+        TranspositionTable {
+            entries: PageBuffer::new(TranspositionTable::len(megabytes), None, large_pages),
+            generation: 0,
+        }
+    }
+
+    /// Creates a table like [`TranspositionTable::new`], returning `None` if the memory for it
+    /// cannot be allocated.
+    pub fn try_new(megabytes: usize, large_pages: bool) -> Option<TranspositionTable> {
+        Some(TranspositionTable {
+            entries: PageBuffer::try_new(TranspositionTable::len(megabytes), None, large_pages)?,
+            generation: 0,
+        })
+    }
+
+    /// Returns how many slots fit in `megabytes`, and at least one.
+    fn len(megabytes: usize) -> usize {
+        (megabytes.saturating_mul(1024 * 1024) / std::mem::size_of::<Option<Slot>>()).max(1)
+    }
+
+    /// Starts a new search, after which entries from earlier searches are no longer protected
+    /// from replacement.
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Returns whether the table is backed by huge pages.
+    pub fn large_pages(&self) -> bool {
+        self.entries.huge_pages()
+    }
+
+    fn index(&self, key: u64) -> usize {
+        // Maps the key onto the table without a modulo, see Lemire's "fast range".
+        ((key as u128 * self.entries.len() as u128) >> 64) as usize
+    }
+
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        self.entries[self.index(key)]
+            .map(|slot| slot.entry)
+            .filter(|entry| entry.key == key)
+    }
+
+    /// Hints to the CPU that the slot for `key` is about to be probed, so that the cache miss
+    /// overlaps with other work. Does nothing on targets without a prefetch instruction.
+    #[inline]
+    pub fn prefetch(&self, key: u64) {
+        #[cfg(target_arch = "x86_64")]
+        {
+            use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+
+            let slot = &self.entries[self.index(key)] as *const Option<Slot>;
+
+            // SAFETY: prefetching is only a hint and cannot fault, and SSE is part of the
+            // x86-64 baseline.
+            unsafe { _mm_prefetch::<_MM_HINT_T0>(slot.cast()) };
+        }
+
+        #[cfg(not(target_arch = "x86_64"))]
+        let _ = key;
+    }
+
+    /// Returns how full the table is in permille, estimated from its first thousand slots.
+    pub fn hashfull(&self) -> u32 {
+        let sample = &self.entries[..self.entries.len().min(1000)];
+        let used = sample.iter().filter(|entry| entry.is_some()).count();
+
+        (used * 1000 / sample.len()) as u32
+    }
+
+    /// Stores an entry in its slot, unless the slot holds a deeper PV entry of another position
+    /// from the current search. Those are kept so that the principal variation survives the
+    /// many zero-window nodes searched around it.
+    pub fn store(&mut self, entry: Entry) {
+        let index = self.index(entry.key);
+        let generation = self.generation;
+
+        let protected = self.entries[index].is_some_and(|slot| {
+            slot.entry.pv
+                && slot.generation == generation
+                && slot.entry.key != entry.key
+                && !entry.pv
+                && slot.entry.depth > entry.depth
+        });
+
+        if !protected {
+            self.entries[index] = Some(Slot { entry, generation });
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+}
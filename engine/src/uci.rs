@@ -0,0 +1,884 @@
+//! The Universal Chess Interface protocol.
+//!
+//! [`UciSession`] turns command lines into output lines without touching stdin or spawning
+//! threads, so the same protocol layer drives the native binary and hosts such as WebAssembly
+//! that cannot block on input.
+//!
+//! Beyond the protocol, a few commands edit the position for setting one up by hand in a
+//! terminal: `setpiece e4 N`, `clear e4`, `clearboard`, `sidetomove b` and `castling KQ`,
+//! with `d` to show the result. `moves` lists the legal moves with their SAN, static exchange
+//! value, whether they give check and their move ordering score, best ordered first.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
+use gambit::{CastlingPermissions, Colour, Move, Piece, Square, STARTING_POSITION_FEN};
+
+use crate::batch::{json_score, json_string};
+use crate::handicap::{Blunders, Rng};
+use crate::meta::{self, OptionValue};
+use crate::see;
+use crate::weights::{self, Weights};
+use crate::{Engine, EngineOptions, SearchLimits, SearchProgress};
+
+/// Time kept in reserve for communication delays when playing on a clock.
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+
+/// The number of moves the remaining time is spread over when the GUI does not say.
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+/// The share of a move's time budget, in percent, after which an easy move is played.
+pub const DEFAULT_EASY_MOVE_PERCENT: u32 = 25;
+
+/// The share of a move's time budget, in percent, after which a best move that has held for
+/// several iterations is played.
+pub(crate) const STABLE_MOVE_PERCENT: u32 = 50;
+
+/// Where the engine stands with the latest search, as far as the commands read so far go.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EngineState {
+    /// Every search read has finished.
+    Idle,
+    /// A search is running or queued, and will end by its limits or a `stop`.
+    Searching,
+    /// A `go ponder` search is running or queued. Its time limits wait for `ponderhit`, and
+    /// `stop` ends it.
+    Pondering,
+    /// The latest search has been told to stop but has not yet reported its move.
+    Stopping,
+}
+
+/// Watches commands as they are read, ahead of the session handling them, so that `stop` and
+/// `quit` can interrupt a search in progress.
+///
+/// Searches are numbered by the `go` commands that start them. A `stop` applies to the latest
+/// `go` read before it, so it neither cuts short an earlier search still queued in front of it
+/// nor is lost if it arrives before its own search has started.
+///
+/// Commands that arrive out of order are settled by the [`EngineState`]:
+///
+/// - A `go` during a search queues behind it, except that a ponder search or one without
+///   limits, which would never end by itself, is stopped first.
+/// - `ponderhit` turns a ponder search into an ordinary one, whose time limits run from then
+///   on, and is ignored otherwise.
+/// - `stop` when idle is ignored.
+/// - Everything else, such as `position` or `setoption`, waits for the search to finish and
+///   applies to the next one.
+///
+/// Any command read also stops the search the session runs in the background after
+/// answering a move early with the `Ponder` option set.
+pub struct CommandObserver {
+    shared: Arc<Interrupts>,
+    searches_read: u64,
+    /// The state of the latest search read, until it finishes.
+    latest: EngineState,
+    /// Whether the latest search read has no limits.
+    open_ended: bool,
+}
+
+impl CommandObserver {
+    /// Takes note of a command line, which must then be passed on to
+    /// [`UciSession::handle_with`] in the same order.
+    ///
+    /// The exception is `isready` while a search is running or queued, which the session
+    /// cannot get to in time: the reply is returned instead, to be written straight away, and
+    /// the command must not be passed on.
+    pub fn observe(&mut self, command: &str) -> Option<String> {
+        let mut tokens = command.split_whitespace();
+        let state = self.state();
+
+        match (tokens.next(), state) {
+            (Some("go"), _) => {
+                let tokens: Vec<_> = tokens.collect();
+
+                if state == EngineState::Pondering
+                    || (state == EngineState::Searching && self.open_ended)
+                {
+                    self.shared.request_stop(self.searches_read);
+                }
+
+                self.searches_read += 1;
+                self.open_ended = is_open_ended(&tokens);
+                self.latest = match tokens.contains(&"ponder") {
+                    true => EngineState::Pondering,
+                    false => EngineState::Searching,
+                };
+            }
+            (Some("stop"), EngineState::Searching | EngineState::Pondering) => {
+                self.shared.request_stop(self.searches_read);
+                self.latest = EngineState::Stopping;
+            }
+            (Some("ponderhit"), EngineState::Pondering) => {
+                self.shared.request_ponderhit(self.searches_read);
+                self.latest = EngineState::Searching;
+            }
+            (Some("quit"), _) => {
+                self.shared.request_quit();
+                self.latest = EngineState::Stopping;
+            }
+            (Some("isready"), state) if state != EngineState::Idle => {
+                return Some("readyok".to_owned());
+            }
+            _ => {}
+        }
+
+        self.shared.read_command();
+        None
+    }
+
+    /// Returns the state the commands read so far leave the engine in.
+    ///
+    /// This only reads an atomic counter, so `isready` is answered at once however busy the
+    /// session is with the search.
+    pub fn state(&self) -> EngineState {
+        match self.shared.finished.load(Ordering::Acquire) < self.searches_read {
+            true => self.latest,
+            false => EngineState::Idle,
+        }
+    }
+}
+
+/// Whether a `go` command sets no limit, so that its search only ends when it is stopped.
+fn is_open_ended(tokens: &[&str]) -> bool {
+    !tokens
+        .iter()
+        .any(|token| matches!(*token, "depth" | "nodes" | "movetime" | "wtime" | "btime"))
+}
+
+/// Stop requests and ponderhits shared between a [`CommandObserver`] and its session.
+struct Interrupts {
+    stop: Arc<AtomicBool>,
+    /// Raised while the running search ponders.
+    ponder: Arc<AtomicBool>,
+    state: Mutex<InterruptState>,
+    /// Signalled whenever the stop flag is raised or the ponder flag lowered.
+    released: Condvar,
+    /// The number of the last search to have finished, kept outside the lock so that the
+    /// observer can tell whether a search is running without waiting for it.
+    finished: AtomicU64,
+}
+
+#[derive(Default)]
+struct InterruptState {
+    /// The number of the search being run, or that ran last.
+    running: u64,
+    /// The searches a stop was requested for before they started, in order.
+    pending: VecDeque<u64>,
+    /// The number of the last search to have had a ponderhit.
+    ponderhit: u64,
+    quit: bool,
+    /// The commands the observer has passed on, and those the session has started on. The
+    /// session is behind while commands wait for it.
+    read: u64,
+    handled: u64,
+    /// Whether the session is pondering in the background after answering a move, which the
+    /// next command read stops.
+    background: bool,
+}
+
+impl Interrupts {
+    fn state(&self) -> MutexGuard<'_, InterruptState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn request_stop(&self, search: u64) {
+        let mut state = self.state();
+
+        if state.running == search {
+            self.stop.store(true, Ordering::Relaxed);
+            self.released.notify_all();
+        } else {
+            state.pending.push_back(search);
+        }
+    }
+
+    /// Stops the running search and every search still queued.
+    fn request_quit(&self) {
+        let mut state = self.state();
+
+        state.quit = true;
+        self.stop.store(true, Ordering::Relaxed);
+        self.released.notify_all();
+    }
+
+    /// Lowers the ponder flag of `search`, now or when it starts.
+    fn request_ponderhit(&self, search: u64) {
+        let mut state = self.state();
+
+        state.ponderhit = search;
+
+        if state.running == search {
+            self.ponder.store(false, Ordering::Relaxed);
+            self.released.notify_all();
+        }
+    }
+
+    /// Blocks until the running search, which has finished, may report its move: straight away
+    /// for an ordinary search, on a stop for an `infinite` one, and on a stop or ponderhit for
+    /// one that still ponders.
+    fn wait_for_release(&self, infinite: bool) {
+        let mut state = self.state();
+
+        // The flags only change with the state locked, so a change cannot be missed between
+        // the check and the wait.
+        while !self.stop.load(Ordering::Relaxed)
+            && (infinite || self.ponder.load(Ordering::Relaxed))
+        {
+            state = self
+                .released
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    /// Marks `search` as running, raising the stop flag straight away if it was stopped early,
+    /// and the ponder flag if it ponders and has not been hit yet.
+    fn start(&self, search: u64, ponder: bool) {
+        let mut state = self.state();
+
+        state.running = search;
+        state.pending.retain(|&pending| pending >= search);
+
+        let stopped = state.pending.front() == Some(&search);
+        if stopped {
+            state.pending.pop_front();
+        }
+
+        self.stop.store(stopped || state.quit, Ordering::Relaxed);
+        self.ponder
+            .store(ponder && state.ponderhit < search, Ordering::Relaxed);
+    }
+
+    fn finish(&self, search: u64) {
+        self.finished.store(search, Ordering::Release);
+    }
+
+    /// Counts a command passed on to the session, stopping a background search for it.
+    fn read_command(&self) {
+        let mut state = self.state();
+
+        state.read += 1;
+        if state.background {
+            self.stop.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn handle_command(&self) {
+        self.state().handled += 1;
+    }
+
+    /// Starts a background search unless a command is already waiting for the session, or
+    /// there is no observer to stop it, returning whether it did.
+    fn start_background(&self, observed: bool) -> bool {
+        let mut state = self.state();
+
+        if !observed || state.read != state.handled || state.quit {
+            return false;
+        }
+
+        state.background = true;
+        self.stop.store(false, Ordering::Relaxed);
+        self.ponder.store(false, Ordering::Relaxed);
+
+        true
+    }
+
+    fn finish_background(&self) {
+        self.state().background = false;
+    }
+}
+
+/// The engine side of a UCI conversation.
+///
+/// `go` searches synchronously, so a search without limits only ends once `stop` or `quit` is
+/// seen by the [`CommandObserver`] on another thread.
+pub struct UciSession {
+    engine: Engine,
+    interrupts: Arc<Interrupts>,
+    blunders: Blunders,
+    /// The share of the time budget after which an easy move is played, or zero to always use
+    /// the whole budget.
+    easy_move_percent: u32,
+    /// The nodes a millisecond on the clock is worth, or zero to play on the clock itself.
+    nodes_time: u64,
+    /// Whether search information is written as JSON objects rather than `info` lines.
+    json_info: bool,
+    /// Whether the GUI is analysing rather than playing, so that moves are never weakened and
+    /// searches on a clock use the whole budget.
+    analyse_mode: bool,
+    /// Whether the GUI lets the engine think on the opponent's time.
+    ponder: bool,
+    /// Whether a [`CommandObserver`] watches the input, without which nothing could stop a
+    /// search in the background.
+    observed: bool,
+    searches_run: u64,
+    quit: bool,
+}
+
+impl UciSession {
+    pub fn new() -> UciSession {
+        let engine = Engine::new(EngineOptions::default());
+        let interrupts = Arc::new(Interrupts {
+            stop: Arc::clone(engine.stop_flag()),
+            ponder: Arc::clone(engine.ponder_flag()),
+            state: Mutex::default(),
+            released: Condvar::new(),
+            finished: AtomicU64::new(0),
+        });
+
+        UciSession {
+            engine,
+            interrupts,
+            blunders: Blunders::new(0, Rng::from_entropy()),
+            easy_move_percent: DEFAULT_EASY_MOVE_PERCENT,
+            nodes_time: 0,
+            json_info: false,
+            analyse_mode: false,
+            ponder: false,
+            observed: false,
+            searches_run: 0,
+            quit: false,
+        }
+    }
+
+    /// Returns the observer that input must be shown to as it is read.
+    pub fn observer(&mut self) -> CommandObserver {
+        let mut state = self.interrupts.state();
+        state.read = state.handled;
+        drop(state);
+
+        self.observed = true;
+
+        CommandObserver {
+            shared: Arc::clone(&self.interrupts),
+            searches_read: self.searches_run,
+            latest: EngineState::Idle,
+            open_ended: false,
+        }
+    }
+
+    /// Whether `quit` has been received.
+    pub fn quit_requested(&self) -> bool {
+        self.quit
+    }
+
+    /// Handles one command line and returns the lines written in response.
+    pub fn handle(&mut self, command: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        self.handle_with(command, &mut |line| lines.push(line));
+
+        lines
+    }
+
+    /// Handles one command line, passing each line of output to `output` as soon as it is
+    /// produced, which lets search information be streamed while `go` runs.
+    pub fn handle_with(&mut self, command: &str, output: &mut dyn FnMut(String)) {
+        self.interrupts.handle_command();
+        let mut tokens = command.split_whitespace();
+
+        match tokens.next() {
+            Some("uci") => meta::uci_handshake().into_iter().for_each(output),
+            Some("isready") => output("readyok".to_owned()),
+            Some("ucinewgame") => self.engine.new_game(),
+            Some("setoption") => self.set_option(&tokens.collect::<Vec<_>>(), output),
+            Some("position") => self.set_position(&tokens.collect::<Vec<_>>(), output),
+            Some("go") => self.go(&tokens.collect::<Vec<_>>(), output),
+            Some("debug") => match tokens.next() {
+                Some("on") => self.engine.set_debug(true),
+                Some("off") => self.engine.set_debug(false),
+                _ => output("info string error: expected debug on or off".to_owned()),
+            },
+            Some("setpiece" | "clear" | "clearboard" | "sidetomove" | "castling" | "d") => {
+                self.edit(&command.split_whitespace().collect::<Vec<_>>(), output)
+            }
+            Some("moves") => self.list_moves(output),
+            Some("quit") => self.quit = true,
+            Some("stop" | "ponderhit") | None => {}
+            Some(unknown) => output(format!("info string error: unknown command: {unknown}")),
+        }
+    }
+
+    fn set_option(&mut self, tokens: &[&str], output: &mut dyn FnMut(String)) {
+        let value_index = tokens.iter().position(|&token| token == "value");
+        let name = tokens[..value_index.unwrap_or(tokens.len())]
+            .iter()
+            .skip_while(|&&token| token == "name")
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let value = value_index.map(|index| tokens[index + 1..].join(" "));
+
+        let Some(option) = meta::find_option(&name) else {
+            output(format!("info string error: unknown option: {name}"));
+            return;
+        };
+        let value = match option.parse(value.as_deref()) {
+            Ok(value) => value,
+            Err(error) => {
+                output(format!("info string error: {error}"));
+                return;
+            }
+        };
+
+        match (option.name, value) {
+            ("Hash", OptionValue::Spin(megabytes)) => {
+                match self.engine.set_hash(megabytes as usize) {
+                    Ok(()) => output(format!("info string hash resized to {megabytes} MB")),
+                    Err(error) => output(format!("info string error: {error}")),
+                }
+            }
+            ("Clear Hash", OptionValue::Button) => {
+                self.engine.clear_hash();
+                output("info string hash cleared".to_owned());
+            }
+            ("LargePages", OptionValue::Check(large_pages)) => {
+                if let Err(error) = self.engine.set_large_pages(large_pages) {
+                    output(format!("info string error: {error}"));
+                }
+            }
+            ("LazyEvalMargin", OptionValue::Spin(margin)) => {
+                self.engine.set_lazy_eval_margin(margin as i32)
+            }
+            ("WeightsFile", OptionValue::String(path)) if path.is_empty() => {
+                self.engine.set_weights(Weights::default())
+            }
+            ("WeightsFile", OptionValue::String(path)) => match weights::load(&path) {
+                Ok(weights) => self.engine.set_weights(weights),
+                Err(error) => output(format!("info string error: {path}: {error}")),
+            },
+            ("BlunderChance", OptionValue::Spin(chance)) => self.blunders.chance = chance as u8,
+            ("EasyMovePercent", OptionValue::Spin(percent)) => {
+                self.easy_move_percent = percent as u32
+            }
+            ("nodestime", OptionValue::Spin(nodes)) => self.nodes_time = nodes as u64,
+            ("UCI_AnalyseMode", OptionValue::Check(analyse)) => self.analyse_mode = analyse,
+            ("InfoFormat", OptionValue::Combo(format)) => self.json_info = format == "json",
+            ("Ponder", OptionValue::Check(ponder)) => self.ponder = ponder,
+            _ => {}
+        }
+    }
+
+    fn set_position(&mut self, tokens: &[&str], output: &mut dyn FnMut(String)) {
+        let moves_index = tokens.iter().position(|&token| token == "moves");
+        let (position, moves) = tokens.split_at(moves_index.unwrap_or(tokens.len()));
+        let moves = moves.iter().skip(1);
+
+        let fen = match position {
+            ["startpos"] => STARTING_POSITION_FEN.to_owned(),
+            ["fen", fen @ ..] => fen.join(" "),
+            _ => {
+                output("info string error: invalid position command".to_owned());
+                return;
+            }
+        };
+
+        if let Err(error) = self.engine.set_position(&fen, moves) {
+            output(format!("info string error: {error}"));
+        }
+    }
+
+    /// Handles the commands that edit the position, each given with its own name first.
+    fn edit(&mut self, tokens: &[&str], output: &mut dyn FnMut(String)) {
+        let mut board = self.engine.board().clone();
+
+        let edited = match tokens {
+            ["setpiece", square, piece] => {
+                let square = square.parse::<Square>().map_err(|_| "invalid square");
+                let piece = piece
+                    .parse::<char>()
+                    .ok()
+                    .and_then(Piece::from_char)
+                    .ok_or("invalid piece");
+
+                match (square, piece) {
+                    (Ok(square), Ok(piece)) => board
+                        .set_piece(square, piece)
+                        .map(drop)
+                        .map_err(|error| error.to_string()),
+                    (Err(error), _) | (_, Err(error)) => Err(error.to_owned()),
+                }
+            }
+            ["clear", square] => match square.parse::<Square>() {
+                Ok(square) => {
+                    board.clear_square(square);
+                    Ok(())
+                }
+                Err(_) => Err("invalid square".to_owned()),
+            },
+            ["clearboard"] => {
+                board.clear_board();
+                Ok(())
+            }
+            ["sidetomove", colour] => {
+                let colour = match *colour {
+                    "w" => Colour::White,
+                    "b" => Colour::Black,
+                    _ => {
+                        output("info string error: expected sidetomove w or b".to_owned());
+                        return;
+                    }
+                };
+
+                board.set_turn(colour);
+                Ok(())
+            }
+            ["castling", rights] => match rights.parse::<CastlingPermissions>() {
+                Ok(rights) => board
+                    .set_castling_rights(rights)
+                    .map_err(|error| error.to_string()),
+                Err(error) => Err(error.to_string()),
+            },
+            ["d"] => {
+                board
+                    .to_string()
+                    .lines()
+                    .map(str::to_owned)
+                    .for_each(&mut *output);
+                output(format!("Fen: {}", board.to_fen()));
+                return;
+            }
+            [command, ..] => Err(format!("invalid {command} command")),
+            [] => return,
+        };
+
+        match edited {
+            Ok(()) => self.engine.set_board(board),
+            Err(error) => output(format!("info string error: {error}")),
+        }
+    }
+
+    fn list_moves(&self, output: &mut dyn FnMut(String)) {
+        let board = self.engine.board();
+
+        output(format!(
+            "{:<8} {:<6} {:>5} {:<5} {:>11}",
+            "san", "uci", "see", "check", "order"
+        ));
+
+        for (mv, score) in self.engine.ordered_moves() {
+            output(format!(
+                "{:<8} {:<6} {:>5} {:<5} {:>11}",
+                board.san(mv),
+                mv.to_string(),
+                see::see_value(board, mv),
+                if board.gives_check(mv) { "yes" } else { "no" },
+                score
+            ));
+        }
+    }
+
+    fn go(&mut self, tokens: &[&str], output: &mut dyn FnMut(String)) {
+        let white = self.engine.board().side_to_move() == gambit::Colour::White;
+
+        let mut limits = SearchLimits::default();
+        let mut time = None;
+        let mut increment = Duration::ZERO;
+        let mut opponent_time = None;
+        let mut opponent_increment = Duration::ZERO;
+        let mut moves_to_go = None;
+        let mut ponder = false;
+        let mut infinite = false;
+
+        let mut tokens = tokens.iter();
+        while let Some(&token) = tokens.next() {
+            let mut value = || {
+                let text = tokens.next().copied().unwrap_or_default();
+                let value = text.parse::<u64>().ok();
+
+                if value.is_none() {
+                    output(format!(
+                        "info string error: invalid value for {token}: {text}"
+                    ));
+                }
+
+                value
+            };
+
+            match token {
+                "depth" => limits.depth = value().map(|depth| depth.min(u8::MAX as u64) as u8),
+                "nodes" => limits.nodes = value(),
+                "movetime" => {
+                    limits.movetime = value().map(|movetime| {
+                        Duration::from_millis(movetime).saturating_sub(MOVE_OVERHEAD)
+                    });
+                }
+                "wtime" | "btime" => {
+                    let value = value().map(Duration::from_millis);
+
+                    match (token == "wtime") == white {
+                        true => time = value,
+                        false => opponent_time = value,
+                    }
+                }
+                "winc" | "binc" => {
+                    let value = value().map(Duration::from_millis);
+
+                    match (token == "winc") == white {
+                        true => increment = value.unwrap_or_default(),
+                        false => opponent_increment = value.unwrap_or_default(),
+                    }
+                }
+                "movestogo" => moves_to_go = value().map(|moves| moves as u32),
+                "ponder" => ponder = true,
+                "infinite" => infinite = true,
+                "searchmoves" => {
+                    let board = self.engine.board();
+
+                    while let Some(mv) = tokens.clone().next().and_then(|uci| board.parse_uci(uci))
+                    {
+                        limits.search_moves.push(mv);
+                        tokens.next();
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // A ponder search runs on the opponent's time with its limits held back. After a
+        // `ponderhit` it has the budget of a move searched from scratch.
+        if let Some(time) = time {
+            let budget = allocate_time(time, increment, moves_to_go);
+
+            if self.engine.options().debug {
+                output(format!(
+                    "info string time budget {} ms of {} ms left",
+                    budget.as_millis(),
+                    time.as_millis()
+                ));
+            }
+
+            if self.nodes_time > 0 {
+                // Nothing that depends on the speed of the machine may end the search, easy
+                // moves included.
+                let nodes = budget.as_millis() as u64 * self.nodes_time;
+                limits.nodes = Some(limits.nodes.map_or(nodes, |limit| limit.min(nodes)));
+            } else {
+                let movetime = limits
+                    .movetime
+                    .map_or(budget, |movetime| movetime.min(budget));
+
+                limits.movetime = Some(movetime);
+
+                if !self.analyse_mode {
+                    limits.easy_movetime = (self.easy_move_percent > 0)
+                        .then(|| movetime * self.easy_move_percent / 100);
+                    limits.stable_movetime = Some(movetime * STABLE_MOVE_PERCENT / 100);
+                }
+            }
+        }
+
+        self.searches_run += 1;
+        self.interrupts.start(self.searches_run, ponder);
+
+        let format = match self.json_info {
+            true => format_progress_json,
+            false => format_progress,
+        };
+        let started = Instant::now();
+        let best = self.engine.run(limits.clone(), &mut |progress| {
+            if let Some(line) = format(&progress) {
+                output(line);
+            }
+        });
+        let early = limits
+            .movetime
+            .is_some_and(|movetime| started.elapsed() < movetime);
+
+        // The GUI decides when an infinite or ponder search ends, so one that reaches its
+        // maximum depth, as a forced mate soon does, holds its move until it is told.
+        self.interrupts.wait_for_release(infinite);
+
+        // A blunder is searched again without reporting, so the GUI shows the engine's real
+        // assessment while it plays the weaker move. A search that was stopped is answered
+        // straight away, as the stop flag would also cut the searches for a blunder short, and
+        // analysis always gets the real move.
+        let board = self.engine.board().clone();
+        let result = match self.analyse_mode || self.interrupts.stop.load(Ordering::Relaxed) {
+            true => best,
+            false => self.blunders.choose(&board, &limits, best, |limits| {
+                self.engine.run(limits, &mut |_| {})
+            }),
+        };
+
+        let stopped = self.interrupts.stop.load(Ordering::Relaxed);
+        self.interrupts.finish(self.searches_run);
+
+        let best_move = result
+            .best_move
+            .map_or_else(|| "0000".to_owned(), |mv| mv.to_string());
+
+        match result.pv.get(1) {
+            Some(ponder) => output(format!("bestmove {best_move} ponder {ponder}")),
+            None => output(format!("bestmove {best_move}")),
+        }
+
+        // An easy or forced move is answered before its time is up, and would leave the engine
+        // idle for the whole of the opponent's move. When pondering is allowed, it goes on
+        // searching from the reply it expects, to have the table filled for its next move,
+        // until the next command is read.
+        let background = self.ponder && early && !stopped && !ponder && !infinite;
+        if let (true, [best, reply, ..], Some(opponent_time)) =
+            (background, &result.pv[..], opponent_time)
+        {
+            let limits = SearchLimits {
+                movetime: Some(allocate_time(
+                    opponent_time,
+                    opponent_increment,
+                    moves_to_go,
+                )),
+                ..SearchLimits::default()
+            };
+
+            self.ponder_in_background(&[*best, *reply], limits);
+        }
+    }
+
+    /// Searches the position after `moves` without reporting, until the limits are reached or
+    /// the observer reads another command.
+    fn ponder_in_background(&mut self, moves: &[Move], limits: SearchLimits) {
+        if !self.interrupts.start_background(self.observed) {
+            return;
+        }
+
+        self.engine.run_after(moves, limits, &mut |_| {});
+        self.interrupts.finish_background();
+    }
+}
+
+impl Default for UciSession {
+    fn default() -> UciSession {
+        UciSession::new()
+    }
+}
+
+/// Decides how long to think with `time` left on the clock.
+pub(crate) fn allocate_time(
+    time: Duration,
+    increment: Duration,
+    moves_to_go: Option<u32>,
+) -> Duration {
+    let moves = moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+    let budget = time / moves + increment / 2;
+
+    budget.min(time.saturating_sub(MOVE_OVERHEAD))
+}
+
+fn format_progress(progress: &SearchProgress) -> Option<String> {
+    match progress {
+        SearchProgress::DepthCompleted {
+            depth,
+            score,
+            pv,
+            nodes,
+            elapsed,
+            hashfull,
+        } => Some(format!(
+            "info depth {depth} score {} nodes {nodes} nps {} hashfull {hashfull} time {} pv {}",
+            score,
+            nps(*nodes, *elapsed),
+            elapsed.as_millis(),
+            pv
+        )),
+        SearchProgress::Nodes {
+            nodes,
+            elapsed,
+            hashfull,
+        } => Some(format!(
+            "info nodes {nodes} nps {} hashfull {hashfull} time {}",
+            nps(*nodes, *elapsed),
+            elapsed.as_millis()
+        )),
+        SearchProgress::Stats { depth, stats } => Some(format!(
+            "info string depth {depth} tt probes {} hits {} cutoffs {} hashfull {} pruned \
+             razor {} futility {} see {} lazy {} pvs scouts {} researches {}",
+            stats.tt_probes,
+            stats.tt_hits,
+            stats.tt_cutoffs,
+            stats.hashfull,
+            stats.razored,
+            stats.futility_pruned,
+            stats.see_pruned,
+            stats.lazy_evals,
+            stats.scouts,
+            stats.researches
+        )),
+        SearchProgress::CurrMove { depth, mv, number } => Some(format!(
+            "info depth {depth} currmove {mv} currmovenumber {number}"
+        )),
+        SearchProgress::PvChanged { .. } => None,
+        SearchProgress::Crashed { message } => {
+            Some(format!("info string error: search crashed: {message}"))
+        }
+    }
+}
+
+/// Formats progress as a JSON object on one line, with the fields of the `info` line it
+/// stands for and the principal variation as an array of moves.
+fn format_progress_json(progress: &SearchProgress) -> Option<String> {
+    match progress {
+        SearchProgress::DepthCompleted {
+            depth,
+            score,
+            pv,
+            nodes,
+            elapsed,
+            hashfull,
+        } => {
+            let pv: Vec<_> = pv.iter().map(|mv| json_string(&mv.to_string())).collect();
+
+            Some(format!(
+                "{{\"depth\": {depth}, \"score\": {}, \"nodes\": {nodes}, \"nps\": {}, \
+                 \"hashfull\": {hashfull}, \"time\": {}, \"pv\": [{}]}}",
+                json_score(*score),
+                nps(*nodes, *elapsed),
+                elapsed.as_millis(),
+                pv.join(", ")
+            ))
+        }
+        SearchProgress::Nodes {
+            nodes,
+            elapsed,
+            hashfull,
+        } => Some(format!(
+            "{{\"nodes\": {nodes}, \"nps\": {}, \"hashfull\": {hashfull}, \"time\": {}}}",
+            nps(*nodes, *elapsed),
+            elapsed.as_millis()
+        )),
+        SearchProgress::Stats { depth, stats } => Some(format!(
+            "{{\"depth\": {depth}, \"stats\": {{\"tt_probes\": {}, \"tt_hits\": {}, \
+             \"tt_cutoffs\": {}, \"hashfull\": {}, \"razored\": {}, \"futility_pruned\": {}, \
+             \"see_pruned\": {}, \"lazy_evals\": {}, \"scouts\": {}, \"researches\": {}}}}}",
+            stats.tt_probes,
+            stats.tt_hits,
+            stats.tt_cutoffs,
+            stats.hashfull,
+            stats.razored,
+            stats.futility_pruned,
+            stats.see_pruned,
+            stats.lazy_evals,
+            stats.scouts,
+            stats.researches
+        )),
+        SearchProgress::CurrMove { depth, mv, number } => Some(format!(
+            "{{\"depth\": {depth}, \"currmove\": \"{mv}\", \"currmovenumber\": {number}}}"
+        )),
+        SearchProgress::PvChanged { .. } => None,
+        SearchProgress::Crashed { message } => Some(format!(
+            "{{\"error\": {}}}",
+            json_string(&format!("search crashed: {message}"))
+        )),
+    }
+}
+
+fn nps(nodes: u64, elapsed: Duration) -> u64 {
+    (nodes as u128 * 1000 / elapsed.as_millis().max(1)) as u64
+}
@@ -0,0 +1,228 @@
+//! Evaluation weights, built in or loaded from a file.
+//!
+//! A weights file is a small subset of TOML: one `name = value` per line, where the value is
+//! an integer or an array of integers that may span several lines, and `#` starts a comment.
+//! Weights the file leaves out keep their built-in values, so a personality can be as short as
+//! a line or two:
+//!
+//! ```toml
+//! # Aggressive: attacks on the king and threats count for more.
+//! king_attack = [9, 9, 12, 18]
+//! hanging = 60
+//! ```
+//!
+//! The names are `piece_values` for pawn to queen; the piece-square tables `pawn_table`,
+//! `knight_table`, `bishop_table`, `rook_table`, `queen_table`, `king_middlegame_table` and
+//! `king_endgame_table`, from White's point of view with the eighth rank first; `mobility`
+//! and `king_attack` for knight to queen; and `king_zone_weak_square`, `hanging`,
+//! `threat_by_pawn` and `threat_by_pawn_push`.
+//!
+//! Piece values here only change the evaluation. Move ordering and static exchange evaluation
+//! keep the built-in [`PIECE_VALUES`], which only need to rank the pieces.
+
+use std::path::Path;
+use std::sync::LazyLock;
+use std::{fmt, fs};
+
+use crate::eval::{self, PIECE_VALUES};
+
+/// The built-in weights, shared by every evaluation that is not given others.
+static STANDARD: LazyLock<Weights> = LazyLock::new(Weights::default);
+
+/// The weights of the evaluation terms, with the piece values folded into the piece-square
+/// tables the evaluation reads.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Weights {
+    piece_values: [i32; 6],
+    /// The tables of the pawn to the queen, then the king's middlegame and endgame tables.
+    tables: [[i32; 64]; 7],
+    pub(crate) mobility: [i32; 6],
+    pub(crate) king_attack: [i32; 6],
+    pub(crate) king_zone_weak_square: i32,
+    pub(crate) hanging: i32,
+    pub(crate) threat_by_pawn: i32,
+    pub(crate) threat_by_pawn_push: i32,
+    /// Material and placement of each piece by piece index, negative for Black.
+    pub(crate) piece_square: [[i32; 64]; 12],
+}
+
+/// The reasons a weights file can fail to load, counting lines from one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WeightsError {
+    /// A line is not `name = value`, or an array is not closed.
+    Syntax {
+        line: usize,
+    },
+    /// A value is not an integer.
+    InvalidNumber {
+        line: usize,
+    },
+    UnknownWeight {
+        line: usize,
+        name: String,
+    },
+    /// An array has the wrong number of values for its weight.
+    WrongLength {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for WeightsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeightsError::Syntax { line } => write!(f, "line {line}: expected name = value"),
+            WeightsError::InvalidNumber { line } => write!(f, "line {line}: invalid number"),
+            WeightsError::UnknownWeight { line, name } => {
+                write!(f, "line {line}: unknown weight {name}")
+            }
+            WeightsError::WrongLength {
+                line,
+                expected,
+                found,
+            } => write!(f, "line {line}: expected {expected} values, found {found}"),
+        }
+    }
+}
+
+impl std::error::Error for WeightsError {}
+
+impl Weights {
+    /// Returns the built-in weights.
+    pub fn standard() -> &'static Weights {
+        &STANDARD
+    }
+
+    /// Parses a weights file, starting from the built-in weights.
+    pub fn parse(text: &str) -> Result<Weights, WeightsError> {
+        let mut weights = Weights::default();
+        let mut lines = text.lines().enumerate().map(|(index, line)| {
+            let line = line.split_once('#').map_or(line, |(line, _)| line);
+            (index + 1, line.trim())
+        });
+
+        while let Some((line, text)) = lines.next() {
+            if text.is_empty() {
+                continue;
+            }
+
+            let (name, value) = text.split_once('=').ok_or(WeightsError::Syntax { line })?;
+            let (name, mut value) = (name.trim(), value.trim().to_owned());
+
+            // Arrays run on until their closing bracket.
+            if value.starts_with('[') {
+                while !value.ends_with(']') {
+                    let (_, more) = lines.next().ok_or(WeightsError::Syntax { line })?;
+                    value.push(' ');
+                    value.push_str(more);
+                }
+            }
+
+            let values = parse_values(&value).ok_or(WeightsError::InvalidNumber { line })?;
+            let target = weights
+                .slot(name)
+                .ok_or_else(|| WeightsError::UnknownWeight {
+                    line,
+                    name: name.to_owned(),
+                })?;
+
+            if values.len() != target.len() || value.starts_with('[') != (target.len() > 1) {
+                return Err(WeightsError::WrongLength {
+                    line,
+                    expected: target.len(),
+                    found: values.len(),
+                });
+            }
+
+            target.copy_from_slice(&values);
+        }
+
+        weights.piece_square = piece_square(&weights.piece_values, &weights.tables);
+
+        Ok(weights)
+    }
+
+    /// Returns the king's middlegame and endgame tables.
+    pub(crate) fn king_tables(&self) -> (&[i32; 64], &[i32; 64]) {
+        (&self.tables[5], &self.tables[6])
+    }
+
+    /// Returns the weights `name` sets, leaving out the king where a weight is by piece kind.
+    fn slot(&mut self, name: &str) -> Option<&mut [i32]> {
+        Some(match name {
+            "piece_values" => &mut self.piece_values[..5],
+            "pawn_table" => &mut self.tables[0],
+            "knight_table" => &mut self.tables[1],
+            "bishop_table" => &mut self.tables[2],
+            "rook_table" => &mut self.tables[3],
+            "queen_table" => &mut self.tables[4],
+            "king_middlegame_table" => &mut self.tables[5],
+            "king_endgame_table" => &mut self.tables[6],
+            "mobility" => &mut self.mobility[1..5],
+            "king_attack" => &mut self.king_attack[1..5],
+            "king_zone_weak_square" => std::slice::from_mut(&mut self.king_zone_weak_square),
+            "hanging" => std::slice::from_mut(&mut self.hanging),
+            "threat_by_pawn" => std::slice::from_mut(&mut self.threat_by_pawn),
+            "threat_by_pawn_push" => std::slice::from_mut(&mut self.threat_by_pawn_push),
+            _ => return None,
+        })
+    }
+}
+
+impl Default for Weights {
+    fn default() -> Weights {
+        let tables = eval::TABLES.map(|table| *table);
+
+        Weights {
+            piece_values: PIECE_VALUES,
+            tables,
+            mobility: eval::MOBILITY_WEIGHTS,
+            king_attack: eval::KING_ATTACK_WEIGHTS,
+            king_zone_weak_square: eval::KING_ZONE_WEAK_SQUARE,
+            hanging: eval::HANGING,
+            threat_by_pawn: eval::THREAT_BY_PAWN,
+            threat_by_pawn_push: eval::THREAT_BY_PAWN_PUSH,
+            piece_square: piece_square(&PIECE_VALUES, &tables),
+        }
+    }
+}
+
+/// Reads and parses the weights file at `path`.
+pub fn load(path: impl AsRef<Path>) -> Result<Weights, String> {
+    let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+
+    Weights::parse(&text).map_err(|error| error.to_string())
+}
+
+/// Parses an integer or an array of integers, allowing a trailing comma.
+fn parse_values(value: &str) -> Option<Vec<i32>> {
+    match value.strip_prefix('[') {
+        Some(array) => array
+            .strip_suffix(']')?
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.parse().ok())
+            .collect(),
+        None => Some(vec![value.parse().ok()?]),
+    }
+}
+
+/// Combines the piece values and piece-square tables into one table per piece, indexed by
+/// square, from White's point of view. Kings score nothing here, since their table depends on
+/// the phase.
+fn piece_square(piece_values: &[i32; 6], tables: &[[i32; 64]; 7]) -> [[i32; 64]; 12] {
+    let mut scores = [[0; 64]; 12];
+
+    for kind in 0..5 {
+        for index in 0..64 {
+            let value = piece_values[kind];
+
+            scores[kind][index] = value + tables[kind][index ^ 56];
+            scores[kind + 6][index] = -(value + tables[kind][index]);
+        }
+    }
+
+    scores
+}
@@ -0,0 +1,24 @@
+use gambit_engine::affinity::{cpu_order, interleave, parse_cpu_list, pin_current_thread};
+
+#[test]
+fn parses_kernel_cpu_lists() {
+    assert_eq!(parse_cpu_list("0-3,8-9\n"), [0, 1, 2, 3, 8, 9]);
+    assert_eq!(parse_cpu_list("5"), [5]);
+    assert_eq!(parse_cpu_list("1,x,3-2,4"), [1, 4]);
+    assert!(parse_cpu_list("").is_empty());
+}
+
+#[test]
+fn spreads_threads_over_nodes() {
+    let nodes = [vec![0, 1, 2], vec![8, 9], vec![]];
+
+    assert_eq!(interleave(&nodes), [0, 8, 1, 9, 2]);
+    assert!(interleave(&[]).is_empty());
+}
+
+#[test]
+fn pins_where_supported() {
+    let pinned = std::thread::spawn(|| pin_current_thread(0)).join().unwrap();
+
+    assert_eq!(pinned, !cpu_order().is_empty());
+}
@@ -0,0 +1,91 @@
+use std::iter;
+
+use gambit::Board;
+use gambit_engine::analysis;
+use gambit_engine::{Engine, SearchLimits, SearchProgress};
+
+const BACK_RANK: &str = "6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1";
+
+fn engine_at(fen: &str) -> Engine {
+    let mut engine = Engine::default();
+    engine.set_position(fen, iter::empty::<&str>()).unwrap();
+    engine
+}
+
+fn depth(depth: u8) -> SearchLimits {
+    SearchLimits {
+        depth: Some(depth),
+        ..SearchLimits::default()
+    }
+}
+
+#[test]
+fn lines_start_with_different_moves() {
+    let mut engine = engine_at(BACK_RANK);
+    let mut reported = Vec::new();
+
+    let results = analysis::analyse(&mut engine, &depth(3), 3, |line, progress| {
+        if let SearchProgress::DepthCompleted { depth, .. } = progress {
+            reported.push((line, depth));
+        }
+    });
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(
+        results[0].best_move.map(|mv| mv.to_string()).as_deref(),
+        Some("d1d8")
+    );
+    assert!(results[0].score > results[1].score);
+    assert!(results[1].score >= results[2].score);
+    assert_ne!(results[1].best_move, results[2].best_move);
+    assert!(results[1..]
+        .iter()
+        .all(|result| result.best_move != results[0].best_move));
+
+    assert_eq!(reported.first(), Some(&(1, 1)));
+    assert_eq!(reported.last(), Some(&(3, 3)));
+}
+
+#[test]
+fn lines_are_limited_by_the_moves_available() {
+    let mut engine = engine_at("7k/8/6Q1/8/8/8/8/K7 b - - 0 1");
+    let results = analysis::analyse(&mut engine, &depth(2), 3, |_, _| {});
+    assert!(results.is_empty());
+
+    let mut engine = engine_at(BACK_RANK);
+    let board = engine.board().clone();
+    let limits = SearchLimits {
+        search_moves: vec![
+            board.parse_uci("h2h3").unwrap(),
+            board.parse_uci("g2g3").unwrap(),
+        ],
+        ..depth(2)
+    };
+    let results = analysis::analyse(&mut engine, &limits, 3, |_, _| {});
+    assert_eq!(results.len(), 2);
+}
+
+#[test]
+fn lines_are_written_in_san() {
+    let board = Board::from_fen(BACK_RANK).unwrap();
+    let mut engine = engine_at(BACK_RANK);
+    let results = analysis::analyse(&mut engine, &depth(2), 1, |_, _| {});
+
+    assert_eq!(
+        analysis::format_line(&board, 1, &results[0]),
+        format!(
+            "line 1 depth 2 score mate 1 nodes {} pv Rd8#",
+            results[0].nodes
+        )
+    );
+
+    let moves: Vec<_> = ["e2e4", "e7e5", "g1f3"]
+        .iter()
+        .scan(Board::default(), |board, uci| {
+            let mv = board.parse_uci(uci).unwrap();
+            board.make_move(mv);
+            Some(mv)
+        })
+        .collect();
+    assert_eq!(analysis::san_line(&Board::default(), &moves), "e4 e5 Nf3");
+}
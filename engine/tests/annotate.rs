@@ -0,0 +1,68 @@
+use gambit::pgn::parse_games;
+use gambit_engine::annotate::{annotate, Thresholds};
+use gambit_engine::{Engine, SearchLimits};
+
+fn annotate_pgn(pgn: &str, thresholds: &Thresholds) -> String {
+    let games = parse_games(pgn).unwrap();
+    let limits = SearchLimits {
+        depth: Some(4),
+        ..SearchLimits::default()
+    };
+
+    let pgn = annotate(&mut Engine::default(), &games[0], &limits, thresholds);
+    assert!(pgn.lines().all(|line| line.len() < 80));
+
+    // The movetext is wrapped wherever the lengths fall, so it is checked as one line.
+    pgn.split('\n')
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+fn blunders_are_marked_with_the_best_move() {
+    let pgn = annotate_pgn(
+        "[Event \"Queen trap\"]\n1. e4 e5 2. Nf3 Qh4 3. Nxh4 *",
+        &Thresholds::default(),
+    );
+
+    assert!(pgn.starts_with("[Event \"Queen trap\"]  1. e4 {[%eval "));
+    assert!(pgn.contains(" 2... Qh4 $4 {[%eval "));
+    assert!(pgn.contains(" was best} 3. Nxh4 {[%eval "));
+    assert!(pgn.ends_with("]} * "));
+
+    let reread = parse_games(&pgn).unwrap();
+    assert_eq!(reread[0].moves().len(), 5);
+}
+
+#[test]
+fn thresholds_decide_what_is_marked() {
+    let game = "1. e4 e5 2. Nf3 Qh4 3. Nxh4 *";
+
+    let lenient = Thresholds {
+        inaccuracy: 5000,
+        mistake: 5000,
+        blunder: 5000,
+    };
+    assert!(!annotate_pgn(game, &lenient).contains('$'));
+
+    let strict = Thresholds {
+        inaccuracy: 1,
+        mistake: 300,
+        blunder: 5000,
+    };
+    let pgn = annotate_pgn(game, &strict);
+    assert!(pgn.contains("Qh4 $2 "));
+    assert!(!pgn.contains("$4"));
+}
+
+#[test]
+fn mate_ends_without_an_evaluation() {
+    let pgn = annotate_pgn(
+        "1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0",
+        &Thresholds::default(),
+    );
+
+    assert!(pgn.contains("3... Nf6 $4 {[%eval #1] "));
+    assert!(pgn.ends_with(" 4. Qxf7# 1-0 "));
+}
@@ -0,0 +1,89 @@
+use gambit::FenError;
+use gambit_engine::batch::{self, BatchError, Format};
+use gambit_engine::{EngineOptions, SearchLimits};
+
+const POSITIONS: &str = "\
+# A comment, then a FEN and two EPD records.
+rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
+
+6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - bm Rd8#; id \"back, rank\";
+4k3/8/8/8/8/8/4P3/4K3 w - - id \"pawn\";
+";
+
+#[test]
+fn reads_fen_and_epd_lines() {
+    let positions = batch::read_positions(POSITIONS).unwrap();
+
+    assert_eq!(positions.len(), 3);
+    assert_eq!(positions[0].id, None);
+    assert_eq!(positions[1].id.as_deref(), Some("back, rank"));
+    assert_eq!(
+        positions[2].board.to_fen(),
+        "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"
+    );
+
+    assert_eq!(
+        batch::read_positions("\n8/8/8 w - -\n"),
+        Err(BatchError {
+            line: 2,
+            error: FenError::InvalidPiecePlacement,
+        })
+    );
+}
+
+#[test]
+fn results_do_not_depend_on_the_threads() {
+    let positions = batch::read_positions(POSITIONS).unwrap();
+    let limits = SearchLimits {
+        depth: Some(4),
+        ..SearchLimits::default()
+    };
+    let options = EngineOptions::default();
+
+    let single = batch::analyse(&positions, &limits, &options, 1);
+    let parallel = batch::analyse(&positions, &limits, &options, 3);
+    let pinned = batch::analyse(
+        &positions,
+        &limits,
+        &EngineOptions {
+            numa: true,
+            ..options
+        },
+        2,
+    );
+
+    assert_eq!(single, parallel);
+    assert_eq!(single, pinned);
+    assert_eq!(single[1].score.mate_moves(), Some(1));
+}
+
+#[test]
+fn writes_csv_and_json() {
+    let positions = batch::read_positions(POSITIONS).unwrap();
+    let limits = SearchLimits {
+        depth: Some(2),
+        ..SearchLimits::default()
+    };
+    let results = batch::analyse(&positions, &limits, &EngineOptions::default(), 2);
+
+    let mut csv = Vec::new();
+    batch::write_results(&mut csv, Format::Csv, &positions, &results).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let rows: Vec<_> = csv.lines().collect();
+
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0], "id,fen,bestmove,score,depth,nodes,pv");
+    assert!(
+        rows[2].starts_with("\"back, rank\",6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1,d1d8,mate 1,2,")
+    );
+
+    let mut json = Vec::new();
+    batch::write_results(&mut json, Format::Json, &positions, &results).unwrap();
+    let json = String::from_utf8(json).unwrap();
+
+    assert!(json.starts_with("[\n"));
+    assert!(json.ends_with("]\n"));
+    assert!(json.contains("\"id\": \"back, rank\""));
+    assert!(json.contains("\"score\": {\"mate\": 1}"));
+    assert_eq!(json.matches("\"fen\"").count(), 3);
+}
@@ -0,0 +1,51 @@
+use gambit::Board;
+use gambit_engine::correction::CorrectionHistory;
+use gambit_engine::Score;
+
+#[test]
+fn learns_the_error_of_a_pawn_structure() {
+    let mut correction = CorrectionHistory::new();
+    let board = Board::default();
+    let eval = Score::new(20);
+
+    assert_eq!(correction.correct(&board, eval), eval);
+
+    for _ in 0..200 {
+        let corrected = correction.correct(&board, eval);
+        correction.update(&board, 10, corrected, Score::new(60));
+    }
+
+    let corrected = correction.correct(&board, eval);
+    assert!(corrected > Score::new(50), "{corrected}");
+    assert!(corrected <= Score::new(60), "{corrected}");
+
+    // The same pawns with the pieces elsewhere share the correction, the other side does not.
+    let developed =
+        Board::from_fen("r1bqkb1r/pppppppp/2n2n2/8/8/2N2N2/PPPPPPPP/R1BQKB1R w KQkq - 4 3")
+            .unwrap();
+    let black =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1").unwrap();
+    assert_eq!(correction.correct(&developed, eval), corrected);
+    assert_eq!(correction.correct(&black, eval), eval);
+
+    correction.clear();
+    assert_eq!(correction.correct(&board, eval), eval);
+}
+
+#[test]
+fn corrections_are_bounded() {
+    let mut correction = CorrectionHistory::new();
+    let board = Board::default();
+
+    for _ in 0..1000 {
+        let corrected = correction.correct(&board, Score::DRAW);
+        correction.update(&board, 20, corrected, Score::new(5000));
+    }
+
+    let corrected = correction.correct(&board, Score::DRAW);
+    assert!(corrected > Score::new(50));
+    assert!(corrected < Score::new(200));
+
+    let near_mate = Score::MATE_BOUND - 10;
+    assert!(!correction.correct(&board, near_mate).is_mate());
+}
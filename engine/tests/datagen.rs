@@ -0,0 +1,87 @@
+use gambit::Board;
+use gambit_engine::datagen::{self, DatagenSettings, DecodeError, Record, RECORD_SIZE};
+use gambit_engine::{EngineOptions, SearchLimits};
+
+#[test]
+fn records_round_trip() {
+    for (fen, score, result) in [
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            20,
+            0,
+        ),
+        (
+            "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b Kq e3 0 3",
+            -150,
+            -1,
+        ),
+        ("8/8/4k3/8/8/8/4P3/4K3 w - - 47 300", i16::MAX, 1),
+    ] {
+        let record = Record {
+            board: Board::from_fen(fen).unwrap(),
+            score,
+            result,
+        };
+        let bytes = record.encode();
+
+        assert_eq!(Record::decode(&bytes), Ok(record));
+    }
+}
+
+#[test]
+fn invalid_records_are_rejected() {
+    let mut bytes = Record {
+        board: Board::default(),
+        score: 0,
+        result: 0,
+    }
+    .encode();
+
+    // The rook on A1 becomes a nibble that is no piece.
+    bytes[8] |= 0xF;
+    assert_eq!(Record::decode(&bytes), Err(DecodeError::InvalidPieces));
+
+    // Only the kings are left, which puts the black king on A2 next to the white one.
+    let mut bytes = [0; RECORD_SIZE];
+    bytes[0] = 0b11;
+    bytes[8] = 5 | 11 << 4;
+    assert!(matches!(
+        Record::decode(&bytes),
+        Err(DecodeError::InvalidPosition(_))
+    ));
+}
+
+#[test]
+fn self_play_keeps_quiet_positions() {
+    let settings = DatagenSettings {
+        games: 2,
+        limits: SearchLimits {
+            depth: Some(1),
+            ..SearchLimits::default()
+        },
+        random_plies: 6,
+        threads: 2,
+        seed: 1,
+    };
+    let options = EngineOptions {
+        hash: 1,
+        ..EngineOptions::default()
+    };
+    let mut output = Vec::new();
+
+    let stats = datagen::generate(&options, &settings, &mut output).unwrap();
+    assert_eq!(stats.games, 2);
+    assert_eq!(output.len(), stats.positions * RECORD_SIZE);
+
+    let records: Vec<Record> = datagen::decode_all(&output)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(records.len(), stats.positions);
+    assert!(records.iter().all(|record| !record.board.in_check()));
+    assert!(records
+        .iter()
+        .all(|record| record.board.fullmove_number() >= 4));
+    assert!(records
+        .iter()
+        .all(|record| (-1..=1).contains(&record.result)));
+}
@@ -0,0 +1,343 @@
+use std::time::{Duration, Instant};
+
+use gambit::{Square, STARTING_POSITION_FEN};
+use gambit_engine::{Engine, EngineOptions, PositionError, Score, SearchLimits, SearchProgress};
+
+fn depth(depth: u8) -> SearchLimits {
+    SearchLimits {
+        depth: Some(depth),
+        ..SearchLimits::default()
+    }
+}
+
+#[test]
+fn finds_back_rank_mate() {
+    let mut engine = Engine::default();
+    engine
+        .set_position("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1", [""; 0])
+        .unwrap();
+
+    let result = engine.search(depth(3));
+
+    assert_eq!(result.best_move.unwrap().to_string(), "d1d8");
+    assert_eq!(result.score, Score::mate_in(1));
+}
+
+#[test]
+fn takes_an_offered_repetition() {
+    // Two rooks down, white has already checked the king back and forth between h7 and g8.
+    // After Qh5+ black could return to the position twice seen with the king on g8, which
+    // only an upcoming repetition check sees this shallow.
+    let mut engine = Engine::default();
+    engine
+        .set_position(
+            "6k1/6p1/8/7Q/8/8/1r3r2/7K w - - 0 1",
+            ["h5e8", "g8h7", "e8h5", "h7g8", "h5e8", "g8h7"],
+        )
+        .unwrap();
+
+    let result = engine.search(depth(4));
+
+    assert_eq!(result.best_move.unwrap().to_string(), "e8h5");
+    assert_eq!(result.score, Score::DRAW);
+}
+
+#[test]
+fn copy_make_and_make_unmake_search_the_same_tree() {
+    let kiwipete = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+    let results: Vec<_> = [true, false]
+        .into_iter()
+        .map(|copy_make| {
+            let mut engine = Engine::new(EngineOptions {
+                copy_make,
+                ..EngineOptions::default()
+            });
+            engine.set_position(kiwipete, [""; 0]).unwrap();
+
+            let result = engine.search(depth(4));
+            assert_eq!(engine.board().to_fen(), kiwipete);
+
+            result
+        })
+        .collect();
+
+    assert_eq!(results[0].pv, results[1].pv);
+    assert_eq!(results[0].score, results[1].score);
+    assert_eq!(results[0].nodes, results[1].nodes);
+}
+
+#[test]
+fn always_completes_the_first_iteration_on_the_clock() {
+    let mut engine = Engine::default();
+
+    let result = engine.search(SearchLimits {
+        movetime: Some(Duration::ZERO),
+        ..SearchLimits::default()
+    });
+
+    assert_eq!(result.depth, 1);
+    assert_eq!(result.pv.first().copied(), result.best_move);
+    assert!(result.best_move.is_some());
+}
+
+#[test]
+fn node_limits_stop_the_first_iteration() {
+    let mut engine = Engine::default();
+
+    let result = engine.search(SearchLimits {
+        nodes: Some(1),
+        ..SearchLimits::default()
+    });
+
+    assert!(result.nodes <= 2, "{}", result.nodes);
+    assert_eq!(result.depth, 0);
+    assert_eq!(result.pv.first().copied(), result.best_move);
+    assert!(result.best_move.is_some());
+}
+
+#[test]
+fn rejects_illegal_moves_and_keeps_the_position() {
+    let mut engine = Engine::default();
+    engine
+        .set_position(gambit::STARTING_POSITION_FEN, ["e2e4"])
+        .unwrap();
+
+    let before = engine.board().clone();
+    let error = engine.set_position(gambit::STARTING_POSITION_FEN, ["e2e4", "e2e4"]);
+
+    assert_eq!(error, Err(PositionError::IllegalMove("e2e4".to_owned())));
+    assert_eq!(engine.board(), &before);
+}
+
+#[test]
+fn reports_no_move_when_mated() {
+    let mut engine = Engine::default();
+    engine
+        .set_position("3R2k1/5ppp/8/8/8/8/5PPP/6K1 b - - 0 1", [""; 0])
+        .unwrap();
+
+    let result = engine.search(depth(2));
+
+    assert_eq!(result.best_move, None);
+    assert!(result.pv.is_empty());
+}
+
+#[test]
+fn reports_each_completed_depth() {
+    let mut engine = Engine::default();
+    let mut completed = Vec::new();
+
+    let result = engine.search_with_progress(depth(4), |progress| {
+        if let SearchProgress::DepthCompleted { depth, pv, .. } = progress {
+            completed.push((depth, pv));
+        }
+    });
+
+    let depths: Vec<u8> = completed.iter().map(|(depth, _)| *depth).collect();
+
+    assert_eq!(depths, [1, 2, 3, 4]);
+    assert_eq!(completed.last().map(|(_, pv)| pv), Some(&result.pv));
+}
+
+#[test]
+fn scouts_later_moves_with_a_zero_window() {
+    let mut engine = Engine::new(EngineOptions {
+        debug: true,
+        ..EngineOptions::default()
+    });
+    let mut stats = None;
+
+    engine.search_with_progress(depth(6), |progress| {
+        if let SearchProgress::Stats {
+            stats: reported, ..
+        } = progress
+        {
+            stats = Some(reported);
+        }
+    });
+
+    let stats = stats.unwrap();
+    assert!(stats.scouts > 0);
+    assert!(stats.researches < stats.scouts);
+}
+
+#[test]
+fn stops_background_search_on_timeout() {
+    let mut engine = Engine::default();
+    let handle = engine.start_search(SearchLimits::default());
+
+    let result = handle.result_timeout(Duration::from_millis(100));
+
+    assert!(result.best_move.is_some());
+    assert!(result.depth < 100);
+}
+
+#[test]
+fn dropping_the_handle_aborts_the_search() {
+    let mut engine = Engine::default();
+    let start = Instant::now();
+
+    drop(engine.start_search(SearchLimits::default()));
+
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(engine.search(depth(1)).best_move.is_some());
+}
+
+#[test]
+fn panicking_searches_still_return_a_move() {
+    let mut engine = Engine::default();
+    let mut events = Vec::new();
+
+    let result = engine.search_with_progress(depth(6), |progress| {
+        if let SearchProgress::DepthCompleted { depth: 3, .. } = progress {
+            panic!("search bug");
+        }
+
+        events.push(progress);
+    });
+
+    let completed: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            SearchProgress::DepthCompleted { depth, .. } => Some(*depth),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(completed, [1, 2]);
+    assert_eq!(
+        events.last(),
+        Some(&SearchProgress::Crashed {
+            message: "search bug".to_owned()
+        })
+    );
+    assert_eq!(result.depth, 3);
+    assert!(result.best_move.is_some());
+
+    // The engine is usable again, in the background as well.
+    assert_eq!(engine.search(depth(3)).depth, 3);
+
+    let handle = engine.start_search_with_progress(depth(4), |progress| {
+        if let SearchProgress::PvChanged { .. } = progress {
+            panic!("search bug");
+        }
+    });
+    assert!(handle.await_result().best_move.is_some());
+}
+
+#[test]
+fn the_hash_is_cleared_on_request_and_when_resized() {
+    let mut engine = Engine::new(EngineOptions {
+        hash: 1,
+        ..EngineOptions::default()
+    });
+
+    engine.search(SearchLimits {
+        depth: Some(6),
+        ..SearchLimits::default()
+    });
+    assert!(engine.hashfull() > 0);
+
+    engine.clear_hash();
+    assert_eq!(engine.hashfull(), 0);
+
+    engine.search(SearchLimits {
+        depth: Some(6),
+        ..SearchLimits::default()
+    });
+    engine.set_hash(2).unwrap();
+    assert_eq!(engine.hashfull(), 0);
+    assert_eq!(engine.options().hash, 2);
+}
+
+#[test]
+fn root_moves_are_reported_after_the_first_second() {
+    let mut engine = Engine::default();
+    let legal = gambit::movegen::legal_moves(engine.board());
+    let mut reported = Vec::new();
+
+    let start = Instant::now();
+    engine.search_with_progress(
+        SearchLimits {
+            movetime: Some(Duration::from_secs(3)),
+            ..SearchLimits::default()
+        },
+        |progress| {
+            if let SearchProgress::CurrMove { mv, number, .. } = progress {
+                reported.push((mv, number, start.elapsed()));
+            }
+        },
+    );
+
+    assert!(!reported.is_empty());
+
+    for &(mv, number, elapsed) in &reported {
+        assert!(elapsed >= Duration::from_secs(1));
+        assert!(legal.contains(&mv));
+        assert!((1..=legal.len()).contains(&number));
+    }
+}
+
+#[test]
+fn stable_best_moves_are_played_early() {
+    let search = |stable_movetime| {
+        let mut engine = Engine::default();
+        engine
+            .set_position("4k3/8/8/3q4/8/8/8/3QK3 w - - 0 1", [""; 0])
+            .unwrap();
+
+        engine.search(SearchLimits {
+            depth: Some(6),
+            stable_movetime,
+            ..SearchLimits::default()
+        })
+    };
+
+    // The queen is taken at every depth, so the search stops as soon as three iterations
+    // agree on it.
+    let stable = search(Some(Duration::ZERO));
+    assert_eq!(stable.best_move.unwrap().to_string(), "d1d5");
+    assert_eq!(stable.depth, 3);
+
+    assert_eq!(search(None).depth, 6);
+}
+
+#[test]
+fn easy_moves_are_played_early() {
+    let easy = |fen: &str, moves: &[&str]| {
+        let mut engine = Engine::default();
+        engine.set_position(fen, moves).unwrap();
+
+        let start = Instant::now();
+        let result = engine.search(SearchLimits {
+            movetime: Some(Duration::from_secs(20)),
+            easy_movetime: Some(Duration::ZERO),
+            ..SearchLimits::default()
+        });
+        assert!(start.elapsed() < Duration::from_secs(10), "{fen} {moves:?}");
+
+        result
+    };
+
+    // An only move needs no more than the first iteration.
+    let only = easy("7k/8/8/8/8/8/6q1/7K w - - 0 1", &[]);
+    assert_eq!(only.best_move.unwrap().to_string(), "h1g2");
+    assert_eq!(only.depth, 1);
+
+    let recapture = easy(
+        STARTING_POSITION_FEN,
+        &["e2e4", "e7e5", "g1f3", "b8c6", "f1b5", "a7a6", "b5c6"],
+    );
+    assert_eq!(recapture.best_move.unwrap().to(), Square::C6);
+
+    // Taking a hanging queen stays best by far at every depth.
+    let queen = easy("4k3/8/8/3q4/8/8/8/3QK3 w - - 0 1", &[]);
+    assert_eq!(queen.best_move.unwrap().to_string(), "d1d5");
+
+    // Without the easy move time the search runs to its other limits.
+    let mut engine = Engine::default();
+    engine
+        .set_position("7k/8/8/8/8/8/6q1/7K w - - 0 1", [""; 0])
+        .unwrap();
+    assert_eq!(engine.search(depth(3)).depth, 3);
+}
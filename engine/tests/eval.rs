@@ -0,0 +1,187 @@
+use gambit::{Board, Colour, Square};
+use gambit_engine::eval::{
+    evaluate, evaluate_lazily, evaluate_position, king_safety, mobility, scale_for_halfmove_clock,
+    threats, EvalInfo,
+};
+use gambit_engine::material::analyse;
+use gambit_engine::weights::Weights;
+use gambit_engine::Score;
+
+#[test]
+fn advantages_shrink_towards_the_fifty_move_rule() {
+    let fresh = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 60").unwrap();
+    let stale = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 80 60").unwrap();
+
+    assert_eq!(evaluate_position(&fresh), evaluate_position(&stale));
+    assert_eq!(evaluate(&fresh), Score::new(evaluate_position(&fresh)));
+    assert!(evaluate(&stale) < evaluate(&fresh));
+    assert!(evaluate(&stale) > Score::DRAW);
+
+    assert_eq!(scale_for_halfmove_clock(300, 100), Score::new(150));
+    assert_eq!(scale_for_halfmove_clock(-300, 150), Score::new(-150));
+}
+
+#[test]
+fn threats_against_pieces() {
+    let threats = |fen, colour| {
+        let board = Board::from_fen(fen).unwrap();
+        threats(&board, &EvalInfo::new(&board), colour, Weights::standard())
+    };
+
+    // The knight on e5 is attacked by the rook and undefended, then defended.
+    let hanging = threats("4k3/8/8/4n3/8/8/8/4RK2 w - - 0 1", Colour::White);
+    let defended = threats("4k3/8/5p2/4n3/8/8/8/4RK2 w - - 0 1", Colour::White);
+    assert!(hanging > 0);
+    assert_eq!(defended, 0);
+
+    // A pawn attacking a defended knight is still a threat.
+    let by_pawn = threats("4k3/8/5p2/4n3/3P4/8/8/5K2 w - - 0 1", Colour::White);
+    assert!(by_pawn > 0);
+
+    // Pushing d3 to d4 would attack the knight, unless a black pawn guards d4.
+    let push = threats("4k3/8/8/4n3/8/3P4/8/5K2 w - - 0 1", Colour::White);
+    let unsafe_push = threats("4k3/8/8/2p1n3/8/3P4/8/5K2 w - - 0 1", Colour::White);
+    assert!(push > 0);
+    assert_eq!(unsafe_push, 0);
+
+    assert_eq!(
+        threats("4k3/8/8/4n3/8/8/8/4RK2 w - - 0 1", Colour::Black),
+        0
+    );
+}
+
+#[test]
+fn evaluation_is_symmetric() {
+    for fen in [
+        "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "4k3/8/8/4n3/8/3P4/8/4RK2 b - - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(
+            evaluate_position(&board),
+            evaluate_position(&board.flipped()),
+            "{fen}"
+        );
+    }
+}
+
+#[test]
+fn mobility_and_king_safety() {
+    let info = |fen| {
+        let board = Board::from_fen(fen).unwrap();
+        let info = EvalInfo::new(&board);
+        (board, info)
+    };
+
+    // A knight in the centre reaches more than one in the corner.
+    let (centre, centre_info) = info("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1");
+    let (corner, corner_info) = info("4k3/8/8/8/8/8/8/N3K3 w - - 0 1");
+    assert!(
+        mobility(&centre, &centre_info, Colour::White, Weights::standard())
+            > mobility(&corner, &corner_info, Colour::White, Weights::standard())
+    );
+
+    // A queen next to the enemy king attacks its zone, and counts for less in an endgame.
+    let (_, attacking) = info("6k1/5ppp/8/6Q1/8/8/5PPP/6K1 w - - 0 1");
+    assert!(attacking.king_zone(Colour::Black).contains(Square::G6));
+    assert!(
+        king_safety(&attacking, Colour::White, 24, Weights::standard())
+            > king_safety(&attacking, Colour::White, 4, Weights::standard())
+    );
+    assert_eq!(
+        king_safety(&attacking, Colour::Black, 24, Weights::standard()),
+        0
+    );
+}
+
+#[test]
+fn lazy_evaluation_skips_positional_terms_far_outside_the_window() {
+    let board =
+        Board::from_fen("r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4")
+            .unwrap();
+    let material = analyse(&board);
+    let full = evaluate_position(&board);
+
+    assert_eq!(
+        evaluate_lazily(&board, &material, Weights::standard(), -50, 50, 600),
+        (full, true)
+    );
+
+    let (cheap, complete) =
+        evaluate_lazily(&board, &material, Weights::standard(), 2000, 2001, 600);
+    assert!(!complete);
+    assert!(cheap + 600 < 2000);
+
+    let (cheap, complete) =
+        evaluate_lazily(&board, &material, Weights::standard(), -2001, -2000, 600);
+    assert!(!complete);
+    assert!(cheap - 600 > -2000);
+}
+
+#[test]
+fn weights_files_override_the_built_in_weights() {
+    let board = Board::from_fen("4k3/4p3/8/8/8/8/4P3/R3K3 w - - 0 1").unwrap();
+    let material = analyse(&board);
+    let evaluate_with = |weights: &Weights| {
+        evaluate_lazily(&board, &material, weights, -30_000, 30_000, i32::MAX).0
+    };
+
+    assert_eq!(
+        Weights::parse("# nothing to change\n").unwrap(),
+        *Weights::standard()
+    );
+
+    let heavy_rooks = Weights::parse(
+        "piece_values = [100, 320, 330, 600, 900]  # rooks up a pawn\n\
+         rook_table = [\n\
+             0, 0, 0, 0, 0, 0, 0, 0,\n\
+             0, 0, 0, 0, 0, 0, 0, 0,\n\
+             0, 0, 0, 0, 0, 0, 0, 0,\n\
+             0, 0, 0, 0, 0, 0, 0, 0,\n\
+             0, 0, 0, 0, 0, 0, 0, 0,\n\
+             0, 0, 0, 0, 0, 0, 0, 0,\n\
+             0, 0, 0, 0, 0, 0, 0, 0,\n\
+             100, 0, 0, 0, 0, 0, 0, 0,\n\
+         ]\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        evaluate_with(&heavy_rooks) - evaluate_with(Weights::standard()),
+        100 + 100
+    );
+}
+
+#[test]
+fn malformed_weights_files_are_rejected() {
+    use gambit_engine::weights::WeightsError;
+
+    assert_eq!(
+        Weights::parse("hanging 40"),
+        Err(WeightsError::Syntax { line: 1 })
+    );
+    assert_eq!(
+        Weights::parse("\nhanging = lots"),
+        Err(WeightsError::InvalidNumber { line: 2 })
+    );
+    assert_eq!(
+        Weights::parse("aggression = 11"),
+        Err(WeightsError::UnknownWeight {
+            line: 1,
+            name: "aggression".to_owned()
+        })
+    );
+    assert_eq!(
+        Weights::parse("mobility = [1, 2, 3]"),
+        Err(WeightsError::WrongLength {
+            line: 1,
+            expected: 4,
+            found: 3
+        })
+    );
+    assert_eq!(
+        Weights::parse("pawn_table = [1, 2,"),
+        Err(WeightsError::Syntax { line: 1 })
+    );
+}
@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+
+use gambit::Colour;
+use gambit_engine::handicap::{Blunders, Odds, Rng};
+use gambit_engine::uci::UciSession;
+use gambit_engine::{Engine, SearchLimits};
+
+#[test]
+fn odds_positions_miss_the_piece() {
+    assert_eq!(
+        Odds::Knight.fen(Colour::White),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/R1BQKBNR w KQkq - 0 1"
+    );
+    assert_eq!(
+        Odds::Rook.fen(Colour::White),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/1NBQKBNR w Kkq - 0 1"
+    );
+    assert_eq!(
+        Odds::Pawn.fen(Colour::Black),
+        "rnbqkbnr/ppppp1pp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+    );
+    assert_eq!(
+        Odds::Queen.fen(Colour::Black),
+        "rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+    );
+}
+
+#[test]
+fn search_moves_restrict_the_root() {
+    let mut engine = Engine::default();
+    engine
+        .set_position("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1", [""; 0])
+        .unwrap();
+
+    let h3 = engine.board().parse_uci("h2h3").unwrap();
+    let result = engine.search(SearchLimits {
+        depth: Some(3),
+        search_moves: vec![h3],
+        ..SearchLimits::default()
+    });
+
+    assert_eq!(result.best_move, Some(h3));
+}
+
+#[test]
+fn blunders_play_a_worse_move_at_the_set_rate() {
+    let mut engine = Engine::default();
+    engine
+        .set_position("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1", [""; 0])
+        .unwrap();
+
+    let limits = SearchLimits {
+        depth: Some(3),
+        ..SearchLimits::default()
+    };
+    let best = engine.search(limits.clone());
+    let board = engine.board().clone();
+
+    let mut never = Blunders::new(0, Rng::new(1));
+    let mut always = Blunders::new(100, Rng::new(1));
+
+    for _ in 0..4 {
+        let kept = never.choose(&board, &limits, best.clone(), |limits| {
+            engine.search(limits)
+        });
+        assert_eq!(kept.best_move, best.best_move);
+
+        let blunder = always.choose(&board, &limits, best.clone(), |limits| {
+            engine.search(limits)
+        });
+        assert_ne!(blunder.best_move, best.best_move);
+        assert!(blunder.score < best.score);
+    }
+}
+
+#[test]
+fn only_moves_are_still_played() {
+    let mut engine = Engine::default();
+    engine
+        .set_position("k7/8/8/8/8/8/1q6/K7 w - - 0 1", [""; 0])
+        .unwrap();
+
+    let limits = SearchLimits {
+        depth: Some(2),
+        ..SearchLimits::default()
+    };
+    let best = engine.search(limits.clone());
+    let board = engine.board().clone();
+
+    let result = Blunders::new(100, Rng::new(7)).choose(&board, &limits, best.clone(), |limits| {
+        engine.search(limits)
+    });
+
+    assert_eq!(result.best_move, best.best_move);
+}
+
+#[test]
+fn uci_searchmoves_and_blunder_chance() {
+    let mut session = UciSession::new();
+    session.handle("position fen 6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1");
+
+    let lines = session.handle("go depth 3 searchmoves g2g3 h2h3");
+    let best = lines.last().unwrap();
+    assert!(
+        best.starts_with("bestmove g2g3") || best.starts_with("bestmove h2h3"),
+        "{best}"
+    );
+
+    assert!(session
+        .handle("setoption name BlunderChance value 100")
+        .is_empty());
+    let lines = session.handle("go depth 3");
+    assert!(lines.last().unwrap().starts_with("bestmove"));
+    assert_ne!(lines.last().map(String::as_str), Some("bestmove d1d8"));
+
+    assert_eq!(
+        session.handle("setoption name BlunderChance value 101"),
+        ["info string error: invalid value for BlunderChance: 101"]
+    );
+}
+
+#[test]
+fn blunders_stay_within_the_time_budget() {
+    let mut session = UciSession::new();
+    session.handle("position startpos moves e2e4 e7e5 g1f3");
+    session.handle("setoption name BlunderChance value 100");
+
+    let start = Instant::now();
+    let lines = session.handle("go movetime 1000");
+
+    // A blunder search with a clock of its own would take at least twice the movetime.
+    assert!(lines.last().unwrap().starts_with("bestmove"));
+    assert!(
+        start.elapsed() < Duration::from_millis(1900),
+        "{:?}",
+        start.elapsed()
+    );
+}
@@ -0,0 +1,96 @@
+use gambit::{movegen, Board, Colour, Move};
+use gambit_engine::history::{self, History, Killers, MAX_HISTORY};
+use gambit_engine::thread::ThreadData;
+
+fn find(board: &Board, uci: &str) -> Move {
+    movegen::legal_moves(board)
+        .iter()
+        .copied()
+        .find(|mv| mv.to_string() == uci)
+        .unwrap()
+}
+
+#[test]
+fn scores_stay_bounded_under_repeated_bonuses() {
+    let board = Board::default();
+    let mv = find(&board, "g1f3");
+    let mut history = History::new();
+
+    for _ in 0..10_000 {
+        history.update_quiet(Colour::White, mv, None, history::bonus(20));
+    }
+
+    let score = history.quiet(Colour::White, mv, None);
+    assert!((1..=MAX_HISTORY).contains(&score), "{score}");
+
+    for _ in 0..10_000 {
+        history.update_quiet(Colour::White, mv, None, -history::bonus(20));
+    }
+
+    let score = history.quiet(Colour::White, mv, None);
+    assert!((-MAX_HISTORY..0).contains(&score), "{score}");
+}
+
+#[test]
+fn aging_halves_scores() {
+    let board = Board::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+    let mv = find(&board, "e4d5");
+    let mut history = History::new();
+
+    history.update_capture(Colour::White, mv, 1000);
+    let before = history.capture(Colour::White, mv);
+
+    history.age();
+
+    assert_eq!(history.capture(Colour::White, mv), before / 2);
+    assert_eq!(history.capture(Colour::Black, mv), 0);
+}
+
+#[test]
+fn killers_follow_the_game_into_the_next_search() {
+    let mut board = Board::default();
+    let mut keys = Vec::new();
+    let knight = find(&board, "g1f3");
+
+    let mut thread = ThreadData::default();
+    thread.continue_from(board.key(), &keys);
+    thread.killers.add(2, knight);
+    thread.killers.add(3, knight);
+
+    // The same position keeps its killers.
+    thread.continue_from(board.key(), &keys);
+    assert_eq!(thread.killers.get(2), [Some(knight), None]);
+
+    // Two plies on, the killers of the grandchildren become those of the root.
+    for uci in ["e2e4", "e7e5"] {
+        keys.push(board.key());
+        board.make_move(find(&board, uci));
+    }
+    thread.continue_from(board.key(), &keys);
+    assert_eq!(thread.killers.get(0), [Some(knight), None]);
+    assert_eq!(thread.killers.get(1), [Some(knight), None]);
+    assert_eq!(thread.killers.get(2), [None, None]);
+
+    // An unrelated position starts afresh.
+    let other = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    thread.continue_from(other.key(), &[]);
+    assert_eq!(thread.killers.get(0), [None, None]);
+}
+
+#[test]
+fn killers_keep_the_two_latest_moves() {
+    let board = Board::default();
+    let [first, second, third] = ["g1f3", "b1c3", "e2e4"].map(|uci| find(&board, uci));
+    let mut killers = Killers::new();
+
+    killers.add(0, first);
+    killers.add(0, first);
+    assert_eq!(killers.get(0), [Some(first), None]);
+
+    killers.add(0, second);
+    killers.add(0, third);
+    assert_eq!(killers.get(0), [Some(third), Some(second)]);
+
+    killers.shift(200);
+    assert_eq!(killers.get(0), [None, None]);
+}
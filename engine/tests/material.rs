@@ -0,0 +1,80 @@
+use gambit::Board;
+use gambit_engine::eval::{evaluate_position, evaluate_with_material};
+use gambit_engine::material::{analyse, MaterialTable};
+
+#[test]
+fn insufficient_material_is_a_draw() {
+    for fen in [
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4KB2 w - - 0 1",
+        "4k3/8/8/8/8/8/8/4KN2 b - - 0 1",
+        "4kb2/8/8/8/8/8/8/4KN2 w - - 0 1",
+        "4k3/8/8/8/8/8/8/3NKN2 w - - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(evaluate_position(&board), 0, "{fen}");
+    }
+
+    let bishops = Board::from_fen("4k3/8/8/8/8/8/8/3BKB2 w - - 0 1").unwrap();
+    assert!(evaluate_position(&bishops) > 0);
+}
+
+#[test]
+fn lone_kings_are_driven_to_the_edge() {
+    let centre = Board::from_fen("8/8/8/4k3/8/8/8/R3K3 w - - 0 1").unwrap();
+    let edge = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+    let near = Board::from_fen("4k3/8/4K3/8/8/8/8/R7 w - - 0 1").unwrap();
+
+    assert!(evaluate_position(&edge) > evaluate_position(&centre));
+    assert!(evaluate_position(&near) > evaluate_position(&edge));
+
+    let losing = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 b - - 0 1").unwrap();
+    assert_eq!(evaluate_position(&losing), -evaluate_position(&edge));
+}
+
+#[test]
+fn entries_are_shared_by_material_signature() {
+    let mut table = MaterialTable::new();
+    let board = Board::default();
+    let developed =
+        Board::from_fen("r1bqkb1r/pppp1ppp/2n2n2/4p3/4P3/2N2N2/PPPP1PPP/R1BQKB1R w KQkq - 4 4")
+            .unwrap();
+
+    let entry = table.probe(&board);
+    assert_eq!(entry.phase, 24);
+    assert_eq!(entry.imbalance, 0);
+    assert!(entry.endgame.is_none());
+
+    let entry = table.probe(&developed);
+    assert_eq!(
+        evaluate_with_material(&developed, &entry),
+        evaluate_position(&developed)
+    );
+
+    let bishop_pair = Board::from_fen("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1").unwrap();
+    assert!(analyse(&bishop_pair).imbalance > 0);
+    assert_eq!(analyse(&bishop_pair).phase, 2);
+}
+
+#[test]
+fn imbalances_depend_on_the_pawns() {
+    let imbalance = |fen| analyse(&Board::from_fen(fen).unwrap()).imbalance;
+
+    // The two minor pieces gain on the rook as pawns are added.
+    let closed = imbalance("r3k3/pppppppp/8/8/8/8/PPPPPPPP/2B1KN2 w - - 0 1");
+    let open = imbalance("r3k3/pp6/8/8/8/8/PP6/2B1KN2 w - - 0 1");
+    assert!(closed > open);
+
+    // The two bishops are worth more on an open board.
+    assert!(
+        imbalance("4k3/8/8/8/8/8/PP6/2B1KB2 w - - 0 1")
+            > imbalance("4k3/8/8/8/8/8/PPPPPPPP/2B1KB2 w - - 0 1")
+    );
+
+    // A second rook adds less than the first.
+    assert!(
+        imbalance("4k3/8/8/8/8/8/8/R3K2R w - - 0 1")
+            < 2 * imbalance("4k3/8/8/8/8/8/8/R3K3 w - - 0 1")
+    );
+    assert_eq!(imbalance("r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1"), 0);
+}
@@ -0,0 +1,116 @@
+use gambit_engine::meta::{self, OptionError, OptionInfo, OptionType, OptionValue};
+use gambit_engine::uci::UciSession;
+
+#[test]
+fn handshake_matches_the_uci_command() {
+    let handshake = meta::uci_handshake();
+
+    assert_eq!(handshake[0], format!("id name Gambit {}", meta::VERSION));
+    assert_eq!(handshake[1], "id author Joshua Clements");
+    assert_eq!(handshake.last().map(String::as_str), Some("uciok"));
+    assert_eq!(handshake.len(), meta::options().len() + 3);
+
+    assert_eq!(UciSession::new().handle("uci"), handshake);
+}
+
+#[test]
+fn options_format_as_uci_declarations() {
+    let spin = OptionInfo {
+        name: "Hash",
+        option_type: OptionType::Spin {
+            default: 16,
+            min: 1,
+            max: 65536,
+        },
+    };
+    let check = OptionInfo {
+        name: "Ponder",
+        option_type: OptionType::Check { default: false },
+    };
+
+    assert_eq!(
+        spin.to_string(),
+        "option name Hash type spin default 16 min 1 max 65536"
+    );
+    assert_eq!(
+        check.to_string(),
+        "option name Ponder type check default false"
+    );
+    assert!(meta::options().contains(&spin));
+    assert!(meta::options().contains(&check));
+}
+
+#[test]
+fn combos_and_buttons_format_as_uci_declarations() {
+    let combo = OptionInfo {
+        name: "Style",
+        option_type: OptionType::Combo {
+            default: "Normal",
+            values: &["Solid", "Normal", "Risky"],
+        },
+    };
+    let button = OptionInfo {
+        name: "Clear Hash",
+        option_type: OptionType::Button,
+    };
+
+    assert_eq!(
+        combo.to_string(),
+        "option name Style type combo default Normal var Solid var Normal var Risky"
+    );
+    assert_eq!(button.to_string(), "option name Clear Hash type button");
+}
+
+#[test]
+fn values_are_checked_against_the_option_type() {
+    let hash = meta::find_option("hash").unwrap();
+    let combo = OptionInfo {
+        name: "Style",
+        option_type: OptionType::Combo {
+            default: "Normal",
+            values: &["Solid", "Normal", "Risky"],
+        },
+    };
+    let button = OptionInfo {
+        name: "Clear Hash",
+        option_type: OptionType::Button,
+    };
+    let invalid = |name, value: &str| {
+        Err(OptionError::InvalidValue {
+            name,
+            value: value.to_owned(),
+        })
+    };
+
+    assert_eq!(hash.name, "Hash");
+    assert_eq!(hash.parse(Some("64")), Ok(OptionValue::Spin(64)));
+    assert_eq!(hash.parse(Some("0")), invalid("Hash", "0"));
+    assert_eq!(hash.parse(Some("lots")), invalid("Hash", "lots"));
+    assert_eq!(
+        hash.parse(None),
+        Err(OptionError::MissingValue { name: "Hash" })
+    );
+
+    let large_pages = meta::find_option("LargePages").unwrap();
+    assert_eq!(
+        large_pages.parse(Some("true")),
+        Ok(OptionValue::Check(true))
+    );
+    assert_eq!(large_pages.parse(Some("yes")), invalid("LargePages", "yes"));
+
+    let weights = meta::find_option("WeightsFile").unwrap();
+    assert_eq!(
+        weights.parse(Some("<empty>")),
+        Ok(OptionValue::String(String::new()))
+    );
+    assert_eq!(
+        weights.parse(Some("aggressive.toml")),
+        Ok(OptionValue::String("aggressive.toml".to_owned()))
+    );
+
+    assert_eq!(combo.parse(Some("risky")), Ok(OptionValue::Combo("Risky")));
+    assert_eq!(combo.parse(Some("Reckless")), invalid("Style", "Reckless"));
+    assert_eq!(button.parse(None), Ok(OptionValue::Button));
+
+    assert_eq!(meta::find_option("Colour"), None);
+}
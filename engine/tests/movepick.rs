@@ -0,0 +1,27 @@
+use gambit::{movegen, Board, Move, PieceKind, Square};
+use gambit_engine::history::History;
+use gambit_engine::movepick::MovePicker;
+
+#[test]
+fn killers_follow_captures_and_stale_ones_are_ignored() {
+    let board = Board::from_fen("4k3/8/8/3p4/4P3/8/8/4K1N1 w - - 0 1").unwrap();
+    let history = History::new();
+    let mv = |uci| board.parse_uci(uci).unwrap();
+
+    // A killer from a sibling where the pawn had already advanced.
+    let stale = Move::new(Square::E5, Square::E6, PieceKind::Pawn);
+
+    let picked: Vec<_> = MovePicker::new(
+        &board,
+        movegen::legal_moves(&board),
+        None,
+        [Some(stale), Some(mv("g1f3"))],
+        &history,
+        None,
+    )
+    .collect();
+
+    assert_eq!(picked.len(), movegen::legal_moves(&board).len());
+    assert_eq!(picked[..2], [mv("e4d5"), mv("g1f3")]);
+    assert!(!picked.contains(&stale));
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use gambit::Board;
+use gambit_engine::handicap::Rng;
+use gambit_engine::openings::{self, OpeningsError, Selection};
+
+const PGN: &str = "\
+[Event \"Italian\"]
+1. e4 e5 2. Nf3 Nc6 3. Bc4 *
+
+[Event \"Queen's Gambit\"]
+1. d4 d5 2. c4 *
+";
+
+const EPD: &str = "\
+# Two openings.
+rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2
+rnbqkbnr/ppp1pppp/8/3p4/3P4/8/PPP1PPPP/RNBQKBNR w KQkq - id \"d4 d5\";
+";
+
+#[test]
+fn suites_are_read_as_pgn_or_epd() {
+    let openings = openings::read_openings(Path::new("book.PGN"), PGN).unwrap();
+    assert_eq!(
+        openings
+            .iter()
+            .map(|board| board.fen().without_counters().to_string())
+            .collect::<Vec<_>>(),
+        [
+            "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R b KQkq -",
+            "rnbqkbnr/ppp1pppp/8/3p4/2PP4/8/PP2PPPP/RNBQKBNR b KQkq c3",
+        ]
+    );
+
+    let openings = openings::read_openings(Path::new("book.epd"), EPD).unwrap();
+    assert_eq!(openings.len(), 2);
+    assert_eq!(openings[1].side_to_move(), gambit::Colour::White);
+
+    assert!(matches!(
+        openings::read_openings(Path::new("book.epd"), PGN),
+        Err(OpeningsError::Epd(_))
+    ));
+    assert!(matches!(
+        openings::read_pgn("1. e5 *"),
+        Err(OpeningsError::Pgn(_))
+    ));
+}
+
+#[test]
+fn openings_are_shuffled_or_kept_in_order() {
+    // The white king on each square of the first rank.
+    let suite: Vec<Board> = ["K7", "1K6", "2K5", "3K4", "4K3", "5K2", "6K1", "7K"]
+        .iter()
+        .map(|rank| Board::from_fen(&format!("4k3/8/8/8/8/8/8/{rank} w - - 0 1")).unwrap())
+        .collect();
+
+    let mut sequential = suite.clone();
+    openings::select(&mut sequential, Selection::Sequential);
+    assert_eq!(sequential, suite);
+
+    let shuffle = |seed| {
+        let mut openings = suite.clone();
+        openings::select(&mut openings, Selection::Random(Rng::new(seed)));
+        openings
+    };
+    let shuffled = shuffle(7);
+    assert_eq!(shuffled, shuffle(7));
+    assert_ne!(shuffled, suite);
+    assert!(suite.iter().all(|board| shuffled.contains(board)));
+}
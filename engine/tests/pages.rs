@@ -0,0 +1,56 @@
+use gambit_engine::pages::PageBuffer;
+use gambit_engine::tt::TranspositionTable;
+use gambit_engine::{AllocationError, Engine, EngineOptions, SearchLimits};
+
+#[test]
+fn buffers_behave_like_slices_with_or_without_huge_pages() {
+    for huge_pages in [false, true] {
+        let mut buffer = PageBuffer::new(3 << 20, 7u64, huge_pages);
+
+        assert_eq!(buffer.len(), 3 << 20);
+        assert!(buffer.iter().all(|&value| value == 7));
+
+        buffer[12345] = 1;
+        buffer.fill(2);
+        assert!(buffer.iter().all(|&value| value == 2));
+
+        if !huge_pages {
+            assert!(!buffer.huge_pages());
+        }
+    }
+}
+
+#[test]
+fn searches_with_large_pages_fall_back_gracefully() {
+    let mut engine = Engine::new(EngineOptions {
+        large_pages: true,
+        ..EngineOptions::default()
+    });
+
+    let result = engine.search(SearchLimits {
+        depth: Some(4),
+        ..SearchLimits::default()
+    });
+    assert!(result.best_move.is_some());
+
+    engine.set_large_pages(false).unwrap();
+    assert!(!engine.uses_large_pages());
+    engine.set_hash(4).unwrap();
+    assert!(!engine.uses_large_pages());
+}
+
+#[test]
+fn tables_too_large_to_allocate_are_refused() {
+    assert!(PageBuffer::try_new(usize::MAX / 8, 0u64, false).is_none());
+    assert!(PageBuffer::try_new(usize::MAX / 8, 0u64, true).is_none());
+    assert!(TranspositionTable::try_new(usize::MAX, false).is_none());
+
+    let mut engine = Engine::default();
+    assert_eq!(
+        engine.set_hash(usize::MAX),
+        Err(AllocationError {
+            megabytes: usize::MAX
+        })
+    );
+    assert_eq!(engine.options().hash, EngineOptions::default().hash);
+}
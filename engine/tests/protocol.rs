@@ -0,0 +1,235 @@
+//! Drives the engine binary over its standard input and output, as a GUI would.
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long any single response may take before the test fails.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Process {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl Process {
+    fn spawn() -> Process {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_gambit"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (sender, lines) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Process {
+            child,
+            stdin,
+            lines,
+        }
+    }
+
+    fn send(&mut self, command: &str) {
+        writeln!(self.stdin, "{command}").unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    /// Reads lines up to and including the first that starts with `prefix`.
+    fn read_until(&mut self, prefix: &str) -> Vec<String> {
+        let deadline = Instant::now() + TIMEOUT;
+        let mut lines = Vec::new();
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = self
+                .lines
+                .recv_timeout(remaining)
+                .unwrap_or_else(|_| panic!("no `{prefix}` after {lines:?}"));
+
+            let done = line.starts_with(prefix);
+            lines.push(line);
+
+            if done {
+                return lines;
+            }
+        }
+    }
+
+    fn wait_for_exit(&mut self) {
+        let deadline = Instant::now() + TIMEOUT;
+
+        while self.child.try_wait().unwrap().is_none() {
+            assert!(Instant::now() < deadline, "the engine did not exit");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn handshake() {
+    let mut engine = Process::spawn();
+
+    engine.send("uci");
+    let lines = engine.read_until("uciok");
+
+    assert!(lines[0].starts_with("id name Gambit"));
+    assert!(lines[1].starts_with("id author"));
+    assert!(lines[2..lines.len() - 1]
+        .iter()
+        .all(|line| line.starts_with("option name")));
+
+    engine.send("isready");
+    assert_eq!(engine.read_until("readyok"), ["readyok"]);
+
+    engine.send("quit");
+    engine.wait_for_exit();
+}
+
+#[test]
+fn search_info_comes_before_bestmove() {
+    let mut engine = Process::spawn();
+
+    engine.send("ucinewgame");
+    engine.send("position startpos moves e2e4 e7e5 g1f3");
+    engine.send("go depth 4");
+
+    let lines = engine.read_until("bestmove");
+    let depths: Vec<&str> = lines
+        .iter()
+        .filter_map(|line| line.strip_prefix("info depth "))
+        .filter_map(|info| info.split(' ').next())
+        .collect();
+
+    assert_eq!(depths, ["1", "2", "3", "4"]);
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+}
+
+#[test]
+fn stop_and_ponderhit_end_open_ended_searches() {
+    let mut engine = Process::spawn();
+
+    for finish in ["stop", "ponderhit"] {
+        engine.send("position startpos");
+        engine.send(if finish == "stop" {
+            "go infinite"
+        } else {
+            "go ponder wtime 1000 btime 1000"
+        });
+
+        // Give the search time to start, so that the stop races a running search.
+        engine.send("isready");
+        engine.read_until("readyok");
+
+        let start = Instant::now();
+        engine.send(finish);
+        engine.read_until("bestmove");
+
+        assert!(start.elapsed() < Duration::from_secs(1), "{finish}");
+    }
+}
+
+#[test]
+fn ponderhit_keeps_searching_on_the_clock() {
+    let mut engine = Process::spawn();
+
+    engine.send("setoption name EasyMovePercent value 0");
+    engine.send("position startpos moves e2e4");
+    // A thirtieth of the clock gives the move 200 ms.
+    engine.send("go ponder wtime 6000 btime 6000");
+
+    // Pondering for longer than the budget uses none of it.
+    thread::sleep(Duration::from_millis(300));
+
+    let start = Instant::now();
+    engine.send("ponderhit");
+    engine.read_until("bestmove");
+
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(100), "{elapsed:?}");
+    assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+}
+
+#[test]
+fn movetime_is_respected() {
+    let mut engine = Process::spawn();
+
+    engine.send("position startpos");
+
+    let start = Instant::now();
+    engine.send("go movetime 200");
+    engine.read_until("bestmove");
+
+    // The move overhead is kept in reserve for the reply to reach the GUI.
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(150), "{elapsed:?}");
+    assert!(elapsed < Duration::from_millis(400), "{elapsed:?}");
+}
+
+#[test]
+fn malformed_input_is_reported_and_survived() {
+    let mut engine = Process::spawn();
+
+    for command in [
+        "position fen not a fen",
+        "position startpos moves e2e5",
+        "position sideways",
+        "setoption name Hash value lots",
+        "setoption name Colour value blue",
+        "setoption name Hash",
+        "xyzzy",
+    ] {
+        engine.send(command);
+        let lines = engine.read_until("info string error: ");
+        assert_eq!(lines.len(), 1, "{command}: {lines:?}");
+    }
+
+    // Input that is not UTF-8 is an unknown command like any other.
+    engine.stdin.write_all(b"\xff\xfe\n").unwrap();
+    engine.stdin.flush().unwrap();
+    assert_eq!(engine.read_until("info string error: ").len(), 1);
+
+    engine.send("");
+    engine.send("go depth two");
+    assert_eq!(
+        engine.read_until("info string"),
+        ["info string error: invalid value for depth: two"]
+    );
+    engine.send("stop");
+    engine.read_until("bestmove");
+
+    engine.send("isready");
+    engine.read_until("readyok");
+}
+
+#[test]
+fn quit_interrupts_a_search() {
+    let mut engine = Process::spawn();
+
+    engine.send("go infinite");
+    engine.send("quit");
+    engine.wait_for_exit();
+}
@@ -0,0 +1,53 @@
+use gambit::pgn::parse_games;
+use gambit_engine::puzzles::find_puzzles;
+use gambit_engine::Engine;
+
+fn puzzles(pgn: &str) -> Vec<String> {
+    let games = parse_games(pgn).unwrap();
+    let mut engine = Engine::default();
+
+    find_puzzles(&mut engine, &games[0])
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn finds_a_back_rank_mate_after_a_blunder() {
+    let puzzles = puzzles(
+        r#"[Event "Back rank"]
+[FEN "4r1k1/5ppp/8/8/8/8/5PPP/3R2K1 b - - 0 1"]
+
+1... Re7 2. Rd8+ Re8 3. Rxe8# 1-0"#,
+    );
+
+    assert_eq!(
+        puzzles,
+        [
+            r#"6k1/4rppp/8/8/8/8/5PPP/3R2K1 w - - bm Rd8+; pv Rd8+ Re8 Rxe8#; dm 2; id "Back rank ply 1"; c0 "mate mateIn2 backRankMate";"#
+        ]
+    );
+}
+
+#[test]
+fn finds_a_knight_fork() {
+    let puzzles = puzzles(
+        r#"[Event "Fork"]
+[FEN "q4k2/8/8/1N6/8/8/8/4K3 b - - 0 1"]
+
+1... Ke8 2. Nc7+ *"#,
+    );
+
+    assert_eq!(puzzles.len(), 1);
+    assert!(puzzles[0].contains("bm Nc7+;"), "{}", puzzles[0]);
+    assert!(
+        puzzles[0].ends_with(r#"c0 "material fork";"#),
+        "{}",
+        puzzles[0]
+    );
+}
+
+#[test]
+fn quiet_games_have_no_puzzles() {
+    assert!(puzzles("1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 *").is_empty());
+}
@@ -0,0 +1,114 @@
+use gambit::{Board, Move, PackedMove};
+use gambit_engine::search::MAX_PLY;
+use gambit_engine::tt::{Bound, Entry, TranspositionTable};
+use gambit_engine::{Pv, Score};
+
+/// Stores `mv` as the move of the position `board`.
+fn store(tt: &mut TranspositionTable, board: &Board, mv: PackedMove) {
+    tt.store(Entry {
+        key: board.key(),
+        mv,
+        score: Score::DRAW,
+        depth: 1,
+        bound: Bound::Exact,
+        pv: true,
+    });
+}
+
+/// Stores each of `moves`, in UCI notation, as the move of the position it is played in.
+fn store_line(tt: &mut TranspositionTable, board: &Board, moves: &[&str]) -> Vec<Move> {
+    let mut board = board.clone();
+
+    moves
+        .iter()
+        .map(|uci| {
+            let mv = board.parse_uci(uci).unwrap();
+            store(tt, &board, mv.pack());
+            board.make_move(mv);
+
+            mv
+        })
+        .collect()
+}
+
+#[test]
+fn push_front_splices_the_child_line() {
+    let board = Board::default();
+    let e4 = board.parse_uci("e2e4").unwrap();
+    let d4 = board.parse_uci("d2d4").unwrap();
+
+    let mut child = Pv::new();
+    child.push_front(d4, &Pv::new());
+    assert_eq!(child.len(), 1);
+
+    let mut pv = Pv::new();
+    pv.push_front(e4, &child);
+    assert_eq!(&pv[..], [e4, d4]);
+    assert_eq!(pv.to_string(), "e2e4 d2d4");
+
+    pv.clear();
+    assert!(pv.is_empty());
+    assert_eq!(pv.to_string(), "");
+}
+
+#[test]
+fn push_front_drops_moves_beyond_the_capacity() {
+    let e4 = Board::default().parse_uci("e2e4").unwrap();
+    let mut pv = Pv::new();
+
+    for _ in 0..MAX_PLY + 3 {
+        let child = pv.clone();
+        pv.push_front(e4, &child);
+    }
+
+    assert_eq!(pv.len(), MAX_PLY);
+}
+
+#[test]
+fn lines_are_completed_from_the_table() {
+    let board = Board::default();
+    let mut tt = TranspositionTable::new(1, false);
+    let line = store_line(&mut tt, &board, &["e2e4", "e7e5", "g1f3", "b8c6"]);
+
+    let mut pv = Pv::new();
+    pv.push_front(line[0], &Pv::new());
+    pv.complete(&board, &tt, 3);
+    assert_eq!(&pv[..], &line[..3]);
+
+    pv.complete(&board, &tt, MAX_PLY);
+    assert_eq!(&pv[..], &line[..]);
+}
+
+#[test]
+fn completion_stops_at_illegal_moves_and_repetitions() {
+    let board = Board::default();
+    let mut tt = TranspositionTable::new(1, false);
+
+    // The knights go out and back, which would lead round the same four positions forever.
+    let line = store_line(&mut tt, &board, &["g1f3", "g8f6", "f3g1"]);
+    let mut pv = Pv::new();
+    pv.complete(&board, &tt, MAX_PLY);
+    assert_eq!(&pv[..], &line[..]);
+
+    let mut back = board.clone();
+    line.iter().for_each(|&mv| back.make_move(mv));
+    store(&mut tt, &back, back.parse_uci("f6g8").unwrap().pack());
+
+    let mut pv = Pv::new();
+    pv.complete(&board, &tt, MAX_PLY);
+    assert_eq!(&pv[..], &line[..]);
+
+    // A move left by a colliding position, here a pawn push that the pin on d2 makes
+    // illegal, is not followed.
+    let pinned = Board::from_fen("4k3/8/8/8/1b6/8/3P4/4K3 w - - 0 1").unwrap();
+    let mut tt = TranspositionTable::new(1, false);
+    store(
+        &mut tt,
+        &pinned,
+        Board::default().parse_uci("d2d4").unwrap().pack(),
+    );
+
+    let mut pv = Pv::new();
+    pv.complete(&pinned, &tt, MAX_PLY);
+    assert!(pv.is_empty());
+}
@@ -0,0 +1,76 @@
+use gambit::pgn::parse_games;
+use gambit::{Board, Colour};
+use gambit_engine::repertoire::{self, LineStats, Repertoire, Statistics};
+
+const REPERTOIRE: &str = r#"
+[Opening "Italian"]
+1. e4 e5 2. Nf3 Nc6 3. Bc4 *
+
+[Opening "Scotch"]
+1. e4 e5 2. Nf3 Nc6 3. d4 *
+"#;
+
+fn repertoire() -> Repertoire {
+    Repertoire::new(&parse_games(REPERTOIRE).unwrap())
+}
+
+#[test]
+fn accepts_any_move_of_the_repertoire() {
+    let repertoire = repertoire();
+    let lines = repertoire.lines();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].name(), "Italian");
+
+    let mut board = Board::default();
+    for &mv in &lines[0].moves()[..4] {
+        board.make_move(mv);
+    }
+
+    assert!(repertoire.is_book_move(&board, board.parse_san("Bc4").unwrap()));
+    assert!(repertoire.is_book_move(&board, board.parse_san("d4").unwrap()));
+    assert!(!repertoire.is_book_move(&board, board.parse_san("Bb5").unwrap()));
+}
+
+#[test]
+fn trains_and_records_each_answer() {
+    let repertoire = repertoire();
+    let mut statistics = Statistics::default();
+    let mut output = Vec::new();
+
+    // The Italian line is trained first; the Scotch is abandoned after a typo.
+    let answers = "e4\nNf3\nBb5\ne4\nNf9\nquit\n";
+    repertoire::train(
+        &repertoire,
+        &mut statistics,
+        Colour::White,
+        answers.as_bytes(),
+        &mut output,
+    )
+    .unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("The repertoire plays Bc4."));
+    assert!(output.contains("invalid SAN move: Nf9"));
+
+    let lines = repertoire.lines();
+    assert_eq!(
+        statistics.get(&lines[0]),
+        LineStats {
+            asked: 3,
+            correct: 2
+        }
+    );
+    assert_eq!(
+        statistics.get(&lines[1]),
+        LineStats {
+            asked: 1,
+            correct: 1
+        }
+    );
+
+    // The Scotch has the better record, so the Italian comes up first again.
+    let statistics = Statistics::parse(&statistics.to_string());
+    assert_eq!(repertoire.schedule(&statistics), [0, 1]);
+    assert_eq!(statistics.get(&lines[1]).asked, 1);
+}
@@ -0,0 +1,36 @@
+use gambit::Board;
+use gambit_engine::root::RootMoves;
+use gambit_engine::{Pv, Score};
+
+#[test]
+fn iterations_reorder_by_score_then_effort() {
+    let board = Board::default();
+    let mv = |uci| board.parse_uci(uci).unwrap();
+    let (e4, d4, c4, nf3) = (mv("e2e4"), mv("d2d4"), mv("c2c4"), mv("g1f3"));
+
+    let mut root = RootMoves::new([e4, d4, c4, nf3]);
+    assert_eq!(root.order()[..], [e4, d4, c4, nf3]);
+
+    let mut pv = Pv::new();
+    pv.push_front(d4, &Pv::new());
+
+    // The first move is searched with the full window, and only d4 improves on it. The
+    // moves that failed low are told apart by how long they took to refute.
+    root.add_nodes(e4, 500);
+    root.set_score(e4, Score::new(20), &Pv::new());
+    root.add_nodes(d4, 400);
+    root.set_score(d4, Score::new(30), &pv);
+    root.add_nodes(c4, 50);
+    root.add_nodes(nf3, 200);
+    root.finish_iteration();
+
+    assert_eq!(root.order()[..], [d4, e4, nf3, c4]);
+
+    let best = root.best().unwrap();
+    assert_eq!(best.mv, d4);
+    assert_eq!(best.previous_score, Score::new(30));
+    assert_eq!(&best.pv[..], [d4]);
+    assert!(root
+        .iter()
+        .all(|root| root.nodes == 0 && root.score == -Score::INFINITE));
+}
@@ -0,0 +1,44 @@
+use gambit_engine::Score;
+
+#[test]
+fn mates_are_encoded_by_distance() {
+    assert!(Score::mate_in(1) > Score::mate_in(3));
+    assert!(Score::mated_in(2) < Score::mated_in(4));
+    assert_eq!(-Score::mate_in(5), Score::mated_in(5));
+
+    assert!(Score::mate_in(7).is_mate());
+    assert!(Score::mated_in(7).is_mate());
+    assert!(!Score::new(900).is_mate());
+    assert!(!Score::INFINITE.is_mate());
+
+    assert_eq!(Score::mate_in(1).mate_moves(), Some(1));
+    assert_eq!(Score::mate_in(3).mate_moves(), Some(2));
+    assert_eq!(Score::mated_in(2).mate_moves(), Some(-1));
+    assert_eq!(Score::DRAW.mate_moves(), None);
+}
+
+#[test]
+fn formats_as_uci() {
+    assert_eq!(Score::new(35).to_string(), "cp 35");
+    assert_eq!(Score::new(-120).to_string(), "cp -120");
+    assert_eq!(Score::mate_in(5).to_string(), "mate 3");
+    assert_eq!(Score::mated_in(4).to_string(), "mate -2");
+}
+
+#[test]
+fn arithmetic_saturates() {
+    assert_eq!(Score::INFINITE + 1, Score::INFINITE);
+    assert_eq!(-Score::INFINITE - i32::MAX, -Score::INFINITE);
+    assert_eq!(Score::new(i32::MIN), -Score::INFINITE);
+    assert_eq!(Score::new(100) + 50 - 200, Score::new(-50));
+}
+
+#[test]
+fn node_relative_mates_round_trip() {
+    let score = Score::mate_in(9);
+
+    assert_eq!(score.to_node(4), Score::mate_in(5));
+    assert_eq!(score.to_node(4).from_node(4), score);
+    assert_eq!(Score::mated_in(9).to_node(4), Score::mated_in(5));
+    assert_eq!(Score::new(250).to_node(4), Score::new(250));
+}
@@ -0,0 +1,44 @@
+use std::sync::atomic::AtomicBool;
+
+use gambit::Board;
+use gambit_engine::search::Search;
+use gambit_engine::thread::ThreadData;
+use gambit_engine::tt::TranspositionTable;
+use gambit_engine::{EngineOptions, SearchLimits, SearchResult};
+
+/// Searches the starting position with the stop flag raised before the search begins.
+fn search_stopped_from_the_start(check_interval: u64) -> SearchResult {
+    let mut tt = TranspositionTable::new(1, false);
+    let mut thread = ThreadData::default();
+    let options = EngineOptions {
+        check_interval,
+        ..EngineOptions::default()
+    };
+    let limits = SearchLimits::default();
+    let stop = AtomicBool::new(true);
+    let mut progress = |_| {};
+
+    Search::new(
+        &mut tt,
+        &mut thread,
+        &options,
+        &limits,
+        &stop,
+        Vec::new(),
+        &mut progress,
+    )
+    .run(&Board::default())
+}
+
+#[test]
+fn smaller_check_intervals_stop_sooner() {
+    // The first iteration takes fewer nodes than the default interval, so the stop is only
+    // seen once it is over.
+    let default = search_stopped_from_the_start(EngineOptions::default().check_interval);
+    assert_eq!(default.depth, 1);
+
+    let every_node = search_stopped_from_the_start(1);
+    assert_eq!(every_node.depth, 0);
+    assert!(every_node.nodes <= 1, "{}", every_node.nodes);
+    assert!(every_node.best_move.is_some());
+}
@@ -0,0 +1,53 @@
+use gambit::{movegen, Board, Move};
+use gambit_engine::see::{see, see_value};
+
+fn find(board: &Board, uci: &str) -> Move {
+    movegen::legal_moves(board)
+        .iter()
+        .copied()
+        .find(|mv| mv.to_string() == uci)
+        .unwrap()
+}
+
+/// Asserts that the exchange started by `uci` is worth exactly `value`.
+fn assert_see(fen: &str, uci: &str, value: i32) {
+    let board = Board::from_fen(fen).unwrap();
+    let mv = find(&board, uci);
+
+    assert!(see(&board, mv, value), "{uci} should win at least {value}");
+    assert!(
+        !see(&board, mv, value + 1),
+        "{uci} should win at most {value}"
+    );
+    assert_eq!(see_value(&board, mv), value, "{uci}");
+}
+
+#[test]
+fn undefended_capture_wins_the_victim() {
+    assert_see(
+        "1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1",
+        "e1e5",
+        100,
+    );
+}
+
+#[test]
+fn exchange_sequence_with_xrays() {
+    assert_see(
+        "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1",
+        "d3e5",
+        -220,
+    );
+}
+
+#[test]
+fn quiet_move_to_a_defended_square_loses_the_piece() {
+    assert_see(gambit::STARTING_POSITION_FEN, "g1f3", 0);
+    assert_see("4k3/8/4p3/8/8/8/8/3QK3 w - - 0 1", "d1d5", -900);
+}
+
+#[test]
+fn king_only_recaptures_on_an_undefended_square() {
+    assert_see("3rk3/8/8/8/3q4/4K3/8/3R4 w - - 0 1", "d1d4", 900);
+    assert_see("3rk3/b7/8/8/3q4/4K3/8/3R4 w - - 0 1", "d1d4", 900 - 500);
+}
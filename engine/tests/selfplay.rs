@@ -0,0 +1,197 @@
+use gambit::pgn::parse_games;
+use gambit::Board;
+use gambit_engine::selfplay::{
+    play_game, play_match, Adjudication, MatchScore, MatchSettings, Outcome, Player, Sprt,
+    Termination, TimeControl,
+};
+use gambit_engine::{Engine, EngineOptions, SearchLimits};
+
+const BACK_RANK: &str = "6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1";
+
+fn depth(depth: u8) -> TimeControl {
+    TimeControl::PerMove(SearchLimits {
+        depth: Some(depth),
+        ..SearchLimits::default()
+    })
+}
+
+fn play(fen: &str, adjudication: &Adjudication) -> gambit_engine::selfplay::GameRecord {
+    play_game(
+        &mut Engine::default(),
+        &mut Engine::default(),
+        &Board::from_fen(fen).unwrap(),
+        &depth(3),
+        adjudication,
+    )
+}
+
+#[test]
+fn games_end_by_the_rules() {
+    let record = play(BACK_RANK, &Adjudication::default());
+    assert_eq!(record.outcome, Outcome::WhiteWins);
+    assert_eq!(record.termination, Termination::Checkmate);
+    assert_eq!(record.moves.len(), 1);
+    assert_eq!(record.moves[0].mv.to_string(), "d1d8");
+    assert_eq!(record.moves[0].depth, 3);
+
+    let record = play("7k/8/6Q1/8/8/8/8/K7 b - - 0 1", &Adjudication::default());
+    assert_eq!(
+        (record.outcome, record.termination),
+        (Outcome::Draw, Termination::Stalemate)
+    );
+
+    let record = play("7k/8/8/8/8/8/8/K5N1 w - - 0 1", &Adjudication::default());
+    assert_eq!(
+        (record.outcome, record.termination),
+        (Outcome::Draw, Termination::Material)
+    );
+    assert!(record.moves.is_empty());
+}
+
+#[test]
+fn clear_results_are_adjudicated() {
+    let queens_up = "4k3/pppp4/8/8/8/8/PPPP4/QQ2K3 w - - 0 1";
+
+    let record = play(
+        queens_up,
+        &Adjudication {
+            win_moves: 2,
+            ..Adjudication::default()
+        },
+    );
+    assert_eq!(
+        (record.outcome, record.termination),
+        (Outcome::WhiteWins, Termination::Adjudication)
+    );
+    assert_eq!(record.moves.len(), 4);
+
+    let record = play(
+        queens_up,
+        &Adjudication {
+            win_score: 10_000,
+            max_moves: 3,
+            ..Adjudication::default()
+        },
+    );
+    assert_eq!(
+        (record.outcome, record.termination),
+        (Outcome::Draw, Termination::MoveLimit)
+    );
+    assert_eq!(record.moves.len(), 6);
+}
+
+#[test]
+fn games_are_written_as_pgn() {
+    let record = play(BACK_RANK, &Adjudication::default());
+    let pgn = record.to_pgn("First", "Second", 3);
+
+    assert!(pgn.contains("[Round \"3\"]\n[White \"First\"]\n[Black \"Second\"]\n"));
+    assert!(pgn.contains(&format!("[FEN \"{BACK_RANK}\"]\n")));
+    assert!(pgn.contains("1. Rd8# {+M1/3 "));
+    assert!(pgn.ends_with("s, checkmate} 1-0\n"));
+
+    let games = parse_games(&pgn).unwrap();
+    assert_eq!(games[0].result(), Some("1-0"));
+    assert_eq!(games[0].moves(), [record.moves[0].mv]);
+}
+
+#[test]
+fn matches_play_each_opening_from_both_sides() {
+    let player = |name: &str| Player {
+        name: name.to_owned(),
+        options: EngineOptions::default(),
+    };
+    let settings = MatchSettings {
+        games: 6,
+        time_control: depth(2),
+        adjudication: Adjudication::default(),
+        concurrency: 2,
+        sprt: None,
+    };
+    // White mates at once in the first opening and Black in the second.
+    let openings = [
+        Board::from_fen(BACK_RANK).unwrap(),
+        Board::from_fen("3r2k1/5ppp/8/8/8/8/5PPP/6K1 b - - 0 1").unwrap(),
+    ];
+    let mut games = Vec::new();
+
+    let score = play_match(
+        [&player("first"), &player("second")],
+        &openings,
+        &settings,
+        |game, record, _| games.push((game, record.start.clone(), record.outcome)),
+    );
+
+    games.sort_by_key(|(game, _, _)| *game);
+    let expected: Vec<_> = [0, 0, 1, 1, 0, 0]
+        .into_iter()
+        .enumerate()
+        .map(|(game, opening)| {
+            let outcome = match opening {
+                0 => Outcome::WhiteWins,
+                _ => Outcome::BlackWins,
+            };
+            (game, openings[opening].clone(), outcome)
+        })
+        .collect();
+    assert_eq!(games, expected);
+    assert_eq!(
+        score,
+        MatchScore {
+            wins: 3,
+            draws: 0,
+            losses: 3,
+        }
+    );
+}
+
+#[test]
+fn elo_and_likelihood_ratios_follow_the_score() {
+    let even = MatchScore {
+        wins: 30,
+        draws: 40,
+        losses: 30,
+    };
+    let (elo, margin) = even.elo().unwrap();
+    assert!(elo.abs() < 1e-9);
+    assert!((margin - 53.2).abs() < 0.1, "{margin}");
+
+    let ahead = MatchScore {
+        wins: 50,
+        draws: 40,
+        losses: 10,
+    };
+    let (elo, _) = ahead.elo().unwrap();
+    assert!((elo - 147.2).abs() < 0.1, "{elo}");
+
+    assert!(ahead.llr(0.0, 5.0) > 0.0);
+    assert!(even.llr(0.0, 5.0) < 0.0);
+    assert_eq!(MatchScore::default().llr(0.0, 5.0), 0.0);
+    assert_eq!(
+        MatchScore {
+            wins: 3,
+            ..MatchScore::default()
+        }
+        .elo(),
+        None
+    );
+
+    let sprt = Sprt::new(0.0, 5.0);
+    let (lower, upper) = sprt.bounds();
+    assert!((lower + 2.944).abs() < 1e-3 && (upper - 2.944).abs() < 1e-3);
+    assert_eq!(sprt.decide(ahead), None);
+    assert_eq!(sprt.decide(even), None);
+
+    let scaled = |score: MatchScore| MatchScore {
+        wins: score.wins * 10,
+        draws: score.draws * 10,
+        losses: score.losses * 10,
+    };
+    let behind = MatchScore {
+        wins: ahead.losses,
+        draws: ahead.draws,
+        losses: ahead.wins,
+    };
+    assert_eq!(sprt.decide(scaled(ahead)), Some(true));
+    assert_eq!(sprt.decide(scaled(behind)), Some(false));
+}
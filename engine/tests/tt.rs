@@ -0,0 +1,55 @@
+use gambit::PackedMove;
+use gambit_engine::tt::{Bound, Entry, TranspositionTable};
+use gambit_engine::Score;
+
+fn entry(key: u64, depth: u8, pv: bool) -> Entry {
+    Entry {
+        key,
+        mv: PackedMove::default(),
+        score: Score::new(25),
+        depth,
+        bound: if pv { Bound::Exact } else { Bound::Lower },
+        pv,
+    }
+}
+
+#[test]
+fn bounds_cut_off_on_their_own_side_of_the_window() {
+    let (alpha, beta) = (Score::new(-50), Score::new(50));
+
+    assert!(Bound::Exact.cuts_off(Score::new(0), alpha, beta));
+    assert!(Bound::Lower.cuts_off(Score::new(50), alpha, beta));
+    assert!(!Bound::Lower.cuts_off(Score::new(49), alpha, beta));
+    assert!(Bound::Upper.cuts_off(Score::new(-50), alpha, beta));
+    assert!(!Bound::Upper.cuts_off(Score::new(-49), alpha, beta));
+}
+
+#[test]
+fn pv_entries_survive_shallower_entries_of_the_same_search() {
+    // Without any memory the table has a single slot, which every key shares.
+    let mut tt = TranspositionTable::new(0, false);
+    tt.new_search();
+
+    tt.store(entry(1, 8, true));
+    tt.store(entry(2, 4, false));
+    assert!(tt.probe(1).is_some());
+    assert!(tt.probe(2).is_none());
+
+    // The same position, deeper entries and other PV entries still replace it.
+    tt.store(entry(1, 2, false));
+    assert_eq!(tt.probe(1).map(|entry| entry.pv), Some(false));
+
+    tt.store(entry(3, 8, true));
+    tt.store(entry(4, 9, false));
+    assert!(tt.probe(4).is_some());
+
+    tt.store(entry(5, 8, true));
+    tt.store(entry(6, 1, true));
+    assert!(tt.probe(6).is_some());
+
+    // A later search no longer protects it.
+    tt.store(entry(7, 8, true));
+    tt.new_search();
+    tt.store(entry(8, 1, false));
+    assert!(tt.probe(8).is_some());
+}
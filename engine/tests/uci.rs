@@ -0,0 +1,515 @@
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use gambit_engine::uci::{EngineState, UciSession};
+
+#[test]
+fn handshake_and_search() {
+    let mut session = UciSession::new();
+
+    let lines = session.handle("uci");
+    assert!(lines[0].starts_with("id name Gambit"));
+    assert_eq!(lines.last().map(String::as_str), Some("uciok"));
+    assert_eq!(session.handle("isready"), ["readyok"]);
+
+    assert!(session
+        .handle("position fen 6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1")
+        .is_empty());
+
+    let lines = session.handle("go depth 3");
+    assert!(lines[0].starts_with("info depth 1 score mate 1"));
+    assert_eq!(lines.last().map(String::as_str), Some("bestmove d1d8"));
+}
+
+#[test]
+fn search_info_reports_how_full_the_hash_is() {
+    let mut session = UciSession::new();
+    session.handle("setoption name Hash value 1");
+
+    let lines = session.handle("go depth 6");
+    let hashfull: Vec<u32> = lines
+        .iter()
+        .filter(|line| line.starts_with("info depth ") && line.contains(" pv "))
+        .map(|line| {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let index = tokens
+                .iter()
+                .position(|&token| token == "hashfull")
+                .unwrap();
+
+            tokens[index + 1].parse().unwrap()
+        })
+        .collect();
+
+    assert_eq!(hashfull.len(), 6);
+    assert!(hashfull.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(hashfull[5] > 0);
+}
+
+#[test]
+fn stop_only_interrupts_its_own_search() {
+    let mut session = UciSession::new();
+    let mut observer = session.observer();
+    let script = ["go depth 2", "go infinite", "stop"];
+
+    for command in script {
+        observer.observe(command);
+    }
+
+    let finished = session.handle(script[0]);
+    assert!(finished.iter().any(|line| line.starts_with("info depth 2")));
+
+    let stopped = session.handle(script[1]);
+    assert!(stopped.last().unwrap().starts_with("bestmove "));
+    assert!(!stopped.iter().any(|line| line.starts_with("info depth 2")));
+}
+
+#[test]
+fn infinite_searches_wait_for_stop() {
+    let mut session = UciSession::new();
+    let mut observer = session.observer();
+    // With no moves to search, the search reaches its maximum depth long before the stop.
+    session.handle("position fen 3R2k1/5ppp/8/8/8/8/5PPP/6K1 b - - 0 1");
+
+    let (sender, lines) = mpsc::channel();
+
+    thread::scope(|scope| {
+        observer.observe("go infinite");
+        scope.spawn(|| {
+            session.handle_with("go infinite", &mut |line| sender.send(line).unwrap());
+        });
+
+        thread::sleep(Duration::from_millis(300));
+        assert!(!lines
+            .try_iter()
+            .any(|line: String| line.starts_with("bestmove")));
+
+        observer.observe("stop");
+    });
+
+    assert!(lines.try_iter().any(|line| line.starts_with("bestmove")));
+}
+
+#[test]
+fn isready_is_answered_during_a_search() {
+    let mut session = UciSession::new();
+    let mut observer = session.observer();
+
+    assert_eq!(observer.observe("isready"), None);
+    assert_eq!(session.handle("isready"), ["readyok"]);
+
+    assert_eq!(observer.observe("go depth 1"), None);
+    assert_eq!(observer.observe("isready"), Some("readyok".to_owned()));
+
+    session.handle("go depth 1");
+    assert_eq!(observer.observe("isready"), None);
+}
+
+#[test]
+fn ponders_in_the_background_after_an_early_answer() {
+    let mut session = UciSession::new();
+    let mut observer = session.observer();
+    // Taking the queen is the only move, which is answered long before its time is up.
+    for command in [
+        "setoption name Ponder value true",
+        "position fen 7k/8/8/8/8/8/6q1/R6K w - - 0 1",
+    ] {
+        assert_eq!(observer.observe(command), None);
+        session.handle(command);
+    }
+
+    let go = "go wtime 3000 btime 60000";
+    let (sender, lines) = mpsc::channel();
+    observer.observe(go);
+
+    thread::scope(|scope| {
+        let search = scope.spawn(|| {
+            session.handle_with(go, &mut |line| sender.send(line).unwrap());
+            Instant::now()
+        });
+
+        let best = lines.iter().find(|line| line.starts_with("bestmove"));
+        assert!(best.unwrap().starts_with("bestmove h1g2 ponder "));
+
+        // The session goes on searching on the opponent's time until the next command.
+        thread::sleep(Duration::from_millis(300));
+        assert!(!search.is_finished());
+
+        let read = Instant::now();
+        assert_eq!(observer.observe("isready"), None);
+        let finished = search.join().unwrap();
+        assert!(finished.duration_since(read) < Duration::from_millis(200));
+    });
+
+    assert_eq!(session.handle("isready"), ["readyok"]);
+    assert!(lines.try_iter().all(|line| !line.starts_with("info")));
+}
+
+#[test]
+fn answers_without_pondering_unless_allowed() {
+    let mut session = UciSession::new();
+    let mut observer = session.observer();
+    let position = "position fen 7k/8/8/8/8/8/6q1/R6K w - - 0 1";
+    let go = "go wtime 3000 btime 60000";
+
+    let start = Instant::now();
+    for command in [position, go] {
+        observer.observe(command);
+        session.handle(command);
+    }
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    // Nothing can stop a background search in a session that has no observer.
+    let mut session = UciSession::new();
+    session.handle("setoption name Ponder value true");
+    session.handle(position);
+
+    let start = Instant::now();
+    let lines = session.handle(go);
+    assert!(lines.last().unwrap().starts_with("bestmove h1g2"));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn isready_is_answered_at_once_while_a_search_runs() {
+    let mut session = UciSession::new();
+    let mut observer = session.observer();
+    let (sender, lines) = mpsc::channel();
+
+    thread::scope(|scope| {
+        observer.observe("go infinite");
+        scope.spawn(|| {
+            session.handle_with("go infinite", &mut |line| sender.send(line).unwrap());
+        });
+
+        for _ in 0..100 {
+            let start = Instant::now();
+            assert_eq!(observer.observe("isready"), Some("readyok".to_owned()));
+            assert!(start.elapsed() < Duration::from_millis(50));
+            thread::sleep(Duration::from_millis(2));
+        }
+
+        observer.observe("stop");
+    });
+
+    assert!(lines.try_iter().any(|line| line.starts_with("bestmove")));
+    assert_eq!(observer.observe("isready"), None);
+}
+
+#[test]
+fn debug_toggles_search_diagnostics() {
+    let mut session = UciSession::new();
+    let diagnostics = |lines: &[String]| {
+        lines
+            .iter()
+            .filter(|line| line.starts_with("info string depth"))
+            .count()
+    };
+
+    assert_eq!(diagnostics(&session.handle("go depth 3")), 0);
+
+    assert!(session.handle("debug on").is_empty());
+    let lines = session.handle("go depth 3 wtime 60000 btime 60000");
+    assert_eq!(diagnostics(&lines), 3);
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("info string time budget")));
+
+    assert!(session.handle("debug off").is_empty());
+    assert_eq!(diagnostics(&session.handle("go depth 3")), 0);
+
+    assert_eq!(
+        session.handle("debug maybe"),
+        ["info string error: expected debug on or off"]
+    );
+}
+
+#[test]
+fn positions_can_be_set_up_by_hand() {
+    let mut session = UciSession::new();
+
+    for command in [
+        "clearboard",
+        "setpiece g1 K",
+        "setpiece d1 R",
+        "setpiece f2 P",
+        "setpiece g2 P",
+        "setpiece h2 P",
+        "setpiece g8 k",
+        "setpiece f7 p",
+        "setpiece g7 p",
+        "setpiece h7 p",
+        "setpiece e4 N",
+        "clear e4",
+        "sidetomove b",
+        "sidetomove w",
+    ] {
+        assert!(session.handle(command).is_empty(), "{command}");
+    }
+
+    let lines = session.handle("d");
+    assert_eq!(lines[0], "8  . . . . . . k .");
+    assert_eq!(
+        lines.last().map(String::as_str),
+        Some("Fen: 6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1")
+    );
+
+    assert_eq!(
+        session.handle("setpiece e8 P"),
+        ["info string error: pawns cannot stand on the back ranks"]
+    );
+    assert_eq!(
+        session.handle("castling K"),
+        ["info string error: castling right K needs its king and rook in place"]
+    );
+    assert_eq!(
+        session.handle("clear z9"),
+        ["info string error: invalid square"]
+    );
+
+    let lines = session.handle("go depth 3");
+    assert_eq!(lines.last().map(String::as_str), Some("bestmove d1d8"));
+}
+
+#[test]
+fn moves_lists_the_legal_moves_best_ordered_first() {
+    let mut session = UciSession::new();
+    session.handle("position fen 4k3/8/8/3p4/8/2N5/8/R3K3 w Q - 0 1");
+
+    let lines = session.handle("moves");
+    let rows: Vec<Vec<&str>> = lines
+        .iter()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+
+    assert_eq!(rows[0], ["san", "uci", "see", "check", "order"]);
+    assert_eq!(rows.len(), 1 + 24);
+    assert_eq!(rows[1][..4], ["Nxd5", "c3d5", "100", "no"]);
+    assert!(rows.contains(&vec!["Ra8+", "a1a8", "0", "yes", "0"]));
+    assert!(rows.contains(&vec!["O-O-O", "e1c1", "0", "no", "0"]));
+
+    // The best move of a search is tried first next time.
+    session.handle("go depth 3");
+    let best = session.handle("moves")[1]
+        .split_whitespace()
+        .nth(4)
+        .map(str::to_owned);
+    assert_eq!(best.as_deref(), Some(&*i32::MAX.to_string()));
+}
+
+#[test]
+fn weights_files_are_loaded_or_reported() {
+    let mut session = UciSession::new();
+    let path = std::env::temp_dir().join(format!("gambit-weights-{}.toml", std::process::id()));
+    std::fs::write(&path, "hanging = 80\n").unwrap();
+
+    let command = format!("setoption name WeightsFile value {}", path.display());
+    assert!(session.handle(&command).is_empty());
+    std::fs::write(&path, "hanging = lots\n").unwrap();
+    assert_eq!(
+        session.handle(&command),
+        [format!(
+            "info string error: {}: line 1: invalid number",
+            path.display()
+        )]
+    );
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(session
+        .handle("setoption name WeightsFile value <empty>")
+        .is_empty());
+}
+
+#[test]
+fn hash_changes_are_confirmed() {
+    let mut session = UciSession::new();
+
+    assert_eq!(
+        session.handle("setoption name Hash value 2"),
+        ["info string hash resized to 2 MB"]
+    );
+    assert_eq!(
+        session.handle("setoption name Clear Hash"),
+        ["info string hash cleared"]
+    );
+    assert_eq!(
+        session.handle("setoption name clear hash value now"),
+        ["info string hash cleared"]
+    );
+}
+
+#[test]
+fn out_of_order_commands_follow_the_engine_state() {
+    let mut session = UciSession::new();
+    let mut observer = session.observer();
+
+    // Stray stops and ponderhits while idle change nothing.
+    observer.observe("stop");
+    observer.observe("ponderhit");
+    assert_eq!(observer.state(), EngineState::Idle);
+
+    // A ponderhit does not cut short an ordinary search.
+    observer.observe("go depth 3");
+    assert_eq!(observer.state(), EngineState::Searching);
+    observer.observe("ponderhit");
+    assert_eq!(observer.state(), EngineState::Searching);
+
+    let lines = session.handle("go depth 3");
+    assert!(lines.iter().any(|line| line.starts_with("info depth 3")));
+    assert_eq!(observer.state(), EngineState::Idle);
+
+    // A second go stops a ponder search, which would otherwise never end, and a position
+    // sent meanwhile waits for the next search.
+    let script = ["go ponder", "position startpos moves e2e4", "go depth 1"];
+    for command in script {
+        observer.observe(command);
+    }
+    assert_eq!(observer.state(), EngineState::Searching);
+
+    let pondered = session.handle(script[0]);
+    assert!(pondered.last().unwrap().starts_with("bestmove "));
+    assert!(session.handle(script[1]).is_empty());
+    let lines = session.handle(script[2]);
+    assert!(lines.last().unwrap().starts_with("bestmove "));
+    assert_eq!(observer.state(), EngineState::Idle);
+
+    // A ponderhit puts the ponder search on the clock rather than stopping it.
+    let ponder = "go ponder wtime 1000 btime 1000";
+    observer.observe(ponder);
+    assert_eq!(observer.state(), EngineState::Pondering);
+    observer.observe("ponderhit");
+    assert_eq!(observer.state(), EngineState::Searching);
+    assert!(session
+        .handle(ponder)
+        .last()
+        .unwrap()
+        .starts_with("bestmove "));
+    assert_eq!(observer.state(), EngineState::Idle);
+}
+
+#[test]
+fn easy_moves_can_be_turned_off() {
+    let mut session = UciSession::new();
+
+    assert!(session
+        .handle("setoption name EasyMovePercent value 0")
+        .is_empty());
+    assert_eq!(
+        session.handle("setoption name EasyMovePercent value 101"),
+        ["info string error: invalid value for EasyMovePercent: 101"]
+    );
+
+    // With easy moves off, the only move still uses its share of the clock.
+    session.handle("position fen 7k/8/8/8/8/8/6q1/7K w - - 0 1");
+    let lines = session.handle("go wtime 3000 btime 3000");
+    assert!(lines.iter().any(|line| line.starts_with("info depth 2")));
+    assert!(lines.last().unwrap().starts_with("bestmove h1g2"));
+}
+
+#[test]
+fn nodestime_turns_the_clock_into_nodes() {
+    let search = || {
+        let mut session = UciSession::new();
+        assert!(session
+            .handle("setoption name nodestime value 1")
+            .is_empty());
+        session.handle("position startpos moves e2e4");
+
+        // Everything but the timings, which are all that may differ between runs.
+        session
+            .handle("go wtime 30000 btime 30000")
+            .into_iter()
+            .map(|line| {
+                let tokens: Vec<_> = line.split_whitespace().collect();
+                let mut kept = Vec::new();
+                let mut tokens = tokens.iter();
+
+                while let Some(&token) = tokens.next() {
+                    match token {
+                        "nps" | "time" => drop(tokens.next()),
+                        _ => kept.push(token.to_owned()),
+                    }
+                }
+
+                kept
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let lines = search();
+    assert_eq!(lines, search());
+
+    // A thirtieth of the clock is a second, worth a thousand nodes.
+    for line in lines.iter().filter(|line| line[1] == "depth") {
+        let nodes = line.iter().position(|token| token == "nodes").unwrap();
+        assert!(line[nodes + 1].parse::<u64>().unwrap() <= 1000, "{line:?}");
+    }
+}
+
+#[test]
+fn go_nodes_stops_within_the_first_iteration() {
+    let mut session = UciSession::new();
+    session.handle("position startpos");
+
+    // A single node is not enough to finish depth 1, so no iteration is reported.
+    let lines = session.handle("go nodes 1");
+    assert_eq!(lines.len(), 1, "{lines:?}");
+    assert!(lines[0].starts_with("bestmove "));
+}
+
+#[test]
+fn analyse_mode_neither_blunders_nor_cuts_searches_short() {
+    let mut session = UciSession::new();
+
+    for option in ["UCI_AnalyseMode value true", "BlunderChance value 100"] {
+        assert!(session
+            .handle(&format!("setoption name {option}"))
+            .is_empty());
+    }
+
+    session.handle("position fen 6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1");
+    let lines = session.handle("go depth 3");
+    assert_eq!(lines.last().map(String::as_str), Some("bestmove d1d8"));
+
+    // An only move would be played as soon as its easy move time had passed, rather than
+    // after the whole budget of a thirtieth of the clock.
+    session.handle("position fen 7k/8/8/8/8/8/6q1/7K w - - 0 1");
+    let start = Instant::now();
+    let lines = session.handle("go wtime 3000 btime 3000");
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert!(lines.last().unwrap().starts_with("bestmove h1g2"));
+}
+
+#[test]
+fn search_info_can_be_written_as_json() {
+    let mut session = UciSession::new();
+
+    assert!(session
+        .handle("setoption name InfoFormat value json")
+        .is_empty());
+    assert_eq!(
+        session.handle("setoption name InfoFormat value xml"),
+        ["info string error: invalid value for InfoFormat: xml"]
+    );
+
+    session.handle("position fen 6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1");
+    let lines = session.handle("go depth 3");
+    let (best, info) = lines.split_last().unwrap();
+
+    assert_eq!(best, "bestmove d1d8");
+    assert_eq!(info.len(), 3);
+
+    for (depth, line) in (1..).zip(info) {
+        assert!(
+            line.starts_with(&format!(
+                "{{\"depth\": {depth}, \"score\": {{\"mate\": 1}}, "
+            )),
+            "{line}"
+        );
+        assert!(line.ends_with(", \"pv\": [\"d1d8\"]}"), "{line}");
+    }
+
+    session.handle("setoption name InfoFormat value UCI");
+    assert!(session.handle("go depth 1")[0].starts_with("info depth 1"));
+}
@@ -0,0 +1,44 @@
+/*
+ * C bindings for the Gambit chess engine.
+ *
+ * Engines are opaque handles created with gambit_engine_new and released with
+ * gambit_engine_free. Strings are NUL-terminated UTF-8. Functions returning strings copy
+ * them into a caller-provided buffer and return the full length, like snprintf.
+ */
+
+#ifndef GAMBIT_H
+#define GAMBIT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define GAMBIT_OK 0
+#define GAMBIT_INVALID_ARGUMENT -1
+#define GAMBIT_INVALID_FEN -2
+#define GAMBIT_ILLEGAL_MOVE -3
+
+typedef struct GambitEngine GambitEngine;
+
+/* Returns NULL if hash_mb is zero. */
+GambitEngine *gambit_engine_new(size_t hash_mb);
+void gambit_engine_free(GambitEngine *engine);
+
+/* moves holds UCI moves separated by spaces and may be NULL. */
+int gambit_engine_set_position(GambitEngine *engine, const char *fen, const char *moves);
+
+/* A limit of zero is no limit. */
+int gambit_engine_search(GambitEngine *engine, uint8_t depth, uint64_t nodes, uint64_t movetime_ms);
+
+int32_t gambit_engine_score(const GambitEngine *engine);
+size_t gambit_engine_best_move(const GambitEngine *engine, char *buffer, size_t length);
+size_t gambit_engine_pv(const GambitEngine *engine, char *buffer, size_t length);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GAMBIT_H */
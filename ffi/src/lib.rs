@@ -0,0 +1,203 @@
+//! A C ABI over [`gambit_engine`], declared in `include/gambit.h`.
+//!
+//! Engines are opaque pointers created by [`gambit_engine_new`] and released with
+//! [`gambit_engine_free`]. Strings are passed in as NUL-terminated UTF-8 and copied out into
+//! caller-provided buffers, so no memory allocated here is ever freed by the caller.
+
+use std::ffi::{c_char, c_int, CStr};
+use std::ptr;
+use std::time::Duration;
+
+use gambit_engine::{Engine, EngineOptions, PositionError, SearchLimits, SearchResult};
+
+/// Returned when a call succeeds.
+pub const GAMBIT_OK: c_int = 0;
+/// Returned when a required pointer is null or a string is not valid UTF-8.
+pub const GAMBIT_INVALID_ARGUMENT: c_int = -1;
+/// Returned when the FEN string cannot be parsed.
+pub const GAMBIT_INVALID_FEN: c_int = -2;
+/// Returned when one of the moves is not legal in the position it is played from.
+pub const GAMBIT_ILLEGAL_MOVE: c_int = -3;
+
+/// An engine together with the result of its latest search.
+pub struct GambitEngine {
+    engine: Engine,
+    result: Option<SearchResult>,
+}
+
+/// Creates an engine with a transposition table of `hash_mb` megabytes, set up at the starting
+/// position. Returns null if `hash_mb` is zero.
+#[no_mangle]
+pub extern "C" fn gambit_engine_new(hash_mb: usize) -> *mut GambitEngine {
+    if hash_mb == 0 {
+        return ptr::null_mut();
+    }
+
+    Box::into_raw(Box::new(GambitEngine {
+        engine: Engine::new(EngineOptions {
+            hash: hash_mb,
+            ..EngineOptions::default()
+        }),
+        result: None,
+    }))
+}
+
+/// Destroys an engine. Passing null does nothing.
+///
+/// # Safety
+///
+/// `engine` must be null or a pointer returned by [`gambit_engine_new`] that has not been
+/// freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn gambit_engine_free(engine: *mut GambitEngine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine));
+    }
+}
+
+/// Sets up the position reached by playing `moves` from `fen`. `moves` holds UCI moves
+/// separated by spaces and may be null when there are none.
+///
+/// # Safety
+///
+/// `engine` must be a live engine, and `fen` and `moves` must be null or point to
+/// NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn gambit_engine_set_position(
+    engine: *mut GambitEngine,
+    fen: *const c_char,
+    moves: *const c_char,
+) -> c_int {
+    let Some(engine) = engine.as_mut() else {
+        return GAMBIT_INVALID_ARGUMENT;
+    };
+
+    let Some(fen) = to_str(fen) else {
+        return GAMBIT_INVALID_ARGUMENT;
+    };
+
+    let moves = if moves.is_null() {
+        ""
+    } else {
+        match to_str(moves) {
+            Some(moves) => moves,
+            None => return GAMBIT_INVALID_ARGUMENT,
+        }
+    };
+
+    match engine.engine.set_position(fen, moves.split_whitespace()) {
+        Ok(()) => GAMBIT_OK,
+        Err(PositionError::Fen(_)) => GAMBIT_INVALID_FEN,
+        Err(PositionError::IllegalMove(_)) => GAMBIT_ILLEGAL_MOVE,
+    }
+}
+
+/// Searches the current position. A limit of zero is no limit; with all three zero the search
+/// only stops at the maximum depth.
+///
+/// # Safety
+///
+/// `engine` must be a live engine.
+#[no_mangle]
+pub unsafe extern "C" fn gambit_engine_search(
+    engine: *mut GambitEngine,
+    depth: u8,
+    nodes: u64,
+    movetime_ms: u64,
+) -> c_int {
+    let Some(engine) = engine.as_mut() else {
+        return GAMBIT_INVALID_ARGUMENT;
+    };
+
+    let limits = SearchLimits {
+        depth: (depth > 0).then_some(depth),
+        nodes: (nodes > 0).then_some(nodes),
+        movetime: (movetime_ms > 0).then(|| Duration::from_millis(movetime_ms)),
+        ..SearchLimits::default()
+    };
+
+    engine.result = Some(engine.engine.search(limits));
+
+    GAMBIT_OK
+}
+
+/// Returns the score of the latest search in centipawns from the side to move's point of view,
+/// or zero if nothing has been searched.
+///
+/// # Safety
+///
+/// `engine` must be a live engine.
+#[no_mangle]
+pub unsafe extern "C" fn gambit_engine_score(engine: *const GambitEngine) -> i32 {
+    engine
+        .as_ref()
+        .and_then(|engine| engine.result.as_ref())
+        .map_or(0, |result| result.score.get())
+}
+
+/// Copies the best move of the latest search, in UCI notation, into `buffer`. The string is
+/// empty if there is no move.
+///
+/// Returns the length of the full string excluding the NUL terminator, like `snprintf`; the
+/// copy was truncated if this is not less than `length`.
+///
+/// # Safety
+///
+/// `engine` must be a live engine, and `buffer` must be null or valid for `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn gambit_engine_best_move(
+    engine: *const GambitEngine,
+    buffer: *mut c_char,
+    length: usize,
+) -> usize {
+    let best_move = engine
+        .as_ref()
+        .and_then(|engine| engine.result.as_ref())
+        .and_then(|result| result.best_move)
+        .map(|mv| mv.to_string())
+        .unwrap_or_default();
+
+    copy_out(&best_move, buffer, length)
+}
+
+/// Copies the principal variation of the latest search into `buffer`, as UCI moves separated
+/// by spaces. Returns the same as [`gambit_engine_best_move`].
+///
+/// # Safety
+///
+/// `engine` must be a live engine, and `buffer` must be null or valid for `length` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn gambit_engine_pv(
+    engine: *const GambitEngine,
+    buffer: *mut c_char,
+    length: usize,
+) -> usize {
+    let pv = engine
+        .as_ref()
+        .and_then(|engine| engine.result.as_ref())
+        .map(|result| result.pv.to_string())
+        .unwrap_or_default();
+
+    copy_out(&pv, buffer, length)
+}
+
+unsafe fn to_str<'a>(string: *const c_char) -> Option<&'a str> {
+    if string.is_null() {
+        return None;
+    }
+
+    CStr::from_ptr(string).to_str().ok()
+}
+
+/// Copies as much of `string` as fits into `buffer`, always NUL-terminating it when `length`
+/// is non-zero, and returns the full length of `string`.
+unsafe fn copy_out(string: &str, buffer: *mut c_char, length: usize) -> usize {
+    if !buffer.is_null() && length > 0 {
+        let copied = string.len().min(length - 1);
+
+        ptr::copy_nonoverlapping(string.as_ptr().cast(), buffer, copied);
+        *buffer.add(copied) = 0;
+    }
+
+    string.len()
+}
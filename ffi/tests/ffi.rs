@@ -0,0 +1,57 @@
+use std::ffi::{c_char, CStr};
+use std::ptr;
+
+use gambit_ffi::*;
+
+#[test]
+fn searches_through_the_c_abi() {
+    unsafe {
+        let engine = gambit_engine_new(1);
+        assert!(!engine.is_null());
+
+        let fen = c"6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1";
+        assert_eq!(
+            gambit_engine_set_position(engine, fen.as_ptr(), ptr::null()),
+            GAMBIT_OK
+        );
+        assert_eq!(gambit_engine_search(engine, 3, 0, 0), GAMBIT_OK);
+
+        let mut buffer = [0 as c_char; 16];
+        let length = gambit_engine_best_move(engine, buffer.as_mut_ptr(), buffer.len());
+
+        assert_eq!(length, 4);
+        assert_eq!(CStr::from_ptr(buffer.as_ptr()).to_str(), Ok("d1d8"));
+
+        let mut short = [0 as c_char; 3];
+        assert_eq!(gambit_engine_pv(engine, short.as_mut_ptr(), short.len()), 4);
+        assert_eq!(CStr::from_ptr(short.as_ptr()).to_str(), Ok("d1"));
+
+        gambit_engine_free(engine);
+    }
+}
+
+#[test]
+fn reports_invalid_positions() {
+    unsafe {
+        let engine = gambit_engine_new(1);
+
+        assert_eq!(
+            gambit_engine_set_position(engine, c"not a fen".as_ptr(), ptr::null()),
+            GAMBIT_INVALID_FEN
+        );
+        assert_eq!(
+            gambit_engine_set_position(
+                engine,
+                c"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".as_ptr(),
+                c"e2e4 e2e4".as_ptr()
+            ),
+            GAMBIT_ILLEGAL_MOVE
+        );
+        assert_eq!(
+            gambit_engine_set_position(engine, ptr::null(), ptr::null()),
+            GAMBIT_INVALID_ARGUMENT
+        );
+
+        gambit_engine_free(engine);
+    }
+}
@@ -0,0 +1,143 @@
+//! Python bindings for [`gambit`], built as the `gambit` extension module with maturin.
+//!
+//! Moves cross the boundary as UCI strings, which Python code can compare and store without
+//! knowing anything about the packed representation.
+
+use ::gambit::movegen;
+use ::gambit::{Board, Move};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// A chess position.
+#[pyclass(name = "Board")]
+#[derive(Clone)]
+struct PyBoard(Board);
+
+#[pymethods]
+impl PyBoard {
+    /// Creates a board from a FEN string, or at the starting position if none is given.
+    #[new]
+    #[pyo3(signature = (fen = None))]
+    fn new(fen: Option<&str>) -> PyResult<PyBoard> {
+        match fen {
+            Some(fen) => Board::from_fen(fen)
+                .map(PyBoard)
+                .map_err(|error| PyValueError::new_err(error.to_string())),
+            None => Ok(PyBoard(Board::default())),
+        }
+    }
+
+    fn fen(&self) -> String {
+        self.0.to_fen()
+    }
+
+    /// Returns `"w"` or `"b"`.
+    #[getter]
+    fn turn(&self) -> String {
+        self.0.side_to_move().to_string()
+    }
+
+    #[getter]
+    fn key(&self) -> u64 {
+        self.0.key()
+    }
+
+    fn is_check(&self) -> bool {
+        self.0.in_check()
+    }
+
+    /// Returns the legal moves in UCI notation.
+    fn legal_moves(&self) -> Vec<String> {
+        movegen::legal_moves(&self.0)
+            .iter()
+            .map(Move::to_string)
+            .collect()
+    }
+
+    /// Returns the legal moves of the piece on `square`, such as `"e2"`, in UCI notation.
+    fn moves_from(&self, square: &str) -> PyResult<Vec<String>> {
+        let square = square
+            .parse()
+            .map_err(|_| PyValueError::new_err(format!("invalid square: {square}")))?;
+
+        Ok(movegen::moves_from(&self.0, square)
+            .iter()
+            .map(Move::to_string)
+            .collect())
+    }
+
+    /// Converts a legal move from UCI notation to SAN.
+    fn san(&self, uci: &str) -> PyResult<String> {
+        Ok(self.0.san(self.find_uci(uci)?))
+    }
+
+    /// Converts a move from SAN to UCI notation.
+    fn parse_san(&self, san: &str) -> PyResult<String> {
+        self.0
+            .parse_san(san)
+            .map(|mv| mv.to_string())
+            .map_err(|error| PyValueError::new_err(format!("{error}: {san}")))
+    }
+
+    /// Plays a legal move given in UCI notation.
+    fn push_uci(&mut self, uci: &str) -> PyResult<()> {
+        let mv = self.find_uci(uci)?;
+        self.0.make_move(mv);
+
+        Ok(())
+    }
+
+    /// Plays a legal move given in SAN.
+    fn push_san(&mut self, san: &str) -> PyResult<()> {
+        let mv = self
+            .0
+            .parse_san(san)
+            .map_err(|error| PyValueError::new_err(format!("{error}: {san}")))?;
+        self.0.make_move(mv);
+
+        Ok(())
+    }
+
+    fn copy(&self) -> PyBoard {
+        self.clone()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Board('{}')", self.0.to_fen())
+    }
+
+    fn __eq__(&self, other: &PyBoard) -> bool {
+        self.0 == other.0
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.0.key()
+    }
+}
+
+impl PyBoard {
+    fn find_uci(&self, uci: &str) -> PyResult<Move> {
+        self.0
+            .parse_uci(uci)
+            .ok_or_else(|| PyValueError::new_err(format!("illegal move: {uci}")))
+    }
+}
+
+/// Counts the leaf nodes of the legal move tree of the given depth.
+#[pyfunction]
+fn perft(board: &PyBoard, depth: u32) -> u64 {
+    ::gambit::perft(&mut board.0.clone(), depth)
+}
+
+#[pymodule]
+fn gambit(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyBoard>()?;
+    module.add_function(wrap_pyfunction!(perft, module)?)?;
+    module.add("STARTING_POSITION_FEN", ::gambit::STARTING_POSITION_FEN)?;
+
+    Ok(())
+}
@@ -0,0 +1,296 @@
+//! Attack lookups for every piece kind.
+//!
+//! Leaper attacks are read from tables built at compile time. Slider attacks walk the
+//! precomputed ray from the origin square and cut it off at the first blocker in `occupied`;
+//! the blocker itself is included, whichever side it belongs to.
+
+use crate::bitboard::Bitboard;
+use crate::location::{Direction, KnightDirection, Square};
+use crate::piece::{Colour, Piece, PieceKind};
+
+pub(crate) const KNIGHT_DELTAS: [(i8, i8); 8] = knight_deltas();
+pub(crate) const KING_DELTAS: [(i8, i8); 8] = king_deltas();
+
+static KNIGHT_ATTACKS: [Bitboard; 64] = leaper_table(&KNIGHT_DELTAS);
+static KING_ATTACKS: [Bitboard; 64] = leaper_table(&KING_DELTAS);
+static PAWN_ATTACKS: [[Bitboard; 64]; 2] = [
+    leaper_table(&[(-1, 1), (1, 1)]),
+    leaper_table(&[(-1, -1), (1, -1)]),
+];
+static KING_ZONES: [[Bitboard; 64]; 2] = king_zone_tables();
+static RAYS: [[Bitboard; 64]; 8] = ray_tables();
+static BETWEEN: [[Bitboard; 64]; 64] = between_table();
+static LINE: [[Bitboard; 64]; 64] = line_table();
+
+const fn knight_deltas() -> [(i8, i8); 8] {
+    let mut deltas = [(0, 0); 8];
+    let mut i = 0;
+
+    while i < KnightDirection::ALL.len() {
+        deltas[i] = KnightDirection::ALL[i].delta();
+        i += 1;
+    }
+
+    deltas
+}
+
+const fn king_deltas() -> [(i8, i8); 8] {
+    let mut deltas = [(0, 0); 8];
+    let mut i = 0;
+
+    while i < Direction::ALL.len() {
+        deltas[i] = Direction::ALL[i].delta();
+        i += 1;
+    }
+
+    deltas
+}
+
+pub(crate) const fn leaper_table(deltas: &[(i8, i8)]) -> [Bitboard; 64] {
+    let mut table = [Bitboard::EMPTY; 64];
+    let mut index = 0;
+
+    while index < 64 {
+        let square = Square::LIST[index];
+
+        let mut bits = 0;
+        let mut i = 0;
+
+        while i < deltas.len() {
+            if let Some(target) = square.offset(deltas[i].0, deltas[i].1) {
+                bits |= 1 << target.index();
+            }
+
+            i += 1;
+        }
+
+        table[index] = Bitboard(bits);
+        index += 1;
+    }
+
+    table
+}
+
+const fn king_zone_tables() -> [[Bitboard; 64]; 2] {
+    let kings = leaper_table(&KING_DELTAS);
+    let mut tables = [[Bitboard::EMPTY; 64]; 2];
+    let mut index = 0;
+
+    while index < 64 {
+        let around = kings[index].0 | 1 << index;
+
+        tables[Colour::White.index()][index] = Bitboard(around | around << 8);
+        tables[Colour::Black.index()][index] = Bitboard(around | around >> 8);
+        index += 1;
+    }
+
+    tables
+}
+
+pub(crate) const fn ray_tables() -> [[Bitboard; 64]; 8] {
+    let mut tables = [[Bitboard::EMPTY; 64]; 8];
+    let mut d = 0;
+
+    while d < Direction::ALL.len() {
+        let mut index = 0;
+
+        while index < 64 {
+            let mut bits = 0;
+            let mut current = Some(Square::LIST[index]);
+
+            while let Some(square) = current {
+                current = square.step(Direction::ALL[d]);
+
+                if let Some(target) = current {
+                    bits |= 1 << target.index();
+                }
+            }
+
+            tables[Direction::ALL[d].index()][index] = Bitboard(bits);
+            index += 1;
+        }
+
+        d += 1;
+    }
+
+    tables
+}
+
+const fn between_table() -> [[Bitboard; 64]; 64] {
+    let mut table = [[Bitboard::EMPTY; 64]; 64];
+    let mut index = 0;
+
+    while index < 64 {
+        let mut d = 0;
+
+        while d < Direction::ALL.len() {
+            let mut bits = 0;
+            let mut current = Some(Square::LIST[index]);
+
+            while let Some(square) = current {
+                current = square.step(Direction::ALL[d]);
+
+                if let Some(target) = current {
+                    table[index][target.index()] = Bitboard(bits);
+                    bits |= 1 << target.index();
+                }
+            }
+
+            d += 1;
+        }
+
+        index += 1;
+    }
+
+    table
+}
+
+const fn line_table() -> [[Bitboard; 64]; 64] {
+    let rays = ray_tables();
+    let mut table = [[Bitboard::EMPTY; 64]; 64];
+    let mut index = 0;
+
+    while index < 64 {
+        let mut d = 0;
+
+        while d < Direction::ALL.len() {
+            // `d ^ 2` is the opposite of `d`, by the order of `Direction::ALL`.
+            let line = rays[d][index].0 | rays[d ^ 2][index].0 | 1 << index;
+            let mut targets = rays[d][index].0;
+
+            while targets != 0 {
+                table[index][targets.trailing_zeros() as usize] = Bitboard(line);
+                targets &= targets - 1;
+            }
+
+            d += 1;
+        }
+
+        index += 1;
+    }
+
+    table
+}
+
+/// Returns every square on the ray from `square` in `direction`, ignoring blockers.
+#[inline]
+pub fn ray(square: Square, direction: Direction) -> Bitboard {
+    RAYS[direction.index()][square.index()]
+}
+
+/// Returns the squares strictly between `from` and `to` if they share a rank, file or diagonal,
+/// and an empty set otherwise.
+#[inline]
+pub fn between(from: Square, to: Square) -> Bitboard {
+    BETWEEN[from.index()][to.index()]
+}
+
+/// Returns the full line, edge to edge, through `a` and `b` if they share a rank, file or
+/// diagonal, and an empty set otherwise.
+#[inline]
+pub fn line(a: Square, b: Square) -> Bitboard {
+    LINE[a.index()][b.index()]
+}
+
+#[inline]
+fn ray_attacks(square: Square, occupied: Bitboard, direction: Direction) -> Bitboard {
+    let ray = ray(square, direction);
+    let blockers = ray & occupied;
+
+    let first_blocker = if direction.is_positive() {
+        blockers.lsb()
+    } else {
+        blockers.msb()
+    };
+
+    match first_blocker {
+        Some(blocker) => ray ^ RAYS[direction.index()][blocker.index()],
+        None => ray,
+    }
+}
+
+#[inline]
+pub fn knight_attacks(square: Square) -> Bitboard {
+    KNIGHT_ATTACKS[square.index()]
+}
+
+#[inline]
+pub fn king_attacks(square: Square) -> Bitboard {
+    KING_ATTACKS[square.index()]
+}
+
+/// Returns the squares around a king on `square`, the ones it attacks.
+#[inline]
+pub fn king_ring(square: Square) -> Bitboard {
+    KING_ATTACKS[square.index()]
+}
+
+/// Returns the squares that matter to the safety of a king of `colour` on `square`: the king's
+/// own square and its ring, extended by one rank towards the enemy.
+#[inline]
+pub fn extended_king_zone(square: Square, colour: Colour) -> Bitboard {
+    KING_ZONES[colour.index()][square.index()]
+}
+
+/// Returns the squares a pawn of the given colour on `square` attacks diagonally.
+#[inline]
+pub fn pawn_attacks(colour: Colour, square: Square) -> Bitboard {
+    PAWN_ATTACKS[colour.index()][square.index()]
+}
+
+#[inline]
+pub fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    ray_attacks(square, occupied, Direction::North)
+        | ray_attacks(square, occupied, Direction::East)
+        | ray_attacks(square, occupied, Direction::South)
+        | ray_attacks(square, occupied, Direction::West)
+}
+
+#[inline]
+pub fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    ray_attacks(square, occupied, Direction::NorthEast)
+        | ray_attacks(square, occupied, Direction::SouthEast)
+        | ray_attacks(square, occupied, Direction::SouthWest)
+        | ray_attacks(square, occupied, Direction::NorthWest)
+}
+
+#[inline]
+pub fn queen_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    rook_attacks(square, occupied) | bishop_attacks(square, occupied)
+}
+
+/// Returns the squares a rook on `square` would additionally attack if the first pieces it hits
+/// among `blockers` were removed.
+///
+/// `blockers` is usually a subset of `occupied`, such as the pieces of one side: a piece from
+/// the rook's own side reveals a battery or discovered attack, an enemy piece reveals a pin.
+#[inline]
+pub fn xray_rook_attacks(square: Square, occupied: Bitboard, blockers: Bitboard) -> Bitboard {
+    let attacks = rook_attacks(square, occupied);
+    let first_blockers = attacks & blockers;
+
+    attacks ^ rook_attacks(square, occupied ^ first_blockers)
+}
+
+/// Returns the squares a bishop on `square` would additionally attack if the first pieces it
+/// hits among `blockers` were removed. See [`xray_rook_attacks`].
+#[inline]
+pub fn xray_bishop_attacks(square: Square, occupied: Bitboard, blockers: Bitboard) -> Bitboard {
+    let attacks = bishop_attacks(square, occupied);
+    let first_blockers = attacks & blockers;
+
+    attacks ^ bishop_attacks(square, occupied ^ first_blockers)
+}
+
+/// Returns the squares attacked by `piece` standing on `square`.
+#[inline]
+pub fn piece_attacks(piece: Piece, square: Square, occupied: Bitboard) -> Bitboard {
+    match piece.kind {
+        PieceKind::Pawn => pawn_attacks(piece.colour, square),
+        PieceKind::Knight => knight_attacks(square),
+        PieceKind::Bishop => bishop_attacks(square, occupied),
+        PieceKind::Rook => rook_attacks(square, occupied),
+        PieceKind::Queen => queen_attacks(square, occupied),
+        PieceKind::King => king_attacks(square),
+    }
+}
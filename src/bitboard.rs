@@ -0,0 +1,214 @@
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::location::{Direction, File, Rank, Square};
+use crate::piece::Colour;
+
+/// A set of squares, one bit per square in [`Square`] index order.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+
+    /// The dark squares, starting with `A1`.
+    pub const DARK_SQUARES: Bitboard = Bitboard(0xAA55_AA55_AA55_AA55);
+    pub const LIGHT_SQUARES: Bitboard = Bitboard(!Bitboard::DARK_SQUARES.0);
+
+    /// Returns the squares of the given colour, with `Colour::Black` for the dark squares.
+    pub const fn squares_of_colour(colour: Colour) -> Bitboard {
+        match colour {
+            Colour::White => Bitboard::LIGHT_SQUARES,
+            Colour::Black => Bitboard::DARK_SQUARES,
+        }
+    }
+
+    pub const fn from_square(square: Square) -> Bitboard {
+        Bitboard(1 << square.index())
+    }
+
+    pub const fn file(file: File) -> Bitboard {
+        Bitboard(0x0101_0101_0101_0101 << file.index())
+    }
+
+    pub const fn rank(rank: Rank) -> Bitboard {
+        Bitboard(0xFF << (rank.index() * 8))
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn contains(self, square: Square) -> bool {
+        self.0 & (1 << square.index()) != 0
+    }
+
+    pub const fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Returns the lowest-indexed square in the set.
+    pub const fn lsb(self) -> Option<Square> {
+        Square::from_index(self.0.trailing_zeros() as usize)
+    }
+
+    /// Returns the highest-indexed square in the set.
+    pub const fn msb(self) -> Option<Square> {
+        match self.0.checked_ilog2() {
+            Some(index) => Square::from_index(index as usize),
+            None => None,
+        }
+    }
+
+    /// Removes and returns the lowest-indexed square in the set.
+    pub fn pop_lsb(&mut self) -> Option<Square> {
+        let square = self.lsb()?;
+        self.0 &= self.0 - 1;
+
+        Some(square)
+    }
+
+    /// Moves every square in the set one step in `direction`, dropping those that would leave
+    /// the board.
+    pub const fn shift(self, direction: Direction) -> Bitboard {
+        // Squares on the edge being left would otherwise wrap round to the other side.
+        let bits = match direction.delta().0 {
+            1 => self.0 & !Bitboard::file(File::H).0,
+            -1 => self.0 & !Bitboard::file(File::A).0,
+            _ => self.0,
+        };
+        let offset = direction.offset();
+
+        match direction.is_positive() {
+            true => Bitboard(bits << offset),
+            false => Bitboard(bits >> -offset),
+        }
+    }
+}
+
+impl From<Square> for Bitboard {
+    fn from(square: Square) -> Bitboard {
+        Bitboard::from_square(square)
+    }
+}
+
+impl Iterator for Bitboard {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Square> {
+        self.pop_lsb()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.count() as usize;
+
+        (count, Some(count))
+    }
+}
+
+impl ExactSizeIterator for Bitboard {}
+
+impl fmt::Debug for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bitboard({:#018x})", self.0)
+    }
+}
+
+/// Draws the board from White's side, with `X` on the squares in the set and `.` elsewhere,
+/// and the ranks and files labelled as [`Board`](crate::Board) draws them. The `+` flag adds
+/// the value in hex underneath.
+///
+/// The alternate flag writes one line instead, with the ranks from the eighth down separated
+/// by `/` as in a FEN:
+///
+/// ```
+/// use gambit::{Bitboard, Rank};
+///
+/// let second_rank = Bitboard::rank(Rank::Second);
+///
+/// assert_eq!(
+///     format!("{second_rank:#}"),
+///     "......../......../......../......../......../......../XXXXXXXX/........"
+/// );
+/// assert!(format!("{second_rank:+}").ends_with("0x000000000000ff00"));
+/// ```
+impl fmt::Display for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranks = Rank::iter().rev();
+        let squares = |rank| {
+            File::iter().map(move |file| match self.contains(Square::new(file, rank)) {
+                true => 'X',
+                false => '.',
+            })
+        };
+
+        if f.alternate() {
+            let ranks: Vec<String> = ranks.map(|rank| squares(rank).collect()).collect();
+
+            return f.write_str(&ranks.join("/"));
+        }
+
+        for rank in ranks {
+            write!(f, "{} ", rank.to_char())?;
+
+            for c in squares(rank) {
+                write!(f, " {c}")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        write!(f, "   a b c d e f g h")?;
+
+        if f.sign_plus() {
+            write!(f, "\n{:#018x}", self.0)?;
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, $op:tt) => {
+        impl $trait for Bitboard {
+            type Output = Bitboard;
+
+            fn $fn(self, rhs: Bitboard) -> Bitboard {
+                Bitboard(self.0 $op rhs.0)
+            }
+        }
+
+        impl $trait<Square> for Bitboard {
+            type Output = Bitboard;
+
+            fn $fn(self, rhs: Square) -> Bitboard {
+                Bitboard(self.0 $op Bitboard::from_square(rhs).0)
+            }
+        }
+
+        impl $assign_trait for Bitboard {
+            fn $assign_fn(&mut self, rhs: Bitboard) {
+                self.0 = self.0 $op rhs.0;
+            }
+        }
+
+        impl $assign_trait<Square> for Bitboard {
+            fn $assign_fn(&mut self, rhs: Square) {
+                self.0 = self.0 $op Bitboard::from_square(rhs).0;
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
@@ -0,0 +1,756 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::attacks;
+use crate::bitboard::Bitboard;
+use crate::castling::{Castling, CastlingPermissions};
+use crate::location::{File, Rank, Square};
+use crate::movegen;
+use crate::moves::Move;
+use crate::piece::{Colour, Piece, PieceKind};
+use crate::zobrist;
+use crate::STARTING_POSITION_FEN;
+
+/// The parts of a position that cannot be recovered from a move alone, saved by
+/// [`Board::state`] before making the move so that [`Board::unmake_move`] can take it back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct State {
+    castling: CastlingPermissions,
+    en_passant: Option<Square>,
+    halfmove_clock: u16,
+    fullmove_number: u16,
+    key: u64,
+}
+
+/// A chess position: piece placement plus the state needed to continue play from it.
+#[derive(Clone)]
+pub struct Board {
+    mailbox: [Option<Piece>; 64],
+    pieces: [Bitboard; 6],
+    colours: [Bitboard; 2],
+    side_to_move: Colour,
+    castling: CastlingPermissions,
+    en_passant: Option<Square>,
+    halfmove_clock: u16,
+    fullmove_number: u16,
+    key: u64,
+    /// The Zobrist key of the pawns alone, shared by every position with the same pawn
+    /// structure.
+    pawn_key: u64,
+    /// The Zobrist key of the number of pieces of each kind and colour.
+    material_key: u64,
+}
+
+impl Board {
+    /// Returns a board with no pieces, White to move and no castling rights.
+    pub(crate) fn empty() -> Board {
+        Board {
+            mailbox: [None; 64],
+            pieces: [Bitboard::EMPTY; 6],
+            colours: [Bitboard::EMPTY; 2],
+            side_to_move: Colour::White,
+            castling: CastlingPermissions::NONE,
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            key: 0,
+            pawn_key: 0,
+            material_key: 0,
+        }
+    }
+
+    pub fn piece_at(&self, square: Square) -> Option<Piece> {
+        self.mailbox[square.index()]
+    }
+
+    /// Returns every square occupied by a piece of the given kind, of either colour.
+    pub fn pieces(&self, kind: PieceKind) -> Bitboard {
+        self.pieces[kind.index()]
+    }
+
+    /// Returns every square occupied by a piece of the given colour.
+    pub fn colour(&self, colour: Colour) -> Bitboard {
+        self.colours[colour.index()]
+    }
+
+    pub fn pieces_of(&self, colour: Colour, kind: PieceKind) -> Bitboard {
+        self.pieces(kind) & self.colour(colour)
+    }
+
+    /// Returns the bishops of `colour` that stand on squares of `square_colour`, with
+    /// `Colour::Black` for the dark squares.
+    pub fn bishops_on(&self, colour: Colour, square_colour: Colour) -> Bitboard {
+        self.pieces_of(colour, PieceKind::Bishop) & Bitboard::squares_of_colour(square_colour)
+    }
+
+    pub fn occupied(&self) -> Bitboard {
+        self.colours[0] | self.colours[1]
+    }
+
+    /// Returns whether every square strictly between `from` and `to` is empty. Squares that do
+    /// not share a rank, file or diagonal have nothing between them, so the path is clear.
+    pub fn is_path_clear(&self, from: Square, to: Square) -> bool {
+        (from.between(to) & self.occupied()).is_empty()
+    }
+
+    /// Returns the square of the given side's king, if it has one.
+    pub fn king_square(&self, colour: Colour) -> Option<Square> {
+        self.pieces_of(colour, PieceKind::King).lsb()
+    }
+
+    pub fn side_to_move(&self) -> Colour {
+        self.side_to_move
+    }
+
+    pub fn castling(&self) -> CastlingPermissions {
+        self.castling
+    }
+
+    /// Returns the square a pawn may capture onto en passant, if the last move was a double push.
+    pub fn en_passant(&self) -> Option<Square> {
+        self.en_passant
+    }
+
+    pub fn halfmove_clock(&self) -> u16 {
+        self.halfmove_clock
+    }
+
+    pub fn fullmove_number(&self) -> u16 {
+        self.fullmove_number
+    }
+
+    /// Returns the Zobrist key of the position.
+    pub fn key(&self) -> u64 {
+        self.key
+    }
+
+    /// Returns the Zobrist key of the pawns of both colours, ignoring every other piece and
+    /// the side to move.
+    pub fn pawn_key(&self) -> u64 {
+        self.pawn_key
+    }
+
+    /// Returns the material signature: a Zobrist key of how many pieces of each kind and
+    /// colour are on the board, wherever they stand.
+    pub fn material_key(&self) -> u64 {
+        self.material_key
+    }
+
+    /// Returns the pieces of either colour that attack `square`, given the occupancy `occupied`.
+    pub fn attackers_to(&self, square: Square, occupied: Bitboard) -> Bitboard {
+        let queens = self.pieces(PieceKind::Queen);
+
+        (attacks::pawn_attacks(Colour::White, square)
+            & self.pieces_of(Colour::Black, PieceKind::Pawn))
+            | (attacks::pawn_attacks(Colour::Black, square)
+                & self.pieces_of(Colour::White, PieceKind::Pawn))
+            | (attacks::knight_attacks(square) & self.pieces(PieceKind::Knight))
+            | (attacks::king_attacks(square) & self.pieces(PieceKind::King))
+            | (attacks::rook_attacks(square, occupied) & (self.pieces(PieceKind::Rook) | queens))
+            | (attacks::bishop_attacks(square, occupied)
+                & (self.pieces(PieceKind::Bishop) | queens))
+    }
+
+    /// Returns the enemy pieces giving check to the side to move.
+    pub fn checkers(&self) -> Bitboard {
+        match self.king_square(self.side_to_move) {
+            Some(king) => {
+                self.attackers_to(king, self.occupied()) & self.colour(!self.side_to_move)
+            }
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    pub fn in_check(&self) -> bool {
+        !self.checkers().is_empty()
+    }
+
+    /// Returns the empty squares on which a piece could be interposed to block the check on the
+    /// side to move.
+    ///
+    /// This is empty unless the king is in check from a single distant slider: checks by a
+    /// pawn, knight or adjacent piece can only be answered by capturing the checker or moving
+    /// the king, and a double check only by moving the king.
+    pub fn check_blocking_squares(&self) -> Bitboard {
+        let checkers = self.checkers();
+
+        match (self.king_square(self.side_to_move), checkers.lsb()) {
+            (Some(king), Some(checker)) if checkers.count() == 1 => attacks::between(king, checker),
+            _ => Bitboard::EMPTY,
+        }
+    }
+
+    /// Returns whether only king moves can answer the check on the side to move, as in double
+    /// check, when neither capturing one checker nor blocking one line stops the other.
+    pub fn must_move_king(&self) -> bool {
+        self.checkers().count() > 1
+    }
+
+    /// Returns whether the side to move has a legal en passant capture.
+    ///
+    /// Only then does the en passant square contribute to the position's key: otherwise two
+    /// positions that allow exactly the same moves would hash differently and repetitions
+    /// would be missed.
+    pub fn can_capture_en_passant(&self) -> bool {
+        let Some(target) = self.en_passant else {
+            return false;
+        };
+
+        let us = self.side_to_move;
+        let Some(captured) = target.backward(us) else {
+            return false;
+        };
+
+        if !self.pieces_of(!us, PieceKind::Pawn).contains(captured) {
+            return false;
+        }
+
+        let capturers = attacks::pawn_attacks(!us, target) & self.pieces_of(us, PieceKind::Pawn);
+
+        let Some(king) = self.king_square(us) else {
+            return !capturers.is_empty();
+        };
+
+        let enemies = self.colour(!us) ^ captured;
+
+        capturers.into_iter().any(|from| {
+            let occupied = (self.occupied() ^ from ^ captured) | target;
+
+            (self.attackers_to(king, occupied) & enemies).is_empty()
+        })
+    }
+
+    /// Returns the squares whose contents differ between `self` and `other`, in square order,
+    /// along with the piece on each square before (in `self`) and after (in `other`).
+    ///
+    /// Only piece placement is compared; side to move, castling rights and the clocks are ignored.
+    pub fn diff(&self, other: &Board) -> Vec<(Square, Option<Piece>, Option<Piece>)> {
+        let mut changed = Bitboard::EMPTY;
+
+        for colour in Colour::ALL {
+            for kind in PieceKind::ALL {
+                changed |= self.pieces_of(colour, kind) ^ other.pieces_of(colour, kind);
+            }
+        }
+
+        changed
+            .map(|square| (square, self.piece_at(square), other.piece_at(square)))
+            .collect()
+    }
+
+    /// Returns the position mirrored top to bottom with the colours swapped, so that White's
+    /// pieces become Black's and the other side is to move. The clocks are kept.
+    ///
+    /// A correct evaluation scores the flipped position exactly as the original from the point
+    /// of view of the side to move.
+    pub fn flipped(&self) -> Board {
+        let mut board = Board::empty();
+
+        for square in self.occupied() {
+            if let Some(piece) = self.piece_at(square) {
+                board.put_piece(square.flipped(), Piece::new(piece.kind, !piece.colour));
+            }
+        }
+
+        let castling = self.castling.bits();
+
+        board.side_to_move = !self.side_to_move;
+        board.castling = CastlingPermissions::from_bits((castling & 0b0011) << 2 | castling >> 2);
+        board.en_passant = self.en_passant.map(Square::flipped);
+        board.halfmove_clock = self.halfmove_clock;
+        board.fullmove_number = self.fullmove_number;
+        board.refresh_key();
+
+        board
+    }
+
+    /// Returns the pieces of `colour` that are pinned to their own king by an enemy slider.
+    pub fn pinned_pieces(&self, colour: Colour) -> Bitboard {
+        match self.king_square(colour) {
+            Some(king) => self.xray_lines(king, !colour, self.colour(colour)).1,
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    /// Returns the enemy sliders that pin a piece of `colour` to its king.
+    pub fn pinners(&self, colour: Colour) -> Bitboard {
+        match self.king_square(colour) {
+            Some(king) => self.xray_lines(king, !colour, self.colour(colour)).0,
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    /// Returns the pieces of `colour` that would uncover a check on the enemy king by moving
+    /// off the line between it and one of their own sliders.
+    pub fn candidates_for_discovered_check(&self, colour: Colour) -> Bitboard {
+        match self.king_square(!colour) {
+            Some(king) => self.xray_lines(king, colour, self.colour(colour)).1,
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    /// Returns the squares from which a piece of the given kind, belonging to the side to move,
+    /// would attack the enemy king.
+    pub fn check_squares(&self, kind: PieceKind) -> Bitboard {
+        let Some(king) = self.king_square(!self.side_to_move) else {
+            return Bitboard::EMPTY;
+        };
+
+        let occupied = self.occupied();
+
+        match kind {
+            PieceKind::Pawn => attacks::pawn_attacks(!self.side_to_move, king),
+            PieceKind::Knight => attacks::knight_attacks(king),
+            PieceKind::Bishop => attacks::bishop_attacks(king, occupied),
+            PieceKind::Rook => attacks::rook_attacks(king, occupied),
+            PieceKind::Queen => attacks::queen_attacks(king, occupied),
+            PieceKind::King => Bitboard::EMPTY,
+        }
+    }
+
+    /// Returns whether playing `mv`, which must be legal in this position, checks the enemy king.
+    ///
+    /// This avoids making the move: direct checks are looked up in [`Board::check_squares`] and
+    /// discovered checks in [`Board::candidates_for_discovered_check`], with only en passant,
+    /// castling and promotions recomputing attacks on the resulting occupancy.
+    pub fn gives_check(&self, mv: Move) -> bool {
+        let us = self.side_to_move;
+        let Some(king) = self.king_square(!us) else {
+            return false;
+        };
+
+        let (from, to) = (mv.from(), mv.to());
+
+        if mv.promotion().is_none() && self.check_squares(mv.piece()).contains(to) {
+            return true;
+        }
+
+        if self.candidates_for_discovered_check(us).contains(from)
+            && !attacks::line(from, king).contains(to)
+        {
+            return true;
+        }
+
+        if let Some(promotion) = mv.promotion() {
+            let occupied = (self.occupied() ^ from) | to;
+
+            return attacks::piece_attacks(Piece::new(promotion, us), to, occupied).contains(king);
+        }
+
+        if mv.is_en_passant() {
+            let captured = Square::new(to.file(), from.rank());
+            let occupied = (self.occupied() ^ from ^ captured) | to;
+            let queens = self.pieces_of(us, PieceKind::Queen);
+            let rooks = self.pieces_of(us, PieceKind::Rook) | queens;
+            let bishops = self.pieces_of(us, PieceKind::Bishop) | queens;
+
+            return !(attacks::rook_attacks(king, occupied) & rooks).is_empty()
+                || !(attacks::bishop_attacks(king, occupied) & bishops).is_empty();
+        }
+
+        if mv.is_castle() {
+            if let Some(castling) = Castling::from_king_destination(to) {
+                let (rook_from, rook_to) = castling.rook_squares();
+                let occupied = (self.occupied() ^ from ^ rook_from) | to | rook_to;
+
+                return attacks::rook_attacks(rook_to, occupied).contains(king);
+            }
+        }
+
+        false
+    }
+
+    /// Returns whether `mv` could be played here if the safety of the mover's king were
+    /// ignored: the right piece stands on its origin, it can reach the destination past the
+    /// pieces in the way, the captured piece is the one there, and any special move is
+    /// possible. Castling must also avoid check and attacked squares, as in play.
+    ///
+    /// Moves that come from elsewhere than the move generator, such as the hash move after a
+    /// key collision, must pass this before they are trusted.
+    pub fn is_pseudo_legal(&self, mv: Move) -> bool {
+        let us = self.side_to_move;
+        let (from, to) = (mv.from(), mv.to());
+        let occupied = self.occupied();
+
+        if self.piece_at(from) != Some(Piece::new(mv.piece(), us)) {
+            return false;
+        }
+
+        if mv.is_castle() {
+            return match Castling::from_king_destination(to) {
+                Some(castling) => {
+                    castling.colour() == us
+                        && castling.king_squares().0 == from
+                        && mv.is_quiet()
+                        && !mv.is_en_passant()
+                        && !mv.is_double_push()
+                        && !self.in_check()
+                        && self.can_castle(castling)
+                }
+                None => false,
+            };
+        }
+
+        // En passant is the one capture whose victim is not on the destination.
+        let victim = match mv.is_en_passant() {
+            true if self.en_passant == Some(to) => Square::new(to.file(), from.rank()),
+            true => return false,
+            false => to,
+        };
+        let captured = match self.piece_at(victim) {
+            Some(piece) if piece.colour == us => return false,
+            captured => captured.map(|piece| piece.kind),
+        };
+
+        if mv.captured() != captured || (mv.is_en_passant() && occupied.contains(to)) {
+            return false;
+        }
+
+        if mv.piece() != PieceKind::Pawn {
+            return !mv.is_en_passant()
+                && !mv.is_double_push()
+                && !mv.is_promotion()
+                && attacks::piece_attacks(Piece::new(mv.piece(), us), from, occupied).contains(to);
+        }
+
+        // Pawns promote exactly when they reach the last rank, and only to a piece.
+        if (to.rank() == Rank::Eighth.relative(us)) != mv.is_promotion()
+            || matches!(mv.promotion(), Some(PieceKind::Pawn | PieceKind::King))
+        {
+            return false;
+        }
+
+        if mv.is_capture() {
+            return !mv.is_double_push() && attacks::pawn_attacks(us, from).contains(to);
+        }
+
+        match from.forward(us).filter(|step| !occupied.contains(*step)) {
+            Some(step) if step == to => !mv.is_double_push(),
+            Some(step) => {
+                mv.is_double_push()
+                    && from.rank() == Rank::Second.relative(us)
+                    && step.forward(us) == Some(to)
+                    && !occupied.contains(to)
+            }
+            None => false,
+        }
+    }
+
+    /// Returns whether the side to move, if it is not in check, may castle with `castling`:
+    /// it has the right, the king and rook are in place, the squares between them and their
+    /// destinations are empty, and the king crosses no attacked square.
+    ///
+    /// Rights are only trusted as far as the board agrees with them, which a malformed FEN may
+    /// not guarantee.
+    pub(crate) fn can_castle(&self, castling: Castling) -> bool {
+        if !self.castling.has(castling) || !self.can_have_castling_right(castling) {
+            return false;
+        }
+
+        let (king_from, king_to) = castling.king_squares();
+        let (rook_from, rook_to) = castling.rook_squares();
+        let occupied = self.occupied();
+
+        let castling_pieces = Bitboard::from(king_from) | rook_from;
+        let must_be_empty =
+            (attacks::between(king_from, rook_from) | king_to | rook_to) & !castling_pieces;
+
+        if !(must_be_empty & occupied).is_empty() {
+            return false;
+        }
+
+        let enemies = self.colour(!castling.colour());
+        let king_path = attacks::between(king_from, king_to) | king_to;
+
+        king_path
+            .into_iter()
+            .all(|square| (self.attackers_to(square, occupied) & enemies).is_empty())
+    }
+
+    /// Finds the legal move written in UCI notation, such as `e2e4` or `a7b8n`. The promotion
+    /// letter is accepted in either case.
+    pub fn parse_uci(&self, uci: &str) -> Option<Move> {
+        let from = uci.get(0..2)?.parse::<Square>().ok()?;
+        let to = uci.get(2..4)?.parse::<Square>().ok()?;
+
+        let promotion = match uci[4..].chars().collect::<Vec<_>>()[..] {
+            [] => None,
+            [c] => Some(PieceKind::from_char(c.to_ascii_lowercase())?),
+            _ => return None,
+        };
+
+        movegen::moves_from(self, from)
+            .iter()
+            .copied()
+            .find(|mv| mv.from() == from && mv.to() == to && mv.promotion() == promotion)
+    }
+
+    /// Plays `mv`, which must be legal in this position, updating the key incrementally.
+    pub fn make_move(&mut self, mv: Move) {
+        let us = self.side_to_move;
+        let (from, to) = (mv.from(), mv.to());
+
+        self.clear_en_passant();
+        self.key ^= zobrist::castling(self.castling);
+
+        if mv.is_capture() {
+            let captured = if mv.is_en_passant() {
+                Square::new(to.file(), from.rank())
+            } else {
+                to
+            };
+
+            self.remove_piece(captured);
+        }
+
+        let piece = self.remove_piece(from);
+        let placed = match mv.promotion() {
+            Some(promotion) => Piece::new(promotion, us),
+            None => piece,
+        };
+        self.put_piece(to, placed);
+
+        if mv.is_castle() {
+            if let Some(castling) = Castling::from_king_destination(to) {
+                let (rook_from, rook_to) = castling.rook_squares();
+                let rook = self.remove_piece(rook_from);
+
+                self.put_piece(rook_to, rook);
+            }
+        }
+
+        self.castling = self.castling.after_move(from, to);
+        self.key ^= zobrist::castling(self.castling);
+
+        if piece.kind == PieceKind::Pawn || mv.is_capture() {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock = self.halfmove_clock.saturating_add(1);
+        }
+
+        if us == Colour::Black {
+            self.fullmove_number = self.fullmove_number.saturating_add(1);
+        }
+
+        self.side_to_move = !us;
+        self.key ^= zobrist::side();
+
+        if mv.is_double_push() {
+            let square = Square::from_index((from.index() + to.index()) / 2);
+
+            self.en_passant = square;
+
+            if let Some(square) = square.filter(|_| self.can_capture_en_passant()) {
+                self.key ^= zobrist::en_passant(square.file());
+            }
+        }
+    }
+
+    /// Returns what [`Board::unmake_move`] needs to take back a move made from this position.
+    pub fn state(&self) -> State {
+        State {
+            castling: self.castling,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            key: self.key,
+        }
+    }
+
+    /// Takes back `mv`, which must be the last move made, restoring the position `state` was
+    /// saved from.
+    ///
+    /// Together with [`Board::make_move`] this walks a search tree on a single board, as an
+    /// alternative to copying the board for every move.
+    pub fn unmake_move(&mut self, mv: Move, state: State) {
+        let us = !self.side_to_move;
+        let (from, to) = (mv.from(), mv.to());
+
+        if mv.is_castle() {
+            if let Some(castling) = Castling::from_king_destination(to) {
+                let (rook_from, rook_to) = castling.rook_squares();
+                let rook = self.remove_piece(rook_to);
+
+                self.put_piece(rook_from, rook);
+            }
+        }
+
+        let placed = self.remove_piece(to);
+        let piece = match mv.promotion() {
+            Some(_) => Piece::new(PieceKind::Pawn, us),
+            None => placed,
+        };
+        self.put_piece(from, piece);
+
+        if let Some(captured) = mv.captured() {
+            let square = if mv.is_en_passant() {
+                Square::new(to.file(), from.rank())
+            } else {
+                to
+            };
+
+            self.put_piece(square, Piece::new(captured, !us));
+        }
+
+        self.side_to_move = us;
+        self.castling = state.castling;
+        self.en_passant = state.en_passant;
+        self.halfmove_clock = state.halfmove_clock;
+        self.fullmove_number = state.fullmove_number;
+        self.key = state.key;
+    }
+
+    /// Removes the en passant square, taking it out of the key if it was hashed.
+    fn clear_en_passant(&mut self) {
+        if let Some(square) = self.en_passant.filter(|_| self.can_capture_en_passant()) {
+            self.key ^= zobrist::en_passant(square.file());
+        }
+
+        self.en_passant = None;
+    }
+
+    /// Finds the sliders of `attacker` that would attack `target` if exactly one piece from
+    /// `blockers` were removed, returning those sliders and the blocking pieces.
+    fn xray_lines(
+        &self,
+        target: Square,
+        attacker: Colour,
+        blockers: Bitboard,
+    ) -> (Bitboard, Bitboard) {
+        let occupied = self.occupied();
+        let queens = self.pieces_of(attacker, PieceKind::Queen);
+        let rooks = self.pieces_of(attacker, PieceKind::Rook) | queens;
+        let bishops = self.pieces_of(attacker, PieceKind::Bishop) | queens;
+
+        let sliders = (attacks::xray_rook_attacks(target, occupied, blockers) & rooks)
+            | (attacks::xray_bishop_attacks(target, occupied, blockers) & bishops);
+
+        let blocking = sliders.fold(Bitboard::EMPTY, |blocking, slider| {
+            blocking | (attacks::between(target, slider) & blockers)
+        });
+
+        (sliders, blocking)
+    }
+
+    pub(crate) fn put_piece(&mut self, square: Square, piece: Piece) {
+        debug_assert!(
+            self.mailbox[square.index()].is_none(),
+            "{square} is already occupied"
+        );
+
+        self.material_key ^=
+            zobrist::material(piece, self.pieces_of(piece.colour, piece.kind).count());
+        self.mailbox[square.index()] = Some(piece);
+        self.pieces[piece.kind.index()] |= square;
+        self.colours[piece.colour.index()] |= square;
+        self.key ^= zobrist::piece(piece, square);
+
+        if piece.kind == PieceKind::Pawn {
+            self.pawn_key ^= zobrist::piece(piece, square);
+        }
+    }
+
+    /// Takes the piece off `square`, which must be occupied, and returns it.
+    pub(crate) fn remove_piece(&mut self, square: Square) -> Piece {
+        let piece = self.mailbox[square.index()]
+            .take()
+            .unwrap_or_else(|| panic!("{square} is empty"));
+
+        self.pieces[piece.kind.index()] ^= square;
+        self.colours[piece.colour.index()] ^= square;
+        self.key ^= zobrist::piece(piece, square);
+        self.material_key ^=
+            zobrist::material(piece, self.pieces_of(piece.colour, piece.kind).count());
+
+        if piece.kind == PieceKind::Pawn {
+            self.pawn_key ^= zobrist::piece(piece, square);
+        }
+
+        piece
+    }
+
+    pub(crate) fn set_side_to_move(&mut self, colour: Colour) {
+        self.side_to_move = colour;
+    }
+
+    pub(crate) fn set_castling(&mut self, castling: CastlingPermissions) {
+        self.castling = castling;
+    }
+
+    pub(crate) fn set_en_passant(&mut self, square: Option<Square>) {
+        self.en_passant = square;
+    }
+
+    pub(crate) fn set_halfmove_clock(&mut self, clock: u16) {
+        self.halfmove_clock = clock;
+    }
+
+    pub(crate) fn set_fullmove_number(&mut self, number: u16) {
+        self.fullmove_number = number;
+    }
+
+    /// Recomputes the cached Zobrist keys after the position was set up piece by piece.
+    pub(crate) fn refresh_key(&mut self) {
+        self.key = zobrist::hash(self);
+        self.pawn_key = zobrist::pawn_hash(self);
+        self.material_key = zobrist::material_hash(self);
+    }
+}
+
+impl Default for Board {
+    /// Returns the standard starting position.
+    fn default() -> Board {
+        Board::from_fen(STARTING_POSITION_FEN).expect("the starting position is valid")
+    }
+}
+
+/// Two boards are equal when they describe the same position for the purposes of play:
+/// piece placement, side to move, castling rights and a legal en passant capture. The clocks
+/// are ignored, so positions reached by different move orders compare equal.
+impl PartialEq for Board {
+    fn eq(&self, other: &Board) -> bool {
+        self.key == other.key
+            && self.pieces == other.pieces
+            && self.colours == other.colours
+            && self.side_to_move == other.side_to_move
+            && self.castling == other.castling
+            && self.en_passant.filter(|_| self.can_capture_en_passant())
+                == other.en_passant.filter(|_| other.can_capture_en_passant())
+    }
+}
+
+impl Eq for Board {}
+
+/// Hashes the Zobrist key, which covers exactly what [`PartialEq`] compares.
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.key);
+    }
+}
+
+impl fmt::Debug for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Board").field(&self.to_fen()).finish()
+    }
+}
+
+/// Draws the board from White's side, with `.` for empty squares.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rank in Rank::iter().rev() {
+            write!(f, "{} ", rank.to_char())?;
+
+            for file in File::iter() {
+                let c = self
+                    .piece_at(Square::new(file, rank))
+                    .map_or('.', Piece::to_char);
+
+                write!(f, " {c}")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        write!(f, "   a b c d e f g h")
+    }
+}
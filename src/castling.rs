@@ -0,0 +1,175 @@
+use std::fmt;
+
+use crate::location::Square;
+use crate::piece::Colour;
+
+/// One of the four ways a side may castle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Castling {
+    WhiteKingside = 0b0001,
+    WhiteQueenside = 0b0010,
+    BlackKingside = 0b0100,
+    BlackQueenside = 0b1000,
+}
+
+impl Castling {
+    pub const ALL: [Castling; 4] = [
+        Castling::WhiteKingside,
+        Castling::WhiteQueenside,
+        Castling::BlackKingside,
+        Castling::BlackQueenside,
+    ];
+
+    pub const fn kingside(colour: Colour) -> Castling {
+        match colour {
+            Colour::White => Castling::WhiteKingside,
+            Colour::Black => Castling::BlackKingside,
+        }
+    }
+
+    pub const fn queenside(colour: Colour) -> Castling {
+        match colour {
+            Colour::White => Castling::WhiteQueenside,
+            Colour::Black => Castling::BlackQueenside,
+        }
+    }
+
+    pub const fn colour(self) -> Colour {
+        match self {
+            Castling::WhiteKingside | Castling::WhiteQueenside => Colour::White,
+            Castling::BlackKingside | Castling::BlackQueenside => Colour::Black,
+        }
+    }
+
+    /// Returns the castling whose king move ends on `to`, if any.
+    pub const fn from_king_destination(to: Square) -> Option<Castling> {
+        match to {
+            Square::G1 => Some(Castling::WhiteKingside),
+            Square::C1 => Some(Castling::WhiteQueenside),
+            Square::G8 => Some(Castling::BlackKingside),
+            Square::C8 => Some(Castling::BlackQueenside),
+            _ => None,
+        }
+    }
+
+    /// Returns the king's origin and destination squares.
+    pub const fn king_squares(self) -> (Square, Square) {
+        match self {
+            Castling::WhiteKingside => (Square::E1, Square::G1),
+            Castling::WhiteQueenside => (Square::E1, Square::C1),
+            Castling::BlackKingside => (Square::E8, Square::G8),
+            Castling::BlackQueenside => (Square::E8, Square::C8),
+        }
+    }
+
+    /// Returns the rook's origin and destination squares.
+    pub const fn rook_squares(self) -> (Square, Square) {
+        match self {
+            Castling::WhiteKingside => (Square::H1, Square::F1),
+            Castling::WhiteQueenside => (Square::A1, Square::D1),
+            Castling::BlackKingside => (Square::H8, Square::F8),
+            Castling::BlackQueenside => (Square::A8, Square::D8),
+        }
+    }
+
+    /// Returns the FEN letter for this right.
+    pub const fn to_char(self) -> char {
+        match self {
+            Castling::WhiteKingside => 'K',
+            Castling::WhiteQueenside => 'Q',
+            Castling::BlackKingside => 'k',
+            Castling::BlackQueenside => 'q',
+        }
+    }
+
+    pub const fn from_char(c: char) -> Option<Castling> {
+        match c {
+            'K' => Some(Castling::WhiteKingside),
+            'Q' => Some(Castling::WhiteQueenside),
+            'k' => Some(Castling::BlackKingside),
+            'q' => Some(Castling::BlackQueenside),
+            _ => None,
+        }
+    }
+}
+
+/// The set of castling rights still available in a position.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CastlingPermissions(u8);
+
+impl CastlingPermissions {
+    pub const NONE: CastlingPermissions = CastlingPermissions(0);
+    pub const ALL: CastlingPermissions = CastlingPermissions(0b1111);
+
+    /// Returns the raw four-bit mask, with one bit per [`Castling`] variant.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn from_bits(bits: u8) -> CastlingPermissions {
+        CastlingPermissions(bits & CastlingPermissions::ALL.0)
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn has(self, castling: Castling) -> bool {
+        self.0 & castling as u8 != 0
+    }
+
+    pub fn insert(&mut self, castling: Castling) {
+        self.0 |= castling as u8;
+    }
+
+    pub fn remove(&mut self, castling: Castling) {
+        self.0 &= !(castling as u8);
+    }
+
+    /// Returns the rights that survive a move from `from` to `to`: moving a king or rook off
+    /// its original square, or capturing a rook on it, loses the rights that depend on it.
+    pub fn after_move(self, from: Square, to: Square) -> CastlingPermissions {
+        CastlingPermissions(
+            self.0 & !(CASTLING_PERMISSIONS[from.index()].0 | CASTLING_PERMISSIONS[to.index()].0),
+        )
+    }
+}
+
+/// The castling rights revoked when a piece moves from, or is captured on, each square.
+#[rustfmt::skip]
+pub static CASTLING_PERMISSIONS: [CastlingPermissions; 64] = {
+    let mut table = [CastlingPermissions::NONE; 64];
+
+    table[Square::A1.index()] = CastlingPermissions(Castling::WhiteQueenside as u8);
+    table[Square::E1.index()] = CastlingPermissions(Castling::WhiteKingside as u8 | Castling::WhiteQueenside as u8);
+    table[Square::H1.index()] = CastlingPermissions(Castling::WhiteKingside as u8);
+    table[Square::A8.index()] = CastlingPermissions(Castling::BlackQueenside as u8);
+    table[Square::E8.index()] = CastlingPermissions(Castling::BlackKingside as u8 | Castling::BlackQueenside as u8);
+    table[Square::H8.index()] = CastlingPermissions(Castling::BlackKingside as u8);
+
+    table
+};
+
+impl fmt::Debug for CastlingPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CastlingPermissions({self})")
+    }
+}
+
+/// Formats the permissions as the FEN castling field, `-` if there are none.
+impl fmt::Display for CastlingPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("-");
+        }
+
+        for castling in Castling::ALL {
+            if self.has(castling) {
+                write!(f, "{}", castling.to_char())?;
+            }
+        }
+
+        Ok(())
+    }
+}
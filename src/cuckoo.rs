@@ -0,0 +1,168 @@
+//! Detection of upcoming repetitions with cuckoo hashing.
+//!
+//! Every reversible move of a non-pawn piece between two squares changes the Zobrist key by
+//! a fixed amount: the XOR of the piece's keys on both squares and the side-to-move key. These
+//! 3668 move keys are stored in a cuckoo table at compile time. If the key of the current
+//! position XOR an earlier position's key is one of them, and the squares between are empty,
+//! the side to move can repeat that earlier position in a single move.
+//!
+//! See Marcel van Kervinck, "The design of a Heuristic for Detecting Upcoming Repetitions".
+
+use crate::attacks::{self, KING_DELTAS, KNIGHT_DELTAS};
+use crate::board::Board;
+use crate::moves::PackedMove;
+use crate::zobrist;
+
+const SIZE: usize = 8192;
+
+struct Cuckoo {
+    keys: [u64; SIZE],
+    moves: [PackedMove; SIZE],
+}
+
+static CUCKOO: Cuckoo = generate_table();
+
+const fn h1(key: u64) -> usize {
+    (key & (SIZE as u64 - 1)) as usize
+}
+
+const fn h2(key: u64) -> usize {
+    ((key >> 16) & (SIZE as u64 - 1)) as usize
+}
+
+const fn generate_table() -> Cuckoo {
+    let keys = zobrist::generate_keys();
+    let knights = attacks::leaper_table(&KNIGHT_DELTAS);
+    let kings = attacks::leaper_table(&KING_DELTAS);
+    let rays = attacks::ray_tables();
+
+    let mut table = Cuckoo {
+        keys: [0; SIZE],
+        moves: [PackedMove::NONE; SIZE],
+    };
+
+    let mut colour = 0;
+    while colour < 2 {
+        // Pawn moves are irreversible, so start from the knight.
+        let mut kind = 1;
+        while kind < 6 {
+            let mut from = 0;
+            while from < 64 {
+                let rook = rays[0][from].0 | rays[1][from].0 | rays[2][from].0 | rays[3][from].0;
+                let bishop = rays[4][from].0 | rays[5][from].0 | rays[6][from].0 | rays[7][from].0;
+
+                let targets = match kind {
+                    1 => knights[from].0,
+                    2 => bishop,
+                    3 => rook,
+                    4 => rook | bishop,
+                    _ => kings[from].0,
+                };
+
+                let mut to = from + 1;
+                while to < 64 {
+                    if targets & (1 << to) != 0 {
+                        let mut key = keys.pieces[colour][kind][from]
+                            ^ keys.pieces[colour][kind][to]
+                            ^ keys.side;
+                        let mut mv = PackedMove::from_bits((from | to << 6) as u16);
+                        let mut slot = h1(key);
+
+                        // Insert, displacing the occupant to its alternative slot until one is free.
+                        loop {
+                            let displaced_key = table.keys[slot];
+                            let displaced_move = table.moves[slot];
+
+                            table.keys[slot] = key;
+                            table.moves[slot] = mv;
+
+                            if displaced_move.is_none() {
+                                break;
+                            }
+
+                            key = displaced_key;
+                            mv = displaced_move;
+                            slot = if slot == h1(key) { h2(key) } else { h1(key) };
+                        }
+                    }
+
+                    to += 1;
+                }
+
+                from += 1;
+            }
+
+            kind += 1;
+        }
+
+        colour += 1;
+    }
+
+    table
+}
+
+/// Returns whether the side to move can reach an earlier position with one reversible move,
+/// meaning the line is about to repeat and may be scored as a draw.
+///
+/// `history` holds the keys of the positions played before the root of the search and `line`
+/// those of the current line from the root up to the position before `board`, both oldest
+/// first, as produced by [`Board::key`]. Only positions since the last irreversible move, as
+/// counted by the halfmove clock, are considered.
+///
+/// Returning to a position after the root repeats it, but a position from before the root
+/// takes a threefold repetition, as in play, so it only counts if it has already occurred
+/// twice.
+pub fn has_upcoming_repetition(board: &Board, history: &[u64], line: &[u64]) -> bool {
+    let ply = line.len();
+    let reversible = (history.len() + ply).min(board.halfmove_clock() as usize);
+
+    if reversible < 3 {
+        return false;
+    }
+
+    let key = board.key();
+    let occupied = board.occupied();
+    let us = board.colour(board.side_to_move());
+    let earlier = |distance: usize| match distance <= ply {
+        true => line[ply - distance],
+        false => history[history.len() + ply - distance],
+    };
+
+    // A move of ours leads to a position with the opponent to move, which is an odd number of
+    // plies back. The position one ply back can only be restored by the opponent's piece.
+    for distance in (3..=reversible).step_by(2) {
+        let move_key = key ^ earlier(distance);
+
+        let slot = if CUCKOO.keys[h1(move_key)] == move_key {
+            h1(move_key)
+        } else if CUCKOO.keys[h2(move_key)] == move_key {
+            h2(move_key)
+        } else {
+            continue;
+        };
+
+        let mv = CUCKOO.moves[slot];
+        let (from, to) = (mv.from(), mv.to());
+
+        if !board.is_path_clear(from, to) {
+            continue;
+        }
+
+        let mover = if occupied.contains(from) { from } else { to };
+        if !us.contains(mover) {
+            continue;
+        }
+
+        let repeated = || {
+            (distance + 4..=reversible)
+                .step_by(2)
+                .any(|older| earlier(older) == earlier(distance))
+        };
+
+        if distance < ply || repeated() {
+            return true;
+        }
+    }
+
+    false
+}
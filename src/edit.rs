@@ -0,0 +1,125 @@
+//! Setting up positions piece by piece, as a board editor does.
+//!
+//! Edits keep the board consistent: the bitboards and keys follow every change, castling
+//! rights whose king or rook leaves its square are dropped, and any en passant square is
+//! forgotten, since it only makes sense straight after a double push. Edits that could never
+//! lead to a playable position, such as a pawn on the back rank, are refused.
+
+use std::fmt;
+
+use crate::bitboard::Bitboard;
+use crate::board::Board;
+use crate::castling::{Castling, CastlingPermissions};
+use crate::location::{Rank, Square};
+use crate::piece::{Colour, Piece, PieceKind};
+
+/// The reasons an edit can be refused.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EditError {
+    /// A pawn would stand on the first or eighth rank.
+    PawnOnBackRank,
+    /// A side would have a second king.
+    SecondKing,
+    /// A castling right was asked for without its king and rook on their original squares.
+    ImpossibleCastling(Castling),
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::PawnOnBackRank => f.write_str("pawns cannot stand on the back ranks"),
+            EditError::SecondKing => f.write_str("each side can have only one king"),
+            EditError::ImpossibleCastling(castling) => write!(
+                f,
+                "castling right {} needs its king and rook in place",
+                castling.to_char()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+impl Board {
+    /// Puts `piece` on `square`, replacing whatever stood there, and returns the piece it
+    /// replaced.
+    pub fn set_piece(&mut self, square: Square, piece: Piece) -> Result<Option<Piece>, EditError> {
+        let back_ranks = Bitboard::rank(Rank::First) | Bitboard::rank(Rank::Eighth);
+        if piece.kind == PieceKind::Pawn && back_ranks.contains(square) {
+            return Err(EditError::PawnOnBackRank);
+        }
+
+        let kings = self.pieces_of(piece.colour, PieceKind::King);
+        if piece.kind == PieceKind::King && !(kings ^ (kings & square)).is_empty() {
+            return Err(EditError::SecondKing);
+        }
+
+        let replaced = self.piece_at(square).map(|_| self.remove_piece(square));
+        self.put_piece(square, piece);
+        self.after_edit();
+
+        Ok(replaced)
+    }
+
+    /// Empties `square`, returning the piece that stood there.
+    pub fn clear_square(&mut self, square: Square) -> Option<Piece> {
+        let removed = self.piece_at(square).map(|_| self.remove_piece(square));
+        self.after_edit();
+
+        removed
+    }
+
+    /// Takes every piece off the board, along with the castling rights. The side to move and
+    /// the clocks are kept.
+    pub fn clear_board(&mut self) {
+        for square in self.occupied() {
+            self.remove_piece(square);
+        }
+
+        self.after_edit();
+    }
+
+    /// Gives the move to `colour`.
+    pub fn set_turn(&mut self, colour: Colour) {
+        self.set_side_to_move(colour);
+        self.after_edit();
+    }
+
+    /// Replaces the castling rights, refusing rights whose king or rook is not in place.
+    pub fn set_castling_rights(&mut self, castling: CastlingPermissions) -> Result<(), EditError> {
+        if let Some(right) = Castling::ALL
+            .into_iter()
+            .find(|&right| castling.has(right) && !self.can_have_castling_right(right))
+        {
+            return Err(EditError::ImpossibleCastling(right));
+        }
+
+        self.set_castling(castling);
+        self.after_edit();
+
+        Ok(())
+    }
+
+    /// Whether the king and rook of `castling` stand on their original squares.
+    pub(crate) fn can_have_castling_right(&self, castling: Castling) -> bool {
+        let colour = castling.colour();
+        let (king, _) = castling.king_squares();
+        let (rook, _) = castling.rook_squares();
+
+        self.piece_at(king) == Some(Piece::new(PieceKind::King, colour))
+            && self.piece_at(rook) == Some(Piece::new(PieceKind::Rook, colour))
+    }
+
+    fn after_edit(&mut self) {
+        let mut castling = self.castling();
+        for right in Castling::ALL {
+            if !self.can_have_castling_right(right) {
+                castling.remove(right);
+            }
+        }
+
+        self.set_castling(castling);
+        self.set_en_passant(None);
+        self.refresh_key();
+    }
+}
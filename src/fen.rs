@@ -0,0 +1,597 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::bitboard::Bitboard;
+use crate::board::Board;
+use crate::castling::{Castling, CastlingPermissions};
+use crate::location::{File, Rank, Square};
+use crate::piece::{Colour, Piece, PieceKind};
+
+/// The reasons a string can fail to parse as a FEN record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FenError {
+    MissingField(&'static str),
+    TooManyFields,
+    InvalidPiecePlacement,
+    InvalidSideToMove,
+    InvalidCastling,
+    InvalidEnPassant,
+    InvalidHalfmoveClock,
+    InvalidFullmoveNumber,
+    /// An EPD operation is malformed or not terminated by a semicolon.
+    InvalidOperation,
+    /// Strict parsing only: a side does not have exactly one king.
+    InvalidKingCount,
+    /// Strict parsing only: a pawn stands on the first or eighth rank.
+    PawnOnBackRank,
+    /// Strict parsing only: the side that just moved is in check.
+    OpponentInCheck,
+    /// Strict parsing only: a castling right whose king or rook is not on its original square.
+    ImpossibleCastling,
+    /// Strict parsing only: the en passant square does not follow a double pawn push.
+    ImpossibleEnPassant,
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenError::MissingField(field) => write!(f, "missing {field} field"),
+            FenError::TooManyFields => f.write_str("unexpected trailing fields"),
+            FenError::InvalidPiecePlacement => f.write_str("invalid piece placement"),
+            FenError::InvalidSideToMove => f.write_str("invalid side to move"),
+            FenError::InvalidCastling => f.write_str("invalid castling rights"),
+            FenError::InvalidEnPassant => f.write_str("invalid en passant square"),
+            FenError::InvalidHalfmoveClock => f.write_str("invalid halfmove clock"),
+            FenError::InvalidFullmoveNumber => f.write_str("invalid fullmove number"),
+            FenError::InvalidOperation => f.write_str("invalid EPD operation"),
+            FenError::InvalidKingCount => f.write_str("each side must have exactly one king"),
+            FenError::PawnOnBackRank => {
+                f.write_str("pawns cannot stand on the first or eighth rank")
+            }
+            FenError::OpponentInCheck => f.write_str("the side not to move is in check"),
+            FenError::ImpossibleCastling => {
+                f.write_str("castling rights without the king and rook on their original squares")
+            }
+            FenError::ImpossibleEnPassant => {
+                f.write_str("en passant square does not follow a double pawn push")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+impl Board {
+    /// Parses a position from Forsyth-Edwards Notation.
+    ///
+    /// The halfmove clock and fullmove number may be omitted, defaulting to `0` and `1`.
+    /// Castling rights may also be given as Shredder-FEN rook files, such as `HAha`.
+    pub fn from_fen(fen: &str) -> Result<Board, FenError> {
+        let mut fields = fen.split_whitespace();
+        let mut board = Board::empty();
+
+        let placement = fields
+            .next()
+            .ok_or(FenError::MissingField("piece placement"))?;
+        parse_placement(&mut board, placement)?;
+
+        let side_to_move = match fields.next() {
+            Some("w") => Colour::White,
+            Some("b") => Colour::Black,
+            Some(_) => return Err(FenError::InvalidSideToMove),
+            None => return Err(FenError::MissingField("side to move")),
+        };
+        board.set_side_to_move(side_to_move);
+
+        let castling = fields.next().ok_or(FenError::MissingField("castling"))?;
+        board.set_castling(parse_castling(castling)?);
+
+        let en_passant = match fields.next() {
+            Some("-") => None,
+            Some(square) => {
+                let square = Square::from_str(square).map_err(|_| FenError::InvalidEnPassant)?;
+                if square.rank() != Rank::Sixth.relative(side_to_move) {
+                    return Err(FenError::InvalidEnPassant);
+                }
+
+                Some(square)
+            }
+            None => return Err(FenError::MissingField("en passant")),
+        };
+        board.set_en_passant(en_passant);
+
+        if let Some(clock) = fields.next() {
+            board.set_halfmove_clock(clock.parse().map_err(|_| FenError::InvalidHalfmoveClock)?);
+        }
+
+        if let Some(number) = fields.next() {
+            match number.parse() {
+                Ok(number) if number > 0 => board.set_fullmove_number(number),
+                _ => return Err(FenError::InvalidFullmoveNumber),
+            }
+        }
+
+        if fields.next().is_some() {
+            return Err(FenError::TooManyFields);
+        }
+
+        board.refresh_key();
+
+        Ok(board)
+    }
+
+    /// Parses a position like [`Board::from_fen`], then rejects positions that cannot arise in
+    /// a game and would otherwise corrupt search: a side without exactly one king, pawns on
+    /// the back ranks, the side not to move in check, castling rights without the king and
+    /// rook in place, or an en passant square that no double push could have produced.
+    ///
+    /// GUIs that send sloppy FENs should keep using the lenient parser.
+    pub fn from_fen_strict(fen: &str) -> Result<Board, FenError> {
+        let board = Board::from_fen(fen)?;
+
+        for colour in Colour::ALL {
+            if board.pieces_of(colour, PieceKind::King).count() != 1 {
+                return Err(FenError::InvalidKingCount);
+            }
+        }
+
+        let back_ranks = Bitboard::rank(Rank::First) | Bitboard::rank(Rank::Eighth);
+        if !(board.pieces(PieceKind::Pawn) & back_ranks).is_empty() {
+            return Err(FenError::PawnOnBackRank);
+        }
+
+        if let Some(king) = board.king_square(!board.side_to_move()) {
+            if !(board.attackers_to(king, board.occupied()) & board.colour(board.side_to_move()))
+                .is_empty()
+            {
+                return Err(FenError::OpponentInCheck);
+            }
+        }
+
+        for castling in Castling::ALL {
+            if board.castling().has(castling) && !board.can_have_castling_right(castling) {
+                return Err(FenError::ImpossibleCastling);
+            }
+        }
+
+        if let Some(target) = board.en_passant() {
+            // The pawn that just moved stands in front of the target square, from the mover's
+            // point of view, and both the target and the square it came from are empty.
+            let them = !board.side_to_move();
+            let pushed = target.forward(them);
+            let origin = target.backward(them);
+            let pawn = Some(Piece::new(PieceKind::Pawn, them));
+
+            let valid = match (pushed, origin) {
+                (Some(pushed), Some(origin)) => {
+                    board.piece_at(pushed) == pawn
+                        && board.piece_at(target).is_none()
+                        && board.piece_at(origin).is_none()
+                }
+                _ => false,
+            };
+
+            if !valid {
+                return Err(FenError::ImpossibleEnPassant);
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Serializes the position as a full six-field FEN record.
+    pub fn to_fen(&self) -> String {
+        self.fen().to_string()
+    }
+
+    /// Returns a formatter for the position's FEN record, which can be switched to other
+    /// dialects before being displayed.
+    pub fn fen(&self) -> Fen<'_> {
+        Fen {
+            board: self,
+            shredder_castling: false,
+            x_fen_en_passant: false,
+            counters: true,
+        }
+    }
+}
+
+/// A displayable FEN record of a [`Board`], in standard FEN unless configured otherwise.
+///
+/// ```
+/// # use gambit::Board;
+/// let board = Board::default();
+///
+/// assert_eq!(
+///     board.fen().shredder_castling().without_counters().to_string(),
+///     "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha -"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Fen<'a> {
+    board: &'a Board,
+    shredder_castling: bool,
+    x_fen_en_passant: bool,
+    counters: bool,
+}
+
+impl Fen<'_> {
+    /// Writes castling rights as the files of the castling rooks (Shredder-FEN), e.g. `HAha`.
+    pub fn shredder_castling(mut self) -> Self {
+        self.shredder_castling = true;
+        self
+    }
+
+    /// Only writes the en passant square when an en passant capture is legal (X-FEN).
+    pub fn x_fen_en_passant(mut self) -> Self {
+        self.x_fen_en_passant = true;
+        self
+    }
+
+    /// Omits the halfmove clock and fullmove number, as in EPD.
+    pub fn without_counters(mut self) -> Self {
+        self.counters = false;
+        self
+    }
+}
+
+impl fmt::Display for Fen<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let board = self.board;
+
+        for rank in Rank::iter().rev() {
+            let mut empty = 0;
+
+            for file in File::iter() {
+                match board.piece_at(Square::new(file, rank)) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            write!(f, "{empty}")?;
+                            empty = 0;
+                        }
+
+                        write!(f, "{}", piece.to_char())?;
+                    }
+                    None => empty += 1,
+                }
+            }
+
+            if empty > 0 {
+                write!(f, "{empty}")?;
+            }
+
+            if rank != Rank::First {
+                f.write_str("/")?;
+            }
+        }
+
+        write!(f, " {} ", board.side_to_move())?;
+
+        if self.shredder_castling && !board.castling().is_empty() {
+            for castling in Castling::ALL
+                .into_iter()
+                .filter(|c| board.castling().has(*c))
+            {
+                let file = castling.rook_squares().0.file().to_char();
+
+                match castling.colour() {
+                    Colour::White => write!(f, "{}", file.to_ascii_uppercase())?,
+                    Colour::Black => write!(f, "{file}")?,
+                }
+            }
+        } else {
+            write!(f, "{}", board.castling())?;
+        }
+
+        let en_passant = board
+            .en_passant()
+            .filter(|_| !self.x_fen_en_passant || board.can_capture_en_passant());
+
+        match en_passant {
+            Some(square) => write!(f, " {square}")?,
+            None => f.write_str(" -")?,
+        }
+
+        if self.counters {
+            write!(f, " {} {}", board.halfmove_clock(), board.fullmove_number())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Board {
+    type Err = FenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Board::from_fen(s)
+    }
+}
+
+/// Parses the FEN castling field, `-` for no rights.
+impl FromStr for CastlingPermissions {
+    type Err = FenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_castling(s)
+    }
+}
+
+fn parse_placement(board: &mut Board, placement: &str) -> Result<(), FenError> {
+    let ranks: Vec<&str> = placement.split('/').collect();
+
+    if ranks.len() != 8 {
+        return Err(FenError::InvalidPiecePlacement);
+    }
+
+    for (rank, row) in Rank::iter().rev().zip(ranks) {
+        let mut file = 0;
+
+        for c in row.chars() {
+            if let Some(skip) = c.to_digit(10).filter(|skip| (1..=8).contains(skip)) {
+                file += skip as usize;
+                continue;
+            }
+
+            let piece = Piece::from_char(c).ok_or(FenError::InvalidPiecePlacement)?;
+            let file_on_board = File::from_index(file).ok_or(FenError::InvalidPiecePlacement)?;
+
+            board.put_piece(Square::new(file_on_board, rank), piece);
+            file += 1;
+        }
+
+        if file != 8 {
+            return Err(FenError::InvalidPiecePlacement);
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_castling(field: &str) -> Result<CastlingPermissions, FenError> {
+    let mut castling = CastlingPermissions::NONE;
+
+    if field == "-" {
+        return Ok(castling);
+    }
+
+    for c in field.chars() {
+        // Shredder-FEN and X-FEN name the castling rook's file instead; only the standard rook
+        // files are meaningful without Chess960 support.
+        let right = match c {
+            'H' => Some(Castling::WhiteKingside),
+            'A' => Some(Castling::WhiteQueenside),
+            'h' => Some(Castling::BlackKingside),
+            'a' => Some(Castling::BlackQueenside),
+            _ => Castling::from_char(c),
+        }
+        .ok_or(FenError::InvalidCastling)?;
+
+        if castling.has(right) {
+            return Err(FenError::InvalidCastling);
+        }
+
+        castling.insert(right);
+    }
+
+    Ok(castling)
+}
+
+/// An Extended Position Description record: a position given by the first four FEN fields,
+/// followed by operations such as `bm e4; id "opening";`.
+///
+/// Operations keep their order and operands are stored as written, with quotes removed. Inside
+/// quotes, `\"` and `\\` stand for a quote and a backslash. The `hmvc` and `fmvn` operations, if
+/// present, set the position's clocks.
+#[derive(Clone, Debug)]
+pub struct Epd {
+    board: Board,
+    operations: Vec<(String, Vec<String>)>,
+}
+
+impl Epd {
+    pub fn new(board: Board) -> Epd {
+        Epd {
+            board,
+            operations: Vec::new(),
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+
+    /// Returns the operands of the first operation with the given opcode.
+    pub fn operands(&self, opcode: &str) -> Option<&[String]> {
+        self.operations
+            .iter()
+            .find(|(op, _)| op == opcode)
+            .map(|(_, operands)| operands.as_slice())
+    }
+
+    /// Returns the first operand of the given opcode.
+    pub fn get(&self, opcode: &str) -> Option<&str> {
+        self.operands(opcode)?.first().map(String::as_str)
+    }
+
+    /// Iterates over the operations in the order they were written.
+    pub fn operations(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.operations
+            .iter()
+            .map(|(opcode, operands)| (opcode.as_str(), operands.as_slice()))
+    }
+
+    /// Sets an operation, replacing any earlier one with the same opcode.
+    pub fn set(&mut self, opcode: &str, operands: Vec<String>) {
+        match self.operations.iter_mut().find(|(op, _)| op == opcode) {
+            Some((_, existing)) => *existing = operands,
+            None => self.operations.push((opcode.to_string(), operands)),
+        }
+    }
+
+    /// The `id` operation, naming the position.
+    pub fn id(&self) -> Option<&str> {
+        self.get("id")
+    }
+
+    /// The `bm` operation: the best moves, in SAN as written.
+    pub fn best_moves(&self) -> &[String] {
+        self.operands("bm").unwrap_or_default()
+    }
+
+    /// The `am` operation: moves to avoid, in SAN as written.
+    pub fn avoid_moves(&self) -> &[String] {
+        self.operands("am").unwrap_or_default()
+    }
+
+    /// The `ce` operation: the evaluation in centipawns from the side to move's point of view.
+    pub fn centipawn_evaluation(&self) -> Option<i32> {
+        self.get("ce")?.parse().ok()
+    }
+
+    /// The `dm` operation: the number of moves to a forced mate.
+    pub fn direct_mate(&self) -> Option<u32> {
+        self.get("dm")?.parse().ok()
+    }
+}
+
+impl FromStr for Epd {
+    type Err = FenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rest = s.trim_start();
+        let mut fields = Vec::with_capacity(4);
+
+        while fields.len() < 4 {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+
+            if end == 0 {
+                break;
+            }
+
+            fields.push(&rest[..end]);
+            rest = rest[end..].trim_start();
+        }
+
+        let mut board = Board::from_fen(&fields.join(" "))?;
+        let operations = parse_operations(rest)?;
+
+        // Other operations, such as `noop`, may have no operands at all.
+        for (opcode, operands) in &operations {
+            let operand = operands.first().map(String::as_str);
+
+            match opcode.as_str() {
+                "hmvc" => board.set_halfmove_clock(
+                    operand
+                        .and_then(|operand| operand.parse().ok())
+                        .ok_or(FenError::InvalidHalfmoveClock)?,
+                ),
+                "fmvn" => match operand.and_then(|operand| operand.parse().ok()) {
+                    Some(number) if number > 0 => board.set_fullmove_number(number),
+                    _ => return Err(FenError::InvalidFullmoveNumber),
+                },
+                _ => {}
+            }
+        }
+
+        Ok(Epd { board, operations })
+    }
+}
+
+impl fmt::Display for Epd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.board.fen().without_counters())?;
+
+        for (opcode, operands) in &self.operations {
+            write!(f, " {opcode}")?;
+
+            for operand in operands {
+                let quoted = operand.is_empty()
+                    || operand.contains(|c: char| c.is_whitespace() || matches!(c, ';' | '"'));
+
+                if quoted {
+                    let escaped = operand.replace('\\', "\\\\").replace('"', "\\\"");
+                    write!(f, " \"{escaped}\"")?;
+                } else {
+                    write!(f, " {operand}")?;
+                }
+            }
+
+            f.write_str(";")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_operations(mut input: &str) -> Result<Vec<(String, Vec<String>)>, FenError> {
+    let mut operations = Vec::new();
+
+    loop {
+        input = input.trim_start();
+
+        if input.is_empty() {
+            return Ok(operations);
+        }
+
+        let end = input
+            .find(|c: char| c.is_whitespace() || c == ';')
+            .unwrap_or(input.len());
+        let opcode = &input[..end];
+
+        if opcode.is_empty() || !opcode.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return Err(FenError::InvalidOperation);
+        }
+
+        input = &input[end..];
+        let mut operands = Vec::new();
+
+        loop {
+            input = input.trim_start();
+
+            if let Some(after) = input.strip_prefix(';') {
+                input = after;
+                break;
+            }
+
+            if let Some(quoted) = input.strip_prefix('"') {
+                let (operand, after) = parse_quoted(quoted)?;
+
+                operands.push(operand);
+                input = after;
+            } else {
+                let end = input
+                    .find(|c: char| c.is_whitespace() || c == ';')
+                    .ok_or(FenError::InvalidOperation)?;
+
+                operands.push(input[..end].to_string());
+                input = &input[end..];
+            }
+        }
+
+        operations.push((opcode.to_string(), operands));
+    }
+}
+
+/// Reads a quoted operand up to its closing quote, which has already been opened, returning
+/// the operand and the input after the quote.
+fn parse_quoted(input: &str) -> Result<(String, &str), FenError> {
+    let mut operand = String::new();
+    let mut chars = input.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok((operand, &input[index + 1..])),
+            '\\' if input[index + 1..].starts_with(['"', '\\']) => {
+                operand.extend(chars.next().map(|(_, escaped)| escaped));
+            }
+            _ => operand.push(c),
+        }
+    }
+
+    Err(FenError::InvalidOperation)
+}
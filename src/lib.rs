@@ -1 +1,30 @@
-pub const STARTING_POSITION_FEN: &'static str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
\ No newline at end of file
+pub mod attacks;
+pub mod bitboard;
+pub mod board;
+pub mod castling;
+pub mod cuckoo;
+pub mod edit;
+pub mod fen;
+pub mod location;
+pub mod movegen;
+pub mod moves;
+pub mod perft;
+pub mod pgn;
+pub mod piece;
+pub mod san;
+pub mod zobrist;
+
+pub use bitboard::Bitboard;
+pub use board::{Board, State};
+pub use castling::{Castling, CastlingPermissions};
+pub use edit::EditError;
+pub use fen::{Epd, Fen, FenError};
+pub use location::{Direction, File, KnightDirection, Rank, Square};
+pub use movegen::MoveList;
+pub use moves::{Move, MoveKind, PackedMove};
+pub use perft::{perft, perft_detailed, PerftCounts};
+pub use pgn::{Game, PgnError};
+pub use piece::{Colour, Piece, PieceKind};
+pub use san::SanError;
+
+pub const STARTING_POSITION_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
@@ -0,0 +1,509 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::attacks;
+use crate::bitboard::Bitboard;
+use crate::piece::Colour;
+
+/// A file (column) of the board, from the queenside `A` to the kingside `H`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum File {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+}
+
+impl File {
+    /// Every file, from `A` to `H`.
+    pub const LIST: [File; 8] = [
+        File::A,
+        File::B,
+        File::C,
+        File::D,
+        File::E,
+        File::F,
+        File::G,
+        File::H,
+    ];
+
+    /// Returns the file with the given zero-based index, if it is on the board.
+    pub const fn from_index(index: usize) -> Option<File> {
+        if index < 8 {
+            Some(File::LIST[index])
+        } else {
+            None
+        }
+    }
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Iterates over every file from `A` to `H`.
+    pub fn iter() -> impl DoubleEndedIterator<Item = File> + ExactSizeIterator {
+        File::LIST.into_iter()
+    }
+
+    /// Parses a lowercase file letter (`'a'..='h'`).
+    pub const fn from_char(c: char) -> Option<File> {
+        match c {
+            'a'..='h' => File::from_index(c as usize - 'a' as usize),
+            _ => None,
+        }
+    }
+
+    pub const fn to_char(self) -> char {
+        (b'a' + self as u8) as char
+    }
+}
+
+/// A rank (row) of the board, numbered from White's side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Rank {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+    Sixth,
+    Seventh,
+    Eighth,
+}
+
+impl Rank {
+    /// Every rank, from the first to the eighth.
+    pub const LIST: [Rank; 8] = [
+        Rank::First,
+        Rank::Second,
+        Rank::Third,
+        Rank::Fourth,
+        Rank::Fifth,
+        Rank::Sixth,
+        Rank::Seventh,
+        Rank::Eighth,
+    ];
+
+    /// Returns the rank with the given zero-based index, if it is on the board.
+    pub const fn from_index(index: usize) -> Option<Rank> {
+        if index < 8 {
+            Some(Rank::LIST[index])
+        } else {
+            None
+        }
+    }
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Iterates over every rank from the first to the eighth.
+    pub fn iter() -> impl DoubleEndedIterator<Item = Rank> + ExactSizeIterator {
+        Rank::LIST.into_iter()
+    }
+
+    /// Returns the rank as `colour` counts it, from its own side of the board, so that
+    /// `Rank::Second.relative(colour)` is where its pawns start.
+    pub const fn relative(self, colour: Colour) -> Rank {
+        match colour {
+            Colour::White => self,
+            Colour::Black => Rank::LIST[7 - self.index()],
+        }
+    }
+
+    /// Parses a rank digit (`'1'..='8'`).
+    pub const fn from_char(c: char) -> Option<Rank> {
+        match c {
+            '1'..='8' => Rank::from_index(c as usize - '1' as usize),
+            _ => None,
+        }
+    }
+
+    pub const fn to_char(self) -> char {
+        (b'1' + self as u8) as char
+    }
+}
+
+/// The Chebyshev distance between two squares, indexed by [`Square::index`]: the number of
+/// moves a king needs to walk from one to the other.
+pub const DISTANCE: [[u8; 64]; 64] = distance_table(false);
+
+/// The Manhattan distance between two squares, indexed by [`Square::index`]: the number of
+/// files plus the number of ranks between them.
+pub const MANHATTAN: [[u8; 64]; 64] = distance_table(true);
+
+/// The Chebyshev distance from each square to the nearest of the four centre squares.
+pub const CENTER_DISTANCE: [u8; 64] = center_distance_table(false);
+
+/// The Manhattan distance from each square to the nearest of the four centre squares.
+pub const CENTER_MANHATTAN: [u8; 64] = center_distance_table(true);
+
+const fn distance(a: usize, b: usize, manhattan: bool) -> u8 {
+    let files = (a % 8).abs_diff(b % 8) as u8;
+    let ranks = (a / 8).abs_diff(b / 8) as u8;
+
+    if manhattan {
+        files + ranks
+    } else if files > ranks {
+        files
+    } else {
+        ranks
+    }
+}
+
+const fn distance_table(manhattan: bool) -> [[u8; 64]; 64] {
+    let mut table = [[0; 64]; 64];
+    let mut a = 0;
+
+    while a < 64 {
+        let mut b = 0;
+
+        while b < 64 {
+            table[a][b] = distance(a, b, manhattan);
+            b += 1;
+        }
+
+        a += 1;
+    }
+
+    table
+}
+
+const fn center_distance_table(manhattan: bool) -> [u8; 64] {
+    const CENTER: [usize; 4] = [
+        Square::D4.index(),
+        Square::E4.index(),
+        Square::D5.index(),
+        Square::E5.index(),
+    ];
+
+    let mut table = [0; 64];
+    let mut index = 0;
+
+    while index < 64 {
+        let mut nearest = u8::MAX;
+        let mut i = 0;
+
+        while i < CENTER.len() {
+            let d = distance(index, CENTER[i], manhattan);
+            if d < nearest {
+                nearest = d;
+            }
+
+            i += 1;
+        }
+
+        table[index] = nearest;
+        index += 1;
+    }
+
+    table
+}
+
+/// A square of the board, indexed little-endian rank-file (`A1 = 0`, `H8 = 63`).
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Square {
+    A1, B1, C1, D1, E1, F1, G1, H1,
+    A2, B2, C2, D2, E2, F2, G2, H2,
+    A3, B3, C3, D3, E3, F3, G3, H3,
+    A4, B4, C4, D4, E4, F4, G4, H4,
+    A5, B5, C5, D5, E5, F5, G5, H5,
+    A6, B6, C6, D6, E6, F6, G6, H6,
+    A7, B7, C7, D7, E7, F7, G7, H7,
+    A8, B8, C8, D8, E8, F8, G8, H8,
+}
+
+impl Square {
+    /// Every square in index order, from `A1` to `H8`, for loops in `const` code that cannot
+    /// use iterators.
+    #[rustfmt::skip]
+    pub const LIST: [Square; 64] = [
+        Square::A1, Square::B1, Square::C1, Square::D1, Square::E1, Square::F1, Square::G1, Square::H1,
+        Square::A2, Square::B2, Square::C2, Square::D2, Square::E2, Square::F2, Square::G2, Square::H2,
+        Square::A3, Square::B3, Square::C3, Square::D3, Square::E3, Square::F3, Square::G3, Square::H3,
+        Square::A4, Square::B4, Square::C4, Square::D4, Square::E4, Square::F4, Square::G4, Square::H4,
+        Square::A5, Square::B5, Square::C5, Square::D5, Square::E5, Square::F5, Square::G5, Square::H5,
+        Square::A6, Square::B6, Square::C6, Square::D6, Square::E6, Square::F6, Square::G6, Square::H6,
+        Square::A7, Square::B7, Square::C7, Square::D7, Square::E7, Square::F7, Square::G7, Square::H7,
+        Square::A8, Square::B8, Square::C8, Square::D8, Square::E8, Square::F8, Square::G8, Square::H8,
+    ];
+
+    pub const fn new(file: File, rank: Rank) -> Square {
+        Square::LIST[rank.index() * 8 + file.index()]
+    }
+
+    /// Returns the square with the given index, if it is on the board.
+    pub const fn from_index(index: usize) -> Option<Square> {
+        if index < 64 {
+            Some(Square::LIST[index])
+        } else {
+            None
+        }
+    }
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub const fn file(self) -> File {
+        File::LIST[self.index() % 8]
+    }
+
+    pub const fn rank(self) -> Rank {
+        Rank::LIST[self.index() / 8]
+    }
+
+    /// Returns the number of king moves between this square and `other`.
+    pub const fn distance(self, other: Square) -> u8 {
+        DISTANCE[self.index()][other.index()]
+    }
+
+    /// Returns the number of files plus the number of ranks between this square and `other`.
+    pub const fn manhattan_distance(self, other: Square) -> u8 {
+        MANHATTAN[self.index()][other.index()]
+    }
+
+    /// Returns the squares strictly between this square and `other` if they share a rank, file
+    /// or diagonal, and an empty set otherwise.
+    pub fn between(self, other: Square) -> Bitboard {
+        attacks::between(self, other)
+    }
+
+    /// Returns whether this is a dark square, as `A1` is.
+    pub const fn is_dark(self) -> bool {
+        (self.file().index() + self.rank().index()).is_multiple_of(2)
+    }
+
+    /// Returns the colour of the square, with `Colour::Black` for the dark squares.
+    pub const fn colour(self) -> Colour {
+        if self.is_dark() {
+            Colour::Black
+        } else {
+            Colour::White
+        }
+    }
+
+    /// Iterates over every square from `A1` to `H8`.
+    pub fn iter() -> impl DoubleEndedIterator<Item = Square> + ExactSizeIterator {
+        Square::LIST.into_iter()
+    }
+
+    /// Returns the square mirrored across the middle of the board, e.g. `E2` becomes `E7`.
+    pub const fn flipped(self) -> Square {
+        Square::LIST[self.index() ^ 56]
+    }
+
+    /// Returns the square as `colour` sees it, mirrored for Black, so that one table or one
+    /// rule written from White's side serves both colours.
+    pub const fn relative(self, colour: Colour) -> Square {
+        match colour {
+            Colour::White => self,
+            Colour::Black => self.flipped(),
+        }
+    }
+
+    /// Returns the square in front of this one from `colour`'s side, the way its pawns move,
+    /// if it is on the board.
+    pub const fn forward(self, colour: Colour) -> Option<Square> {
+        match colour {
+            Colour::White => self.offset(0, 1),
+            Colour::Black => self.offset(0, -1),
+        }
+    }
+
+    /// Returns the square behind this one from `colour`'s side, if it is on the board.
+    pub const fn backward(self, colour: Colour) -> Option<Square> {
+        match colour {
+            Colour::White => self.offset(0, -1),
+            Colour::Black => self.offset(0, 1),
+        }
+    }
+
+    /// Returns the square offset by the given number of files and ranks, if it is on the board.
+    pub const fn offset(self, file_delta: i8, rank_delta: i8) -> Option<Square> {
+        let file = self.file().index() as i8 + file_delta;
+        let rank = self.rank().index() as i8 + rank_delta;
+
+        if file < 0 || file >= 8 || rank < 0 || rank >= 8 {
+            return None;
+        }
+
+        Square::from_index(rank as usize * 8 + file as usize)
+    }
+
+    /// Returns the neighbouring square in the given direction, if it is on the board.
+    pub const fn step(self, direction: Direction) -> Option<Square> {
+        let (file_delta, rank_delta) = direction.delta();
+
+        self.offset(file_delta, rank_delta)
+    }
+
+    /// Returns the square a knight reaches with the given jump, if it is on the board.
+    pub const fn jump(self, direction: KnightDirection) -> Option<Square> {
+        let (file_delta, rank_delta) = direction.delta();
+
+        self.offset(file_delta, rank_delta)
+    }
+}
+
+/// One of the eight compass directions a king or slider moves in, with North towards rank 8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+    NorthEast,
+    SouthEast,
+    SouthWest,
+    NorthWest,
+}
+
+impl Direction {
+    /// Every direction, in [`Direction::index`] order: the orthogonals clockwise from North,
+    /// then the diagonals clockwise from North-East, so that `index ^ 2` is the opposite
+    /// direction.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+        Direction::NorthEast,
+        Direction::SouthEast,
+        Direction::SouthWest,
+        Direction::NorthWest,
+    ];
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the `(file, rank)` step taken when moving one square in this direction.
+    pub const fn delta(self) -> (i8, i8) {
+        match self {
+            Direction::North => (0, 1),
+            Direction::East => (1, 0),
+            Direction::South => (0, -1),
+            Direction::West => (-1, 0),
+            Direction::NorthEast => (1, 1),
+            Direction::SouthEast => (1, -1),
+            Direction::SouthWest => (-1, -1),
+            Direction::NorthWest => (-1, 1),
+        }
+    }
+
+    /// Returns the change in square index when moving one square in this direction, ignoring
+    /// the edges of the board.
+    pub const fn offset(self) -> i8 {
+        let (file_delta, rank_delta) = self.delta();
+
+        rank_delta * 8 + file_delta
+    }
+
+    /// Whether moving in this direction increases the square index.
+    pub const fn is_positive(self) -> bool {
+        matches!(
+            self,
+            Direction::North | Direction::East | Direction::NorthEast | Direction::NorthWest
+        )
+    }
+}
+
+/// One of the eight jumps a knight makes, named by the compass points it travels towards,
+/// two squares along the first and one along the second.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum KnightDirection {
+    NorthNorthEast,
+    EastNorthEast,
+    EastSouthEast,
+    SouthSouthEast,
+    SouthSouthWest,
+    WestSouthWest,
+    WestNorthWest,
+    NorthNorthWest,
+}
+
+impl KnightDirection {
+    /// Every jump, clockwise from North-North-East.
+    pub const ALL: [KnightDirection; 8] = [
+        KnightDirection::NorthNorthEast,
+        KnightDirection::EastNorthEast,
+        KnightDirection::EastSouthEast,
+        KnightDirection::SouthSouthEast,
+        KnightDirection::SouthSouthWest,
+        KnightDirection::WestSouthWest,
+        KnightDirection::WestNorthWest,
+        KnightDirection::NorthNorthWest,
+    ];
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the `(file, rank)` change made by this jump.
+    pub const fn delta(self) -> (i8, i8) {
+        match self {
+            KnightDirection::NorthNorthEast => (1, 2),
+            KnightDirection::EastNorthEast => (2, 1),
+            KnightDirection::EastSouthEast => (2, -1),
+            KnightDirection::SouthSouthEast => (1, -2),
+            KnightDirection::SouthSouthWest => (-1, -2),
+            KnightDirection::WestSouthWest => (-2, -1),
+            KnightDirection::WestNorthWest => (-2, 1),
+            KnightDirection::NorthNorthWest => (-1, 2),
+        }
+    }
+
+    /// Returns the change in square index made by this jump, ignoring the edges of the board.
+    pub const fn offset(self) -> i8 {
+        let (file_delta, rank_delta) = self.delta();
+
+        rank_delta * 8 + file_delta
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.file().to_char(), self.rank().to_char())
+    }
+}
+
+/// The error returned when a string is not a square in algebraic notation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseSquareError;
+
+impl fmt::Display for ParseSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid square, expected a file and rank such as \"e4\"")
+    }
+}
+
+impl std::error::Error for ParseSquareError {}
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(file), Some(rank), None) => {
+                let file = File::from_char(file).ok_or(ParseSquareError)?;
+                let rank = Rank::from_char(rank).ok_or(ParseSquareError)?;
+
+                Ok(Square::new(file, rank))
+            }
+            _ => Err(ParseSquareError),
+        }
+    }
+}
@@ -0,0 +1,373 @@
+//! Legal move generation.
+//!
+//! Moves are generated directly as legal moves rather than filtered after the fact: checks are
+//! answered by restricting destinations to the checking piece and the squares between it and
+//! the king, and pinned pieces are restricted to the line through their king.
+
+use std::ops::{Deref, DerefMut};
+
+use crate::attacks;
+use crate::bitboard::Bitboard;
+use crate::board::Board;
+use crate::castling::Castling;
+use crate::location::{Direction, Rank, Square};
+use crate::moves::Move;
+use crate::piece::{Colour, Piece, PieceKind};
+
+/// An upper bound on the number of legal moves in any reachable position (the maximum is 218).
+pub const MAX_MOVES: usize = 256;
+
+/// A fixed-capacity list of moves that lives on the stack.
+#[derive(Clone)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    pub const fn new() -> MoveList {
+        MoveList {
+            moves: [Move::new(Square::A1, Square::A1, PieceKind::Pawn); MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub fn push(&mut self, mv: Move) {
+        debug_assert!(self.len < MAX_MOVES, "move list overflow");
+
+        self.moves[self.len] = mv;
+        self.len += 1;
+    }
+
+    /// Pushes `mv` once for each square in `targets`, as its destination, so that a piece's
+    /// moves that differ only in where they land share one encoding of the rest.
+    #[inline]
+    pub fn push_each(&mut self, mv: Move, targets: Bitboard) {
+        for to in targets {
+            self.push(mv.with_to(to));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Iterates over the captures in the list, including en passant.
+    pub fn captures(&self) -> impl Iterator<Item = Move> + '_ {
+        self.iter().copied().filter(|mv| mv.is_capture())
+    }
+
+    /// Iterates over the promotions in the list, with or without a capture.
+    pub fn promotions(&self) -> impl Iterator<Item = Move> + '_ {
+        self.iter().copied().filter(|mv| mv.is_promotion())
+    }
+
+    /// Iterates over the moves of pieces of the given kind.
+    pub fn of_piece(&self, kind: PieceKind) -> impl Iterator<Item = Move> + '_ {
+        self.iter().copied().filter(move |mv| mv.piece() == kind)
+    }
+
+    /// Iterates over the moves starting on `square`.
+    pub fn from_square(&self, square: Square) -> impl Iterator<Item = Move> + '_ {
+        self.iter().copied().filter(move |mv| mv.from() == square)
+    }
+
+    /// Iterates over the moves ending on `square`, e.g. to find which pieces can reach it.
+    pub fn to_square(&self, square: Square) -> impl Iterator<Item = Move> + '_ {
+        self.iter().copied().filter(move |mv| mv.to() == square)
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> MoveList {
+        MoveList::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl std::fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Generates every legal move for the side to move.
+///
+/// A position without a king for the side to move has no legal moves.
+pub fn legal_moves(board: &Board) -> MoveList {
+    generate(board, Bitboard::FULL)
+}
+
+/// Generates the legal moves of the piece on `square`, which are none if the square is empty
+/// or holds a piece of the side not to move.
+///
+/// This is cheaper than filtering [`legal_moves`], e.g. for a GUI highlighting where a
+/// selected piece can go.
+pub fn moves_from(board: &Board, square: Square) -> MoveList {
+    generate(board, Bitboard::from(square))
+}
+
+/// Generates the legal moves of the pieces of the side to move that stand on `from`.
+fn generate(board: &Board, from: Bitboard) -> MoveList {
+    let mut list = MoveList::new();
+
+    let us = board.side_to_move();
+    let Some(king) = board.king_square(us) else {
+        return list;
+    };
+
+    let occupied = board.occupied();
+    let own = board.colour(us);
+    let enemies = board.colour(!us);
+    let checkers = board.checkers();
+
+    if from.contains(king) {
+        let without_king = occupied ^ king;
+        let safe = (attacks::king_attacks(king) & !own)
+            .into_iter()
+            .filter(|&to| (board.attackers_to(to, without_king) & enemies).is_empty())
+            .fold(Bitboard::EMPTY, |safe, to| safe | to);
+
+        push_moves(&mut list, board, king, safe, PieceKind::King);
+    }
+
+    // In double check only the king can move.
+    if checkers.count() > 1 {
+        return list;
+    }
+
+    let evasions = match checkers.lsb() {
+        Some(checker) => attacks::between(king, checker) | checker,
+        None => Bitboard::FULL,
+    };
+    let pinned = board.pinned_pieces(us);
+
+    for kind in [
+        PieceKind::Knight,
+        PieceKind::Bishop,
+        PieceKind::Rook,
+        PieceKind::Queen,
+    ] {
+        for square in board.pieces_of(us, kind) & from {
+            let mut targets =
+                attacks::piece_attacks(Piece::new(kind, us), square, occupied) & !own & evasions;
+
+            if pinned.contains(square) {
+                targets &= attacks::line(king, square);
+            }
+
+            push_moves(&mut list, board, square, targets, kind);
+        }
+    }
+
+    generate_pawn_moves(&mut list, board, from, king, evasions, pinned);
+
+    if checkers.is_empty() && from.contains(king) {
+        generate_castling(&mut list, board);
+    }
+
+    list
+}
+
+/// Generates pawn pushes, captures, promotions and en passant captures for the pawns on
+/// `pawns`.
+///
+/// Pawns move together: shifting the whole set forward or diagonally gives every destination
+/// at once, and each move's origin is one step back from its destination. Pinned pawns are
+/// rare enough to be handled one at a time, each kept to the line through its king.
+fn generate_pawn_moves(
+    list: &mut MoveList,
+    board: &Board,
+    pawns: Bitboard,
+    king: Square,
+    evasions: Bitboard,
+    pinned: Bitboard,
+) {
+    let us = board.side_to_move();
+    let pawns = board.pieces_of(us, PieceKind::Pawn) & pawns;
+
+    generate_pawn_shifts(list, board, pawns & !pinned, evasions);
+
+    for from in pawns & pinned {
+        let allowed = evasions & attacks::line(king, from);
+        generate_pawn_shifts(list, board, Bitboard::from(from), allowed);
+    }
+
+    // En passant is checked on its own, since it can expose the king in ways the masks miss.
+    if let Some(target) = board.en_passant() {
+        for from in attacks::pawn_attacks(!us, target) & pawns {
+            if is_legal_en_passant(board, king, from, target) {
+                list.push(Move::en_passant(from, target));
+            }
+        }
+    }
+}
+
+/// Generates the pushes and captures of `pawns` that land on `allowed`.
+fn generate_pawn_shifts(list: &mut MoveList, board: &Board, pawns: Bitboard, allowed: Bitboard) {
+    let us = board.side_to_move();
+    let empty = !board.occupied();
+    let enemies = board.colour(!us);
+
+    let (forward, captures) = match us {
+        Colour::White => (
+            Direction::North,
+            [Direction::NorthWest, Direction::NorthEast],
+        ),
+        Colour::Black => (
+            Direction::South,
+            [Direction::SouthWest, Direction::SouthEast],
+        ),
+    };
+
+    let single = pawns.shift(forward) & empty;
+    let double = (single & Bitboard::rank(Rank::Third.relative(us))).shift(forward) & empty;
+
+    push_pawn_moves(list, board, single & allowed, forward);
+
+    for to in double & allowed {
+        let from = origin(origin(to, forward), forward);
+        list.push(Move::double_push(from, to));
+    }
+
+    for direction in captures {
+        push_pawn_moves(
+            list,
+            board,
+            pawns.shift(direction) & enemies & allowed,
+            direction,
+        );
+    }
+}
+
+/// Pushes the moves of pawns that reached `targets` by a step in `direction`, as promotions
+/// where the targets are on the last rank.
+fn push_pawn_moves(list: &mut MoveList, board: &Board, targets: Bitboard, direction: Direction) {
+    let promotions = Bitboard::rank(Rank::First) | Bitboard::rank(Rank::Eighth);
+
+    for (captured, targets) in by_capture(board, targets) {
+        for to in targets & !promotions {
+            list.push(with_captured(
+                Move::new(origin(to, direction), to, PieceKind::Pawn),
+                captured,
+            ));
+        }
+
+        for to in targets & promotions {
+            let mv = with_captured(
+                Move::new(origin(to, direction), to, PieceKind::Pawn),
+                captured,
+            );
+
+            for promotion in [
+                PieceKind::Queen,
+                PieceKind::Rook,
+                PieceKind::Bishop,
+                PieceKind::Knight,
+            ] {
+                list.push(mv.with_promotion(promotion));
+            }
+        }
+    }
+}
+
+/// Returns the square a step in `direction` to `to` started from.
+fn origin(to: Square, direction: Direction) -> Square {
+    Square::LIST[(to.index() as i8 - direction.offset()) as usize]
+}
+
+/// Checks an en passant capture by replaying its effect on the occupancy, since removing two
+/// pawns from a line can expose the king in ways the pin and check masks do not capture.
+fn is_legal_en_passant(board: &Board, king: Square, from: Square, target: Square) -> bool {
+    let us = board.side_to_move();
+    let captured = Square::new(target.file(), from.rank());
+
+    if board.piece_at(captured) != Some(Piece::new(PieceKind::Pawn, !us)) {
+        return false;
+    }
+
+    let occupied = (board.occupied() ^ from ^ captured) | target;
+    let enemies = board.colour(!us) ^ captured;
+
+    (board.attackers_to(king, occupied) & enemies).is_empty()
+}
+
+/// Generates castling for the side to move, which must not be in check.
+fn generate_castling(list: &mut MoveList, board: &Board) {
+    let us = board.side_to_move();
+
+    for castling in [Castling::kingside(us), Castling::queenside(us)] {
+        if board.can_castle(castling) {
+            let (king_from, king_to) = castling.king_squares();
+            list.push(Move::castle(king_from, king_to));
+        }
+    }
+}
+
+/// Pushes the moves of the `kind` piece on `from` to each of `targets`.
+fn push_moves(
+    list: &mut MoveList,
+    board: &Board,
+    from: Square,
+    targets: Bitboard,
+    kind: PieceKind,
+) {
+    for (captured, targets) in by_capture(board, targets) {
+        list.push_each(
+            with_captured(Move::new(from, from, kind), captured),
+            targets,
+        );
+    }
+}
+
+/// Splits `targets` by what the side to move would capture there: first the empty squares,
+/// then the squares of each kind of enemy piece, so that the capture is worked out once per
+/// kind rather than once per square.
+fn by_capture(
+    board: &Board,
+    targets: Bitboard,
+) -> impl Iterator<Item = (Option<PieceKind>, Bitboard)> + '_ {
+    let them = !board.side_to_move();
+
+    [
+        PieceKind::Pawn,
+        PieceKind::Knight,
+        PieceKind::Bishop,
+        PieceKind::Rook,
+        PieceKind::Queen,
+    ]
+    .into_iter()
+    .map(move |kind| (Some(kind), targets & board.pieces_of(them, kind)))
+    .chain([(None, targets & !board.occupied())])
+    .filter(|&(_, targets)| !targets.is_empty())
+}
+
+fn with_captured(mv: Move, captured: Option<PieceKind>) -> Move {
+    match captured {
+        Some(captured) => mv.with_capture(captured),
+        None => mv,
+    }
+}
@@ -0,0 +1,313 @@
+use std::fmt;
+
+use crate::board::Board;
+use crate::location::Square;
+use crate::piece::PieceKind;
+
+const SQUARE_MASK: u32 = 0b11_1111;
+const KIND_MASK: u32 = 0b111;
+const NO_KIND: u32 = KIND_MASK;
+
+const TO_SHIFT: u32 = 6;
+const PIECE_SHIFT: u32 = 12;
+const CAPTURED_SHIFT: u32 = 15;
+const PROMOTION_SHIFT: u32 = 18;
+
+const EN_PASSANT_FLAG: u32 = 1 << 21;
+const CASTLE_FLAG: u32 = 1 << 22;
+const DOUBLE_PUSH_FLAG: u32 = 1 << 23;
+
+/// The category a move falls into, as used by move ordering and pruning decisions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MoveKind {
+    Quiet,
+    DoublePush,
+    Castle,
+    Capture,
+    EnPassant,
+    Promotion,
+    PromotionCapture,
+}
+
+/// A move packed into 32 bits, carrying enough information to play it without looking at the
+/// board: origin, destination, moving piece, captured piece, promotion and special-move flags.
+///
+/// Castling is encoded as the king's move, e.g. `e1g1`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move(u32);
+
+impl Move {
+    /// Creates a quiet move of `piece` from `from` to `to`.
+    pub const fn new(from: Square, to: Square, piece: PieceKind) -> Move {
+        Move(
+            from.index() as u32
+                | (to.index() as u32) << TO_SHIFT
+                | (piece as u32) << PIECE_SHIFT
+                | NO_KIND << CAPTURED_SHIFT
+                | NO_KIND << PROMOTION_SHIFT,
+        )
+    }
+
+    /// Returns this move capturing a piece of the given kind on its destination.
+    pub const fn with_capture(self, captured: PieceKind) -> Move {
+        Move(self.0 & !(KIND_MASK << CAPTURED_SHIFT) | (captured as u32) << CAPTURED_SHIFT)
+    }
+
+    /// Returns this move promoting to the given kind.
+    pub const fn with_promotion(self, promotion: PieceKind) -> Move {
+        Move(self.0 & !(KIND_MASK << PROMOTION_SHIFT) | (promotion as u32) << PROMOTION_SHIFT)
+    }
+
+    /// Returns this move with its destination replaced by `to`, keeping everything else.
+    pub const fn with_to(self, to: Square) -> Move {
+        Move(self.0 & !(SQUARE_MASK << TO_SHIFT) | (to.index() as u32) << TO_SHIFT)
+    }
+
+    pub const fn double_push(from: Square, to: Square) -> Move {
+        Move(Move::new(from, to, PieceKind::Pawn).0 | DOUBLE_PUSH_FLAG)
+    }
+
+    pub const fn en_passant(from: Square, to: Square) -> Move {
+        Move(
+            Move::new(from, to, PieceKind::Pawn)
+                .with_capture(PieceKind::Pawn)
+                .0
+                | EN_PASSANT_FLAG,
+        )
+    }
+
+    /// Creates a castling move, given as the king's origin and destination squares.
+    pub const fn castle(from: Square, to: Square) -> Move {
+        Move(Move::new(from, to, PieceKind::King).0 | CASTLE_FLAG)
+    }
+
+    pub const fn from(self) -> Square {
+        square(self.0 & SQUARE_MASK)
+    }
+
+    pub const fn to(self) -> Square {
+        square(self.0 >> TO_SHIFT & SQUARE_MASK)
+    }
+
+    pub const fn piece(self) -> PieceKind {
+        match kind(self.0 >> PIECE_SHIFT) {
+            Some(piece) => piece,
+            None => unreachable!(),
+        }
+    }
+
+    /// Returns the kind of the captured piece, which for en passant is a pawn not on [`Move::to`].
+    pub const fn captured(self) -> Option<PieceKind> {
+        kind(self.0 >> CAPTURED_SHIFT)
+    }
+
+    pub const fn promotion(self) -> Option<PieceKind> {
+        kind(self.0 >> PROMOTION_SHIFT)
+    }
+
+    pub const fn is_en_passant(self) -> bool {
+        self.0 & EN_PASSANT_FLAG != 0
+    }
+
+    pub const fn is_castle(self) -> bool {
+        self.0 & CASTLE_FLAG != 0
+    }
+
+    pub const fn is_double_push(self) -> bool {
+        self.0 & DOUBLE_PUSH_FLAG != 0
+    }
+
+    /// Whether the move captures a piece, including en passant.
+    pub const fn is_capture(self) -> bool {
+        self.0 >> CAPTURED_SHIFT & KIND_MASK != NO_KIND
+    }
+
+    pub const fn is_promotion(self) -> bool {
+        self.0 >> PROMOTION_SHIFT & KIND_MASK != NO_KIND
+    }
+
+    /// Whether the move changes material, i.e. is a capture or a promotion.
+    pub const fn is_tactical(self) -> bool {
+        self.is_capture() || self.is_promotion()
+    }
+
+    /// Whether the move neither captures nor promotes. Castling and double pushes are quiet.
+    pub const fn is_quiet(self) -> bool {
+        !self.is_tactical()
+    }
+
+    /// Packs the move into 16 bits, dropping the piece information that can be recovered from
+    /// the board.
+    pub const fn pack(self) -> PackedMove {
+        let (special, promotion) = match self.promotion() {
+            Some(promotion) => (
+                SPECIAL_PROMOTION,
+                promotion as u16 - PieceKind::Knight as u16,
+            ),
+            None if self.is_en_passant() => (SPECIAL_EN_PASSANT, 0),
+            None if self.is_castle() => (SPECIAL_CASTLE, 0),
+            None => (0, 0),
+        };
+
+        PackedMove(
+            self.from().index() as u16
+                | (self.to().index() as u16) << TO_SHIFT
+                | promotion << PACKED_PROMOTION_SHIFT
+                | special << PACKED_SPECIAL_SHIFT,
+        )
+    }
+
+    pub const fn kind(self) -> MoveKind {
+        match (self.is_capture(), self.is_promotion()) {
+            (true, true) => MoveKind::PromotionCapture,
+            (false, true) => MoveKind::Promotion,
+            (true, false) if self.is_en_passant() => MoveKind::EnPassant,
+            (true, false) => MoveKind::Capture,
+            (false, false) if self.is_castle() => MoveKind::Castle,
+            (false, false) if self.is_double_push() => MoveKind::DoublePush,
+            (false, false) => MoveKind::Quiet,
+        }
+    }
+}
+
+const fn square(index: u32) -> Square {
+    Square::LIST[index as usize]
+}
+
+const fn kind(bits: u32) -> Option<PieceKind> {
+    match bits & KIND_MASK {
+        0 => Some(PieceKind::Pawn),
+        1 => Some(PieceKind::Knight),
+        2 => Some(PieceKind::Bishop),
+        3 => Some(PieceKind::Rook),
+        4 => Some(PieceKind::Queen),
+        5 => Some(PieceKind::King),
+        _ => None,
+    }
+}
+
+impl fmt::Debug for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Move({self})")
+    }
+}
+
+/// Formats the move in UCI long algebraic notation, such as `e2e4` or `e7e8q`.
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.from(), self.to())?;
+
+        if let Some(promotion) = self.promotion() {
+            write!(f, "{}", promotion.to_char())?;
+        }
+
+        Ok(())
+    }
+}
+
+const PACKED_PROMOTION_SHIFT: u16 = 12;
+const PACKED_SPECIAL_SHIFT: u16 = 14;
+
+const SPECIAL_PROMOTION: u16 = 1;
+const SPECIAL_EN_PASSANT: u16 = 2;
+const SPECIAL_CASTLE: u16 = 3;
+
+/// A move packed into 16 bits for compact storage in hash entries and move tables.
+///
+/// Only the origin, destination, promotion and special-move kind are kept; the moving and
+/// captured pieces are recovered from the board with [`PackedMove::unpack`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PackedMove(u16);
+
+impl PackedMove {
+    /// The null packed move (`a1a1`), used to mark empty slots.
+    pub const NONE: PackedMove = PackedMove(0);
+
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    pub const fn from_bits(bits: u16) -> PackedMove {
+        PackedMove(bits)
+    }
+
+    pub const fn is_none(self) -> bool {
+        self.0 == PackedMove::NONE.0
+    }
+
+    pub const fn from(self) -> Square {
+        square(self.0 as u32 & SQUARE_MASK)
+    }
+
+    pub const fn to(self) -> Square {
+        square((self.0 >> TO_SHIFT) as u32 & SQUARE_MASK)
+    }
+
+    /// Expands the move against `board`, the position it was packed in.
+    ///
+    /// Returns `None` unless the expanded move is pseudo-legal, as a move packed in another
+    /// position whose hash collided may not be; see [`Board::is_pseudo_legal`]. The result is
+    /// not guaranteed to be legal.
+    pub fn unpack(self, board: &Board) -> Option<Move> {
+        let (from, to) = (self.from(), self.to());
+        let us = board.side_to_move();
+
+        let piece = board.piece_at(from).filter(|piece| piece.colour == us)?;
+        let captured = match board.piece_at(to) {
+            Some(target) if target.colour == us => return None,
+            Some(target) => Some(target.kind),
+            None => None,
+        };
+
+        let mv = match self.0 >> PACKED_SPECIAL_SHIFT {
+            SPECIAL_EN_PASSANT if piece.kind == PieceKind::Pawn => Move::en_passant(from, to),
+            SPECIAL_CASTLE if piece.kind == PieceKind::King => Move::castle(from, to),
+            SPECIAL_PROMOTION if piece.kind == PieceKind::Pawn => {
+                let promotion = PROMOTIONS[(self.0 >> PACKED_PROMOTION_SHIFT & 0b11) as usize];
+
+                Move::new(from, to, piece.kind).with_promotion(promotion)
+            }
+            0 if piece.kind == PieceKind::Pawn
+                && from.rank().index().abs_diff(to.rank().index()) == 2 =>
+            {
+                Move::double_push(from, to)
+            }
+            0 => Move::new(from, to, piece.kind),
+            _ => return None,
+        };
+
+        let mv = match captured {
+            Some(captured) => mv.with_capture(captured),
+            None => mv,
+        };
+
+        board.is_pseudo_legal(mv).then_some(mv)
+    }
+}
+
+const PROMOTIONS: [PieceKind; 4] = [
+    PieceKind::Knight,
+    PieceKind::Bishop,
+    PieceKind::Rook,
+    PieceKind::Queen,
+];
+
+impl From<Move> for PackedMove {
+    fn from(mv: Move) -> PackedMove {
+        mv.pack()
+    }
+}
+
+impl fmt::Debug for PackedMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PackedMove({}{}", self.from(), self.to())?;
+
+        if self.0 >> PACKED_SPECIAL_SHIFT == SPECIAL_PROMOTION {
+            let promotion = PROMOTIONS[(self.0 >> PACKED_PROMOTION_SHIFT & 0b11) as usize];
+
+            write!(f, "{}", promotion.to_char())?;
+        }
+
+        write!(f, ")")
+    }
+}
@@ -0,0 +1,86 @@
+//! Counting the legal move tree, the standard check on a move generator.
+//!
+//! [`perft`] counts the positions at a given depth, and [`perft_detailed`] breaks each depth
+//! down by kind of move in the layout of the published tables, so a wrong count points
+//! towards the rule at fault.
+
+use crate::board::Board;
+use crate::movegen;
+
+/// The positions reached at one depth of the tree, counted by the move that reached them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PerftCounts {
+    pub nodes: u64,
+    /// Captures, including en passant.
+    pub captures: u64,
+    pub en_passants: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    pub checks: u64,
+    pub checkmates: u64,
+}
+
+/// Counts the positions `depth` moves from `board`, leaving the board as it was.
+///
+/// The last level is counted from the length of the move lists rather than by making the
+/// moves, as is usual for perft.
+pub fn perft(board: &mut Board, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = movegen::legal_moves(board);
+
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    moves
+        .iter()
+        .map(|&mv| {
+            let state = board.state();
+            board.make_move(mv);
+            let nodes = perft(board, depth - 1);
+            board.unmake_move(mv, state);
+
+            nodes
+        })
+        .sum()
+}
+
+/// Counts the positions at every depth from one to `depth`, with the breakdown of the
+/// published tables, leaving the board as it was.
+///
+/// The first entry holds the moves from `board` itself. Every position is visited, so this is
+/// much slower than [`perft`].
+pub fn perft_detailed(board: &mut Board, depth: u32) -> Vec<PerftCounts> {
+    let mut counts = vec![PerftCounts::default(); depth as usize];
+    count(board, &mut counts);
+
+    counts
+}
+
+fn count(board: &mut Board, counts: &mut [PerftCounts]) {
+    let Some((here, deeper)) = counts.split_first_mut() else {
+        return;
+    };
+
+    for &mv in &movegen::legal_moves(board) {
+        let state = board.state();
+        board.make_move(mv);
+
+        here.nodes += 1;
+        here.captures += mv.is_capture() as u64;
+        here.en_passants += mv.is_en_passant() as u64;
+        here.castles += mv.is_castle() as u64;
+        here.promotions += mv.is_promotion() as u64;
+
+        if board.in_check() {
+            here.checks += 1;
+            here.checkmates += movegen::legal_moves(board).is_empty() as u64;
+        }
+
+        count(board, deeper);
+        board.unmake_move(mv, state);
+    }
+}
@@ -0,0 +1,411 @@
+//! Reading and writing games in Portable Game Notation.
+//!
+//! Only what is needed to replay a game is kept when reading: its tag pairs, the moves of the
+//! main line and the result. Comments, numeric annotation glyphs and variations are skipped.
+//! Games are written in the export format, with a glyph and a comment allowed after each move.
+
+use std::fmt;
+
+use crate::board::Board;
+use crate::fen::FenError;
+use crate::moves::Move;
+use crate::piece::Colour;
+use crate::san::SanError;
+
+/// The reasons PGN text can fail to parse.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PgnError {
+    /// A tag pair is not of the form `[Name "value"]`.
+    InvalidTag,
+    /// The `FEN` tag does not hold a valid position.
+    InvalidFen(FenError),
+    /// A move of the main line cannot be played, counting plies from the start of the game.
+    InvalidMove {
+        ply: usize,
+        san: String,
+        error: SanError,
+    },
+    /// A comment, variation or tag is never closed.
+    Unterminated,
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PgnError::InvalidTag => f.write_str("invalid tag pair"),
+            PgnError::InvalidFen(error) => write!(f, "invalid FEN tag: {error}"),
+            PgnError::InvalidMove { ply, san, error } => {
+                write!(f, "{error} at ply {}: {san}", ply + 1)
+            }
+            PgnError::Unterminated => f.write_str("unterminated comment, variation or tag"),
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+/// A game read from PGN.
+#[derive(Clone, Debug)]
+pub struct Game {
+    tags: Vec<(String, String)>,
+    start: Board,
+    moves: Vec<Move>,
+    result: Option<String>,
+}
+
+impl Game {
+    /// Returns the value of the first tag pair with the given name.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterates over the tag pairs in the order they were written.
+    pub fn tags(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.tags
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// The position the game starts from, set by the `FEN` tag or the standard one.
+    pub fn start(&self) -> &Board {
+        &self.start
+    }
+
+    /// The moves of the main line.
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// The game termination marker, such as `1-0` or `*`, if the movetext ends with one.
+    pub fn result(&self) -> Option<&str> {
+        self.result.as_deref()
+    }
+
+    /// Iterates over the positions of the main line paired with the move played from each.
+    pub fn positions(&self) -> impl Iterator<Item = (Board, Move)> + '_ {
+        self.moves.iter().scan(self.start.clone(), |board, &mv| {
+            let before = board.clone();
+            board.make_move(mv);
+
+            Some((before, mv))
+        })
+    }
+}
+
+/// What is written after a move: a numeric annotation glyph, a comment, both or neither.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Annotation {
+    /// The glyph number, such as 2 for `?`, 4 for `??` or 6 for `?!`.
+    pub nag: Option<u8>,
+    /// The text of a brace comment, which must not contain `}`.
+    pub comment: Option<String>,
+}
+
+/// The longest line of movetext written, as the export format asks.
+const LINE_LENGTH: usize = 79;
+
+/// Writes a game in the PGN export format: the tag pairs, a blank line, then the moves played
+/// from `start` in SAN with their annotations, followed by `result`, wrapped to 79 columns.
+///
+/// The tags are written as given, so a game that does not start from the standard position
+/// needs its `SetUp` and `FEN` tags among them.
+pub fn write_game<'a>(
+    tags: impl IntoIterator<Item = (&'a str, &'a str)>,
+    start: &Board,
+    moves: impl IntoIterator<Item = (Move, Annotation)>,
+    result: &str,
+) -> String {
+    let mut pgn = String::new();
+
+    for (name, value) in tags {
+        let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+        pgn.push_str(&format!("[{name} \"{value}\"]\n"));
+    }
+    pgn.push('\n');
+
+    let mut board = start.clone();
+    let mut tokens = Vec::new();
+    // Black's moves are numbered at the start and after anything that interrupts the moves.
+    let mut numbered = false;
+
+    for (mv, annotation) in moves {
+        let number = board.fullmove_number();
+
+        match board.side_to_move() {
+            Colour::White => tokens.push(format!("{number}.")),
+            Colour::Black if !numbered => tokens.push(format!("{number}...")),
+            Colour::Black => {}
+        }
+
+        tokens.push(board.san(mv));
+        board.make_move(mv);
+        numbered = true;
+
+        if let Some(nag) = annotation.nag {
+            tokens.push(format!("${nag}"));
+            numbered = false;
+        }
+
+        if let Some(comment) = annotation.comment {
+            let comment = format!("{{{}}}", comment.replace('}', ""));
+            tokens.extend(comment_words(&comment));
+            numbered = false;
+        }
+    }
+
+    tokens.push(result.to_owned());
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > LINE_LENGTH {
+            pgn.push_str(&line);
+            pgn.push('\n');
+            line.clear();
+        }
+
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    pgn.push_str(&line);
+    pgn.push('\n');
+
+    pgn
+}
+
+/// Splits a comment into the words a line may be broken between, keeping embedded commands
+/// such as `[%eval 0.35]` whole since tools read them a line at a time.
+fn comment_words(comment: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut command = false;
+
+    for c in comment.chars() {
+        match c {
+            '[' => command = true,
+            ']' => command = false,
+            _ if c.is_whitespace() && !command => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        word.push(c);
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+/// A game whose movetext is still being read.
+struct Partial {
+    tags: Vec<(String, String)>,
+    start: Option<Board>,
+    board: Board,
+    moves: Vec<Move>,
+}
+
+impl Partial {
+    fn new() -> Partial {
+        Partial {
+            tags: Vec::new(),
+            start: None,
+            board: Board::default(),
+            moves: Vec::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.start.is_none()
+    }
+
+    fn play(&mut self, san: &str) -> Result<(), PgnError> {
+        if self.start.is_none() {
+            if let Some((_, fen)) = self.tags.iter().find(|(name, _)| name == "FEN") {
+                self.board = Board::from_fen(fen).map_err(PgnError::InvalidFen)?;
+            }
+
+            self.start = Some(self.board.clone());
+        }
+
+        let mv = self
+            .board
+            .parse_san(san)
+            .map_err(|error| PgnError::InvalidMove {
+                ply: self.moves.len(),
+                san: san.to_owned(),
+                error,
+            })?;
+
+        self.board.make_move(mv);
+        self.moves.push(mv);
+
+        Ok(())
+    }
+
+    fn finish(self, result: Option<String>) -> Result<Game, PgnError> {
+        let start = match self.start {
+            Some(start) => start,
+            None => match self.tags.iter().find(|(name, _)| name == "FEN") {
+                Some((_, fen)) => Board::from_fen(fen).map_err(PgnError::InvalidFen)?,
+                None => Board::default(),
+            },
+        };
+
+        Ok(Game {
+            tags: self.tags,
+            start,
+            moves: self.moves,
+            result,
+        })
+    }
+}
+
+/// Reads every game in `pgn`.
+///
+/// A game ends at its termination marker, or where the tags of the next game begin.
+pub fn parse_games(pgn: &str) -> Result<Vec<Game>, PgnError> {
+    let mut games = Vec::new();
+    let mut game = Partial::new();
+    let mut rest = pgn;
+
+    loop {
+        rest = rest.trim_start();
+
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+
+        match c {
+            '[' => {
+                if game.start.is_some() {
+                    games.push(std::mem::replace(&mut game, Partial::new()).finish(None)?);
+                }
+
+                let end = closing(rest, '"', ']').ok_or(PgnError::Unterminated)?;
+                game.tags.push(parse_tag(&rest[1..end])?);
+                rest = &rest[end + 1..];
+            }
+            '{' => {
+                let end = rest.find('}').ok_or(PgnError::Unterminated)?;
+                rest = &rest[end + 1..];
+            }
+            '(' => rest = skip_variation(rest)?,
+            // Rest-of-line comments, and escaped lines that programs use for their own data.
+            ';' | '%' => rest = rest.find('\n').map_or("", |end| &rest[end..]),
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || "[{(;".contains(c))
+                    .unwrap_or(rest.len())
+                    .max(1);
+                let token = &rest[..end];
+                rest = &rest[end..];
+
+                if matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*") {
+                    games.push(
+                        std::mem::replace(&mut game, Partial::new())
+                            .finish(Some(token.to_owned()))?,
+                    );
+                    continue;
+                }
+
+                // Move numbers may be written apart from or attached to the move, as `12.`,
+                // `12...` or `12.e4`.
+                let san = match token
+                    .trim_start_matches(|c: char| c.is_ascii_digit())
+                    .strip_prefix('.')
+                {
+                    Some(after) => after.trim_start_matches('.'),
+                    None => token,
+                };
+
+                if !san.is_empty() && !san.starts_with('$') {
+                    game.play(san)?;
+                }
+            }
+        }
+    }
+
+    if !game.is_empty() {
+        games.push(game.finish(None)?);
+    }
+
+    Ok(games)
+}
+
+/// Returns the index of the first `close` in `text` that is not inside a string delimited by
+/// `quote`.
+fn closing(text: &str, quote: char, close: char) -> Option<usize> {
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (index, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            _ if c == quote => quoted = !quoted,
+            _ if c == close && !quoted => return Some(index),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Parses the inside of a tag pair, such as `Event "Casual game"`.
+fn parse_tag(tag: &str) -> Result<(String, String), PgnError> {
+    let tag = tag.trim();
+    let (name, value) = tag
+        .split_once(char::is_whitespace)
+        .ok_or(PgnError::InvalidTag)?;
+
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or(PgnError::InvalidTag)?;
+
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(PgnError::InvalidTag);
+    }
+
+    Ok((
+        name.to_owned(),
+        value.replace("\\\"", "\"").replace("\\\\", "\\"),
+    ))
+}
+
+/// Skips a variation, including any variations and comments nested inside it.
+fn skip_variation(text: &str) -> Result<&str, PgnError> {
+    let mut depth = 0;
+    let mut comment = false;
+
+    for (index, c) in text.char_indices() {
+        match c {
+            '}' if comment => comment = false,
+            _ if comment => {}
+            '{' => comment = true,
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Ok(&text[index + 1..]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(PgnError::Unterminated)
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+use std::ops::Not;
+
+/// The side a piece belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Colour {
+    White,
+    Black,
+}
+
+impl Colour {
+    pub const ALL: [Colour; 2] = [Colour::White, Colour::Black];
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Not for Colour {
+    type Output = Colour;
+
+    fn not(self) -> Colour {
+        match self {
+            Colour::White => Colour::Black,
+            Colour::Black => Colour::White,
+        }
+    }
+}
+
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Colour::White => "w",
+            Colour::Black => "b",
+        })
+    }
+}
+
+/// The kind of a piece, irrespective of its colour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum PieceKind {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+impl PieceKind {
+    pub const ALL: [PieceKind; 6] = [
+        PieceKind::Pawn,
+        PieceKind::Knight,
+        PieceKind::Bishop,
+        PieceKind::Rook,
+        PieceKind::Queen,
+        PieceKind::King,
+    ];
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the kind for a lowercase piece letter as used in FEN and SAN.
+    pub const fn from_char(c: char) -> Option<PieceKind> {
+        match c {
+            'p' => Some(PieceKind::Pawn),
+            'n' => Some(PieceKind::Knight),
+            'b' => Some(PieceKind::Bishop),
+            'r' => Some(PieceKind::Rook),
+            'q' => Some(PieceKind::Queen),
+            'k' => Some(PieceKind::King),
+            _ => None,
+        }
+    }
+
+    /// Returns the lowercase letter for this kind.
+    pub const fn to_char(self) -> char {
+        match self {
+            PieceKind::Pawn => 'p',
+            PieceKind::Knight => 'n',
+            PieceKind::Bishop => 'b',
+            PieceKind::Rook => 'r',
+            PieceKind::Queen => 'q',
+            PieceKind::King => 'k',
+        }
+    }
+}
+
+/// A piece of a given kind and colour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Piece {
+    pub kind: PieceKind,
+    pub colour: Colour,
+}
+
+impl Piece {
+    pub const fn new(kind: PieceKind, colour: Colour) -> Piece {
+        Piece { kind, colour }
+    }
+
+    /// Parses a FEN piece letter, uppercase for White and lowercase for Black.
+    pub const fn from_char(c: char) -> Option<Piece> {
+        let colour = if c.is_ascii_uppercase() {
+            Colour::White
+        } else {
+            Colour::Black
+        };
+
+        match PieceKind::from_char(c.to_ascii_lowercase()) {
+            Some(kind) => Some(Piece::new(kind, colour)),
+            None => None,
+        }
+    }
+
+    /// Returns the FEN letter for this piece.
+    pub const fn to_char(self) -> char {
+        let c = self.kind.to_char();
+
+        match self.colour {
+            Colour::White => c.to_ascii_uppercase(),
+            Colour::Black => c,
+        }
+    }
+}
+
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
@@ -0,0 +1,196 @@
+//! Standard Algebraic Notation, as used in PGN and by most human-facing tools.
+
+use std::fmt;
+
+use crate::board::Board;
+use crate::location::{File, Rank, Square};
+use crate::movegen;
+use crate::moves::Move;
+use crate::piece::PieceKind;
+
+/// The reasons a string can fail to parse as a move in SAN.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SanError {
+    /// The string is not syntactically a move.
+    Invalid,
+    /// No legal move matches.
+    Illegal,
+    /// More than one legal move matches and the string does not say which.
+    Ambiguous,
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SanError::Invalid => "invalid SAN move",
+            SanError::Illegal => "illegal move",
+            SanError::Ambiguous => "ambiguous move",
+        })
+    }
+}
+
+impl std::error::Error for SanError {}
+
+impl Board {
+    /// Formats `mv`, which must be legal in this position, in SAN, such as `Nbd7`, `exd8=Q+`
+    /// or `O-O#`.
+    pub fn san(&self, mv: Move) -> String {
+        let mut san = String::new();
+
+        if mv.is_castle() {
+            san.push_str(if mv.to().file() == File::G {
+                "O-O"
+            } else {
+                "O-O-O"
+            });
+        } else {
+            let piece = mv.piece();
+
+            if piece == PieceKind::Pawn {
+                if mv.is_capture() {
+                    san.push(mv.from().file().to_char());
+                }
+            } else {
+                san.push(piece.to_char().to_ascii_uppercase());
+
+                let others: Vec<Square> = movegen::legal_moves(self)
+                    .iter()
+                    .filter(|other| {
+                        other.piece() == piece && other.to() == mv.to() && other.from() != mv.from()
+                    })
+                    .map(|other| other.from())
+                    .collect();
+
+                let (file, rank) = (mv.from().file(), mv.from().rank());
+
+                if !others.is_empty() {
+                    if others.iter().all(|other| other.file() != file) {
+                        san.push(file.to_char());
+                    } else if others.iter().all(|other| other.rank() != rank) {
+                        san.push(rank.to_char());
+                    } else {
+                        san.push(file.to_char());
+                        san.push(rank.to_char());
+                    }
+                }
+            }
+
+            if mv.is_capture() {
+                san.push('x');
+            }
+
+            san.push_str(&mv.to().to_string());
+
+            if let Some(promotion) = mv.promotion() {
+                san.push('=');
+                san.push(promotion.to_char().to_ascii_uppercase());
+            }
+        }
+
+        let mut after = self.clone();
+        after.make_move(mv);
+
+        if after.in_check() {
+            san.push(if movegen::legal_moves(&after).is_empty() {
+                '#'
+            } else {
+                '+'
+            });
+        }
+
+        san
+    }
+
+    /// Parses a move in SAN and finds it among the legal moves.
+    ///
+    /// Parsing is lenient where it cannot be ambiguous: check and annotation suffixes are
+    /// ignored, castling may be written with zeros, and the `=` before a promotion and the `x`
+    /// of a capture are optional.
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        let san = san.trim_end_matches(['+', '#', '!', '?']);
+
+        let moves = movegen::legal_moves(self);
+
+        if matches!(san, "O-O" | "0-0" | "O-O-O" | "0-0-0") {
+            let file = if san.len() == 3 { File::G } else { File::C };
+
+            return moves
+                .iter()
+                .copied()
+                .find(|mv| mv.is_castle() && mv.to().file() == file)
+                .ok_or(SanError::Illegal);
+        }
+
+        let mut chars: Vec<char> = san.chars().collect();
+
+        let piece = match chars.first() {
+            Some(c) if c.is_ascii_uppercase() => {
+                let piece = PieceKind::from_char(c.to_ascii_lowercase())
+                    .filter(|&piece| piece != PieceKind::Pawn)
+                    .ok_or(SanError::Invalid)?;
+
+                chars.remove(0);
+                piece
+            }
+            Some(_) => PieceKind::Pawn,
+            None => return Err(SanError::Invalid),
+        };
+
+        let promotion = match chars.last() {
+            Some(c) if c.is_ascii_uppercase() => {
+                let promotion = PieceKind::from_char(c.to_ascii_lowercase())
+                    .filter(|&promotion| !matches!(promotion, PieceKind::Pawn | PieceKind::King))
+                    .ok_or(SanError::Invalid)?;
+
+                chars.pop();
+                if chars.last() == Some(&'=') {
+                    chars.pop();
+                }
+
+                Some(promotion)
+            }
+            _ => None,
+        };
+
+        if chars.len() < 2 {
+            return Err(SanError::Invalid);
+        }
+
+        let rank = chars.pop().and_then(Rank::from_char);
+        let file = chars.pop().and_then(File::from_char);
+        let to = match (file, rank) {
+            (Some(file), Some(rank)) => Square::new(file, rank),
+            _ => return Err(SanError::Invalid),
+        };
+
+        if chars.last() == Some(&'x') {
+            chars.pop();
+        }
+
+        let (from_file, from_rank) = match chars[..] {
+            [] => (None, None),
+            [c] if File::from_char(c).is_some() => (File::from_char(c), None),
+            [c] if Rank::from_char(c).is_some() => (None, Rank::from_char(c)),
+            [f, r] => match (File::from_char(f), Rank::from_char(r)) {
+                (Some(file), Some(rank)) => (Some(file), Some(rank)),
+                _ => return Err(SanError::Invalid),
+            },
+            _ => return Err(SanError::Invalid),
+        };
+
+        let mut candidates = moves.iter().copied().filter(|mv| {
+            mv.piece() == piece
+                && mv.to() == to
+                && mv.promotion() == promotion
+                && !mv.is_castle()
+                && from_file.is_none_or(|file| mv.from().file() == file)
+                && from_rank.is_none_or(|rank| mv.from().rank() == rank)
+        });
+
+        match (candidates.next(), candidates.next()) {
+            (Some(mv), None) => Ok(mv),
+            (Some(_), Some(_)) => Err(SanError::Ambiguous),
+            (None, _) => Err(SanError::Illegal),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Zobrist hashing keys and full-position hashing.
+//!
+//! Keys are generated at compile time by splitmix64 from [`SEED`], so hashes are stable across
+//! builds and platforms and can be stored or compared between runs.
+
+use crate::board::Board;
+use crate::castling::CastlingPermissions;
+use crate::location::{File, Square};
+use crate::piece::{Colour, Piece, PieceKind};
+
+/// The seed the key tables are generated from.
+pub const SEED: u64 = 0x4761_6D62_6974_2121;
+
+pub(crate) struct Keys {
+    pub(crate) pieces: [[[u64; 64]; 6]; 2],
+    pub(crate) castling: [u64; 16],
+    pub(crate) en_passant: [u64; 8],
+    pub(crate) side: u64,
+    /// Keys for the material signature, indexed by the number of pieces of a kind before the
+    /// one being counted.
+    pub(crate) material: [[[u64; 64]; 6]; 2],
+}
+
+static KEYS: Keys = generate_keys();
+
+const fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+    z ^ (z >> 31)
+}
+
+pub(crate) const fn generate_keys() -> Keys {
+    let mut state = SEED;
+    let mut keys = Keys {
+        pieces: [[[0; 64]; 6]; 2],
+        castling: [0; 16],
+        en_passant: [0; 8],
+        side: 0,
+        material: [[[0; 64]; 6]; 2],
+    };
+
+    let mut colour = 0;
+    while colour < 2 {
+        let mut kind = 0;
+        while kind < 6 {
+            let mut square = 0;
+            while square < 64 {
+                keys.pieces[colour][kind][square] = splitmix64(&mut state);
+                square += 1;
+            }
+            kind += 1;
+        }
+        colour += 1;
+    }
+
+    // Each castling right gets a key and every combination is the XOR of its rights, so that
+    // losing one right can be applied incrementally.
+    let mut rights = [0; 4];
+    let mut i = 0;
+    while i < 4 {
+        rights[i] = splitmix64(&mut state);
+        i += 1;
+    }
+
+    let mut mask = 0;
+    while mask < 16 {
+        let mut i = 0;
+        while i < 4 {
+            if mask & (1 << i) != 0 {
+                keys.castling[mask] ^= rights[i];
+            }
+            i += 1;
+        }
+        mask += 1;
+    }
+
+    let mut file = 0;
+    while file < 8 {
+        keys.en_passant[file] = splitmix64(&mut state);
+        file += 1;
+    }
+
+    keys.side = splitmix64(&mut state);
+
+    // Generated last so that the keys above stay the same as before the table was added.
+    let mut colour = 0;
+    while colour < 2 {
+        let mut kind = 0;
+        while kind < 6 {
+            let mut count = 0;
+            while count < 64 {
+                keys.material[colour][kind][count] = splitmix64(&mut state);
+                count += 1;
+            }
+            kind += 1;
+        }
+        colour += 1;
+    }
+
+    keys
+}
+
+#[inline]
+pub fn piece(piece: Piece, square: Square) -> u64 {
+    KEYS.pieces[piece.colour.index()][piece.kind.index()][square.index()]
+}
+
+#[inline]
+pub fn castling(castling: CastlingPermissions) -> u64 {
+    KEYS.castling[castling.bits() as usize]
+}
+
+#[inline]
+pub fn en_passant(file: File) -> u64 {
+    KEYS.en_passant[file.index()]
+}
+
+/// The key toggled when `piece` is added to or removed from a position with `count` other
+/// pieces of its colour and kind.
+#[inline]
+pub fn material(piece: Piece, count: u32) -> u64 {
+    KEYS.material[piece.colour.index()][piece.kind.index()][count as usize]
+}
+
+/// The key toggled when it is Black to move.
+#[inline]
+pub fn side() -> u64 {
+    KEYS.side
+}
+
+/// Computes the hash of `board` from scratch.
+///
+/// The en passant file is only hashed when the capture is actually legal, see
+/// [`Board::can_capture_en_passant`].
+pub fn hash(board: &Board) -> u64 {
+    let mut hash = castling(board.castling());
+
+    for square in board.occupied() {
+        if let Some(p) = board.piece_at(square) {
+            hash ^= piece(p, square);
+        }
+    }
+
+    if let Some(square) = board
+        .en_passant()
+        .filter(|_| board.can_capture_en_passant())
+    {
+        hash ^= en_passant(square.file());
+    }
+
+    if board.side_to_move() == Colour::Black {
+        hash ^= side();
+    }
+
+    hash
+}
+
+/// Computes the hash of the pawns of `board` from scratch, as kept by [`Board::pawn_key`].
+pub fn pawn_hash(board: &Board) -> u64 {
+    board
+        .pieces(PieceKind::Pawn)
+        .into_iter()
+        .filter_map(|square| board.piece_at(square).map(|p| piece(p, square)))
+        .fold(0, |hash, key| hash ^ key)
+}
+
+/// Computes the material signature of `board` from scratch, as kept by
+/// [`Board::material_key`].
+pub fn material_hash(board: &Board) -> u64 {
+    let mut hash = 0;
+
+    for colour in Colour::ALL {
+        for kind in PieceKind::ALL {
+            for count in 0..board.pieces_of(colour, kind).count() {
+                hash ^= material(Piece::new(kind, colour), count);
+            }
+        }
+    }
+
+    hash
+}
@@ -0,0 +1,103 @@
+use gambit::attacks::*;
+use gambit::{Bitboard, Colour, Square};
+
+fn squares(squares: &[Square]) -> Bitboard {
+    squares
+        .iter()
+        .fold(Bitboard::EMPTY, |bitboard, &square| bitboard | square)
+}
+
+#[test]
+fn leaper_attacks_respect_board_edges() {
+    assert_eq!(
+        knight_attacks(Square::A1),
+        squares(&[Square::B3, Square::C2])
+    );
+    assert_eq!(knight_attacks(Square::E4).count(), 8);
+    assert_eq!(
+        king_attacks(Square::H8),
+        squares(&[Square::G7, Square::H7, Square::G8])
+    );
+    assert_eq!(
+        pawn_attacks(Colour::White, Square::A2),
+        squares(&[Square::B3])
+    );
+    assert_eq!(
+        pawn_attacks(Colour::Black, Square::E5),
+        squares(&[Square::D4, Square::F4])
+    );
+    assert!(pawn_attacks(Colour::White, Square::E8).is_empty());
+}
+
+#[test]
+fn slider_attacks_stop_at_first_blocker() {
+    let occupied = squares(&[Square::D6, Square::B4, Square::F2]);
+
+    assert_eq!(
+        rook_attacks(Square::D4, occupied),
+        squares(&[
+            Square::D1,
+            Square::D2,
+            Square::D3,
+            Square::D5,
+            Square::D6,
+            Square::B4,
+            Square::C4,
+            Square::E4,
+            Square::F4,
+            Square::G4,
+            Square::H4,
+        ])
+    );
+    assert_eq!(
+        bishop_attacks(Square::D4, occupied) & squares(&[Square::F2, Square::G1]),
+        squares(&[Square::F2])
+    );
+    assert_eq!(queen_attacks(Square::D4, Bitboard::EMPTY).count(), 27);
+}
+
+#[test]
+fn xray_rook_attacks_see_through_first_blocker_only() {
+    // Rook on a1, own piece on a3, enemy pieces on a5 and a7.
+    let occupied = squares(&[Square::A3, Square::A5, Square::A7]);
+    let blockers = squares(&[Square::A3]);
+
+    assert_eq!(
+        xray_rook_attacks(Square::A1, occupied, blockers),
+        squares(&[Square::A4, Square::A5])
+    );
+}
+
+#[test]
+fn xray_bishop_attacks_ignore_blockers_outside_the_mask() {
+    let occupied = squares(&[Square::C3, Square::E5]);
+
+    assert!(xray_bishop_attacks(Square::A1, occupied, Bitboard::EMPTY).is_empty());
+    assert_eq!(
+        xray_bishop_attacks(Square::A1, occupied, squares(&[Square::C3])),
+        squares(&[Square::D4, Square::E5])
+    );
+}
+
+#[test]
+fn king_zones_extend_towards_the_enemy() {
+    assert_eq!(king_ring(Square::G1), king_attacks(Square::G1));
+
+    assert_eq!(
+        extended_king_zone(Square::G1, Colour::White),
+        squares(&[
+            Square::F1,
+            Square::G1,
+            Square::H1,
+            Square::F2,
+            Square::G2,
+            Square::H2,
+            Square::F3,
+            Square::G3,
+            Square::H3,
+        ])
+    );
+    assert_eq!(extended_king_zone(Square::E8, Colour::Black).count(), 9);
+    assert_eq!(extended_king_zone(Square::E8, Colour::White).count(), 6);
+    assert_eq!(extended_king_zone(Square::A4, Colour::White).count(), 8);
+}
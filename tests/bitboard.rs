@@ -0,0 +1,57 @@
+use gambit::{Bitboard, Direction, File, Rank, Square};
+
+#[test]
+fn bitboards_draw_with_coordinates() {
+    let bitboard = Bitboard::file(File::A) | Square::H8;
+
+    assert_eq!(
+        bitboard.to_string(),
+        "8  X . . . . . . X\n\
+         7  X . . . . . . .\n\
+         6  X . . . . . . .\n\
+         5  X . . . . . . .\n\
+         4  X . . . . . . .\n\
+         3  X . . . . . . .\n\
+         2  X . . . . . . .\n\
+         1  X . . . . . . .\n   \
+         a b c d e f g h"
+    );
+    assert_eq!(
+        format!("{bitboard:+}").lines().last(),
+        Some("0x8101010101010101")
+    );
+    assert_eq!(
+        format!("{bitboard:#}"),
+        "X......X/X......./X......./X......./X......./X......./X......./X......."
+    );
+    assert_eq!(format!("{:#}", Bitboard::EMPTY), ["........"; 8].join("/"));
+}
+
+#[test]
+fn shifts_drop_squares_that_leave_the_board() {
+    assert_eq!(
+        Bitboard::rank(Rank::Second).shift(Direction::North),
+        Bitboard::rank(Rank::Third)
+    );
+    assert_eq!(
+        Bitboard::rank(Rank::Eighth).shift(Direction::North),
+        Bitboard::EMPTY
+    );
+    assert_eq!(
+        Bitboard::file(File::H).shift(Direction::NorthEast),
+        Bitboard::EMPTY
+    );
+    assert_eq!(
+        Bitboard::file(File::A).shift(Direction::SouthWest),
+        Bitboard::EMPTY
+    );
+
+    for direction in Direction::ALL {
+        for square in Square::iter() {
+            let expected = square
+                .step(direction)
+                .map_or(Bitboard::EMPTY, Bitboard::from);
+            assert_eq!(Bitboard::from(square).shift(direction), expected);
+        }
+    }
+}
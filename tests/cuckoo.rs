@@ -0,0 +1,46 @@
+use gambit::cuckoo::has_upcoming_repetition;
+use gambit::Board;
+
+/// Plays `moves` from `fen`, returning the final position and the keys of those before it.
+fn play(fen: &str, moves: &[&str]) -> (Board, Vec<u64>) {
+    let mut board = Board::from_fen(fen).unwrap();
+    let mut keys = Vec::new();
+
+    for uci in moves {
+        let mv = board.parse_uci(uci).unwrap();
+        keys.push(board.key());
+        board.make_move(mv);
+    }
+
+    (board, keys)
+}
+
+// After the rook goes to h2 and back, the black king can step back to a8 and restore the
+// position after its first move.
+const START: &str = "1k6/8/8/8/8/8/8/K6R b - - 0 1";
+const SHUFFLE: [&str; 4] = ["b8a8", "h1h2", "a8b8", "h2h1"];
+
+#[test]
+fn repetitions_inside_the_line_are_upcoming() {
+    let (board, keys) = play(START, &SHUFFLE);
+
+    assert!(has_upcoming_repetition(&board, &[], &keys));
+}
+
+#[test]
+fn positions_seen_once_before_the_root_are_not_upcoming_repetitions() {
+    let (board, keys) = play(START, &SHUFFLE);
+
+    // The position to return to is the root itself, or was played before it, and occurred
+    // only once, so returning to it would not yet be a draw.
+    assert!(!has_upcoming_repetition(&board, &keys[..2], &keys[2..]));
+    assert!(!has_upcoming_repetition(&board, &keys, &[]));
+}
+
+#[test]
+fn positions_repeated_before_the_root_are_upcoming_repetitions() {
+    let moves = [SHUFFLE, SHUFFLE].concat();
+    let (board, keys) = play(START, &moves);
+
+    assert!(has_upcoming_repetition(&board, &keys, &[]));
+}
@@ -0,0 +1,62 @@
+use gambit::{Board, Castling, CastlingPermissions, Colour, EditError, Piece, PieceKind, Square};
+
+#[test]
+fn edits_match_the_same_position_from_fen() {
+    let mut board = Board::default();
+
+    assert_eq!(
+        board.set_piece(Square::E4, Piece::new(PieceKind::Pawn, Colour::White)),
+        Ok(None)
+    );
+    assert_eq!(
+        board.clear_square(Square::E2),
+        Some(Piece::new(PieceKind::Pawn, Colour::White))
+    );
+    board.set_turn(Colour::Black);
+
+    let expected =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+    assert_eq!(board, expected);
+    assert_eq!(board.key(), expected.key());
+    assert_eq!(board.pawn_key(), expected.pawn_key());
+    assert_eq!(board.material_key(), expected.material_key());
+}
+
+#[test]
+fn castling_rights_follow_the_king_and_rooks() {
+    let mut board = Board::default();
+
+    board.clear_square(Square::H1);
+    assert!(!board.castling().has(Castling::WhiteKingside));
+    assert!(board.castling().has(Castling::WhiteQueenside));
+
+    assert_eq!(
+        board.set_castling_rights(CastlingPermissions::ALL),
+        Err(EditError::ImpossibleCastling(Castling::WhiteKingside))
+    );
+    assert_eq!(board.set_castling_rights("Qk".parse().unwrap()), Ok(()));
+    assert_eq!(board.castling().to_string(), "Qk");
+
+    board.clear_board();
+    assert!(board.occupied().is_empty());
+    assert!(board.castling().is_empty());
+}
+
+#[test]
+fn impossible_pieces_are_refused() {
+    let mut board = Board::default();
+
+    assert_eq!(
+        board.set_piece(Square::A8, Piece::new(PieceKind::Pawn, Colour::White)),
+        Err(EditError::PawnOnBackRank)
+    );
+    assert_eq!(
+        board.set_piece(Square::E4, Piece::new(PieceKind::King, Colour::Black)),
+        Err(EditError::SecondKing)
+    );
+    assert_eq!(
+        board.set_piece(Square::E8, Piece::new(PieceKind::King, Colour::White)),
+        Err(EditError::SecondKing)
+    );
+    assert_eq!(board, Board::default());
+}
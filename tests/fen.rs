@@ -0,0 +1,181 @@
+use gambit::{Board, Epd, FenError, STARTING_POSITION_FEN};
+
+#[test]
+fn round_trips_through_fen() {
+    for fen in [
+        STARTING_POSITION_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+    ] {
+        assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+    }
+}
+
+#[test]
+fn lenient_parse_defaults_missing_counters() {
+    let board = Board::from_fen("8/8/8/8/8/8/8/4K2k w - -").unwrap();
+
+    assert_eq!(board.halfmove_clock(), 0);
+    assert_eq!(board.fullmove_number(), 1);
+}
+
+#[test]
+fn counters_beyond_a_byte() {
+    for fen in [
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 256",
+        "4k3/8/8/8/8/8/8/4K3 b - - 300 1000",
+        "4k3/8/8/8/8/8/8/4K3 w - - 65535 65535",
+    ] {
+        assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+    }
+
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 0 255").unwrap();
+    board.make_move(board.parse_uci("e8e7").unwrap());
+    assert_eq!(board.fullmove_number(), 256);
+
+    // The counters saturate rather than wrap at the end of their range.
+    let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 65535 65535").unwrap();
+    board.make_move(board.parse_uci("e8e7").unwrap());
+    assert_eq!(board.fullmove_number(), 65535);
+    assert_eq!(board.halfmove_clock(), 65535);
+
+    assert_eq!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 65536"),
+        Err(FenError::InvalidFullmoveNumber)
+    );
+    assert_eq!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 65536 1"),
+        Err(FenError::InvalidHalfmoveClock)
+    );
+}
+
+#[test]
+fn strict_parse_accepts_legal_positions() {
+    for fen in [
+        STARTING_POSITION_FEN,
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "8/8/8/k2pP2R/8/8/8/4K3 w - d6 0 1",
+    ] {
+        assert!(Board::from_fen_strict(fen).is_ok(), "{fen}");
+    }
+}
+
+#[test]
+fn strict_parse_rejects_impossible_positions() {
+    let cases = [
+        ("8/8/8/8/8/8/8/4K3 w - - 0 1", FenError::InvalidKingCount),
+        ("4k3/8/8/8/8/8/8/3KK3 w - - 0 1", FenError::InvalidKingCount),
+        ("4k3/8/8/8/8/8/8/P3K3 w - - 0 1", FenError::PawnOnBackRank),
+        ("4k2R/8/8/8/8/8/8/4K3 w - - 0 1", FenError::OpponentInCheck),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+            FenError::ImpossibleCastling,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/R3K3 w K - 0 1",
+            FenError::ImpossibleCastling,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w - d6 0 1",
+            FenError::ImpossibleEnPassant,
+        ),
+    ];
+
+    for (fen, error) in cases {
+        assert!(Board::from_fen(fen).is_ok(), "{fen} should parse leniently");
+        assert_eq!(Board::from_fen_strict(fen).unwrap_err(), error, "{fen}");
+    }
+}
+
+#[test]
+fn writes_fen_dialects() {
+    let board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+
+    assert_eq!(
+        board.fen().x_fen_en_passant().to_string(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+    );
+    assert_eq!(
+        board
+            .fen()
+            .shredder_castling()
+            .without_counters()
+            .to_string(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b HAha e3"
+    );
+}
+
+#[test]
+fn reads_shredder_castling_files() {
+    let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w HAh - 0 1").unwrap();
+
+    assert_eq!(board.to_fen(), "r3k2r/8/8/8/8/8/8/R3K2R w KQk - 0 1");
+    assert_eq!(
+        Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w B - 0 1").unwrap_err(),
+        FenError::InvalidCastling
+    );
+}
+
+#[test]
+fn parses_epd_operations() {
+    let epd: Epd =
+        "1k1r4/pp1b1R2/3q2pp/4p3/2B5/4Q3/PPP2B2/2K5 b - - bm Qd1+; id \"BK.01\"; ce 320; hmvc 3;"
+            .parse()
+            .unwrap();
+
+    assert_eq!(epd.best_moves(), ["Qd1+"]);
+    assert_eq!(epd.id(), Some("BK.01"));
+    assert_eq!(epd.centipawn_evaluation(), Some(320));
+    assert_eq!(epd.board().halfmove_clock(), 3);
+    assert_eq!(
+        epd.to_string(),
+        "1k1r4/pp1b1R2/3q2pp/4p3/2B5/4Q3/PPP2B2/2K5 b - - bm Qd1+; id BK.01; ce 320; hmvc 3;"
+    );
+}
+
+#[test]
+fn epd_operations_round_trip() {
+    let mut epd = Epd::new(Board::default());
+    epd.set("id", vec![r#"the "Immortal" game"#.to_owned()]);
+    epd.set("c0", vec![r"C:\games".to_owned(), String::new()]);
+    epd.set("noop", Vec::new());
+    epd.set("bm", vec!["e4".to_owned(), "d4".to_owned()]);
+
+    let text = epd.to_string();
+    assert_eq!(
+        text,
+        r#"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - id "the \"Immortal\" game"; c0 C:\games ""; noop; bm e4 d4;"#
+    );
+
+    let parsed: Epd = text.parse().unwrap();
+    assert!(parsed.operations().eq(epd.operations()));
+    assert_eq!(parsed.to_string(), text);
+}
+
+#[test]
+fn rejects_unterminated_epd_operations() {
+    assert_eq!(
+        "8/8/8/8/8/8/8/4K2k w - - bm Kd1"
+            .parse::<Epd>()
+            .unwrap_err(),
+        FenError::InvalidOperation
+    );
+}
+
+#[test]
+fn boards_compare_by_position() {
+    use std::collections::HashSet;
+
+    let positions: HashSet<Board> = [
+        STARTING_POSITION_FEN,
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 4 3",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+    ]
+    .into_iter()
+    .map(|fen| Board::from_fen(fen).unwrap())
+    .collect();
+
+    assert_eq!(positions.len(), 2);
+}
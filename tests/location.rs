@@ -0,0 +1,158 @@
+use gambit::location::{CENTER_DISTANCE, CENTER_MANHATTAN};
+use gambit::{Bitboard, Board, Colour, Direction, File, KnightDirection, Rank, Square};
+
+#[test]
+fn square_colours() {
+    assert!(Square::A1.is_dark());
+    assert!(Square::H8.is_dark());
+    assert!(!Square::H1.is_dark());
+    assert!(!Square::D1.is_dark());
+    assert_eq!(Square::D8.colour(), Colour::Black);
+
+    for square in Square::iter() {
+        assert_eq!(Bitboard::DARK_SQUARES.contains(square), square.is_dark());
+        assert_eq!(Bitboard::LIGHT_SQUARES.contains(square), !square.is_dark());
+    }
+
+    assert_eq!(Bitboard::DARK_SQUARES.count(), 32);
+}
+
+#[test]
+fn bishops_by_square_colour() {
+    let board = Board::default();
+
+    assert_eq!(
+        board.bishops_on(Colour::White, Colour::Black),
+        Bitboard::from_square(Square::C1)
+    );
+    assert_eq!(
+        board.bishops_on(Colour::White, Colour::White),
+        Bitboard::from_square(Square::F1)
+    );
+    assert_eq!(
+        board.bishops_on(Colour::Black, Colour::White),
+        Bitboard::from_square(Square::C8)
+    );
+}
+
+#[test]
+fn distances() {
+    assert_eq!(Square::A1.distance(Square::H8), 7);
+    assert_eq!(Square::A1.manhattan_distance(Square::H8), 14);
+    assert_eq!(Square::E4.distance(Square::F6), 2);
+    assert_eq!(Square::E4.manhattan_distance(Square::F6), 3);
+    assert_eq!(Square::C3.distance(Square::C3), 0);
+
+    for a in Square::iter() {
+        for b in Square::iter() {
+            assert_eq!(a.distance(b), b.distance(a));
+            assert!(a.distance(b) <= a.manhattan_distance(b));
+        }
+    }
+
+    assert_eq!(CENTER_DISTANCE[Square::E5.index()], 0);
+    assert_eq!(CENTER_DISTANCE[Square::A1.index()], 3);
+    assert_eq!(CENTER_MANHATTAN[Square::A1.index()], 6);
+    assert_eq!(CENTER_MANHATTAN[Square::C4.index()], 1);
+}
+
+#[test]
+fn direction_offsets_match_steps() {
+    let square = Square::E4;
+
+    for direction in Direction::ALL {
+        let target = square.step(direction).unwrap();
+        assert_eq!(
+            target.index() as i8 - square.index() as i8,
+            direction.offset()
+        );
+        assert_eq!(
+            Direction::ALL[direction.index() ^ 2].offset(),
+            -direction.offset()
+        );
+    }
+
+    for direction in KnightDirection::ALL {
+        let target = square.jump(direction).unwrap();
+        assert_eq!(
+            target.index() as i8 - square.index() as i8,
+            direction.offset()
+        );
+        assert_eq!(square.distance(target), 2);
+    }
+
+    assert_eq!(Square::A1.jump(KnightDirection::WestNorthWest), None);
+    assert_eq!(
+        Square::A1.jump(KnightDirection::NorthNorthEast),
+        Some(Square::B3)
+    );
+}
+
+#[test]
+fn paths_between_squares() {
+    assert_eq!(
+        Square::A1.between(Square::D4),
+        Bitboard::from_square(Square::B2) | Square::C3
+    );
+    assert_eq!(Square::A1.between(Square::B3), Bitboard::EMPTY);
+    assert_eq!(Square::E1.between(Square::E2), Bitboard::EMPTY);
+
+    let board = Board::default();
+
+    assert!(!board.is_path_clear(Square::A1, Square::A8));
+    assert!(board.is_path_clear(Square::A3, Square::H3));
+    assert!(board.is_path_clear(Square::B1, Square::C3));
+    assert!(board.is_path_clear(Square::E1, Square::E2));
+}
+
+#[test]
+fn lists_are_in_index_order() {
+    // The lists can be read in const code, where iterators are not available.
+    const H8: Square = Square::LIST[63];
+    assert_eq!(H8, Square::H8);
+
+    for (index, square) in Square::iter().enumerate() {
+        assert_eq!(square.index(), index);
+        assert_eq!(Square::new(square.file(), square.rank()), square);
+    }
+
+    assert!(File::iter()
+        .enumerate()
+        .all(|(index, file)| file.index() == index));
+    assert!(Rank::iter()
+        .enumerate()
+        .all(|(index, rank)| rank.index() == index));
+    assert_eq!(Rank::iter().next_back(), Some(Rank::Eighth));
+    assert_eq!(Square::iter().len(), 64);
+}
+
+#[test]
+fn relative_squares() {
+    assert_eq!(Rank::Second.relative(Colour::White), Rank::Second);
+    assert_eq!(Rank::Second.relative(Colour::Black), Rank::Seventh);
+    assert_eq!(Rank::Eighth.relative(Colour::Black), Rank::First);
+
+    assert_eq!(Square::E2.relative(Colour::White), Square::E2);
+    assert_eq!(Square::E2.relative(Colour::Black), Square::E7);
+
+    assert_eq!(Square::E2.forward(Colour::White), Some(Square::E3));
+    assert_eq!(Square::E7.forward(Colour::Black), Some(Square::E6));
+    assert_eq!(Square::E8.forward(Colour::White), None);
+    assert_eq!(Square::E1.forward(Colour::Black), None);
+    assert_eq!(Square::E1.backward(Colour::White), None);
+    assert_eq!(Square::E3.backward(Colour::Black), Some(Square::E4));
+
+    for colour in [Colour::White, Colour::Black] {
+        for square in Square::iter() {
+            assert_eq!(
+                square.relative(colour).rank(),
+                square.rank().relative(colour)
+            );
+            assert_eq!(square.relative(colour).relative(colour), square);
+
+            if let Some(ahead) = square.forward(colour) {
+                assert_eq!(ahead.backward(colour), Some(square));
+            }
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use gambit::movegen::{legal_moves, moves_from, MoveList};
+use gambit::{zobrist, Bitboard, Board, Move, Piece, PieceKind, Square, STARTING_POSITION_FEN};
+
+#[test]
+fn move_list_adapters() {
+    let board = Board::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1")
+        .unwrap();
+    let moves = legal_moves(&board);
+
+    assert_eq!(moves.len(), 6);
+    assert_eq!(moves.captures().count(), 0);
+    assert_eq!(moves.of_piece(PieceKind::King).count(), 1);
+    assert_eq!(moves.from_square(Square::B4).count(), 1);
+    assert_eq!(moves.to_square(Square::C5).count(), 2);
+
+    let board = Board::from_fen("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1").unwrap();
+    let moves = legal_moves(&board);
+
+    assert_eq!(moves.promotions().count(), 12);
+    assert_eq!(moves.captures().count(), 11);
+    assert!(moves
+        .to_square(Square::G1)
+        .all(|mv| mv.from() == Square::G2 && mv.is_promotion()));
+}
+
+#[test]
+fn uci_parsing() {
+    let board = Board::from_fen("7r/6P1/6k1/8/8/8/8/K7 w - - 0 1").unwrap();
+
+    let mv = board.parse_uci("g7h8n").unwrap();
+    assert!(mv.is_capture());
+    assert_eq!(mv.promotion(), Some(PieceKind::Knight));
+    assert_eq!(board.parse_uci("g7h8N"), Some(mv));
+    assert_eq!(mv.to_string(), "g7h8n");
+
+    assert_eq!(board.parse_uci("g7h8"), None);
+    assert_eq!(board.parse_uci("g7h8k"), None);
+    assert_eq!(board.parse_uci("g7h8nn"), None);
+    assert_eq!(board.parse_uci("g7"), None);
+    assert_eq!(board.parse_uci("a1a3"), None);
+}
+
+#[test]
+fn promotion_captures_on_the_edge_files() {
+    for (fen, uci, promoted) in [
+        (
+            "7r/6P1/6k1/8/8/8/8/K7 w - - 0 1",
+            "g7h8n",
+            Piece::from_char('N'),
+        ),
+        (
+            "r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1",
+            "b7a8q",
+            Piece::from_char('Q'),
+        ),
+        (
+            "4k3/8/8/8/8/8/1p6/R3K3 b - - 0 1",
+            "b2a1r",
+            Piece::from_char('r'),
+        ),
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let mv = board.parse_uci(uci).unwrap();
+        assert!(board.gives_check(mv), "{uci}");
+
+        let mut after = board.clone();
+        after.make_move(mv);
+
+        assert_eq!(after.piece_at(mv.to()), promoted);
+        assert_eq!(after.piece_at(mv.from()), None);
+        assert_eq!(after.pieces(PieceKind::Pawn), Bitboard::EMPTY);
+        assert!(after.in_check());
+        assert_eq!(after.key(), zobrist::hash(&after));
+        assert_eq!(after, Board::from_fen(&after.to_fen()).unwrap());
+    }
+}
+
+#[test]
+fn en_passant_discovered_checks() {
+    // The capturing pawn uncovers the rook, or the captured pawn uncovers the bishop.
+    for fen in [
+        "4k3/8/8/3pP3/8/8/8/K3R3 w - d6 0 2",
+        "6k1/8/8/3pP3/8/8/B7/K7 w - d6 0 2",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let mv = board.parse_uci("e5d6").unwrap();
+        assert!(mv.is_en_passant());
+        assert!(board.gives_check(mv), "{fen}");
+
+        let mut after = board.clone();
+        after.make_move(mv);
+
+        assert_eq!(after.piece_at(Square::D5), None);
+        assert!(after.in_check());
+        assert_eq!(after.key(), zobrist::hash(&after));
+    }
+}
+
+#[test]
+fn pawn_key_follows_the_pawns() {
+    let board = Board::default();
+    let mut after = board.clone();
+
+    after.make_move(board.parse_uci("g1f3").unwrap());
+    assert_eq!(after.pawn_key(), board.pawn_key());
+    assert_ne!(after.key(), board.key());
+
+    after.make_move(after.parse_uci("e7e5").unwrap());
+    assert_ne!(after.pawn_key(), board.pawn_key());
+
+    // The same pawns with the pieces moved elsewhere share the key.
+    let moved = Board::from_fen("r1bqkb1r/pppppppp/2n2n2/8/8/2N2N2/PPPPPPPP/R1BQKB1R w KQkq - 4 3")
+        .unwrap();
+    assert_eq!(moved.pawn_key(), board.pawn_key());
+    assert_eq!(board.pawn_key(), zobrist::pawn_hash(&board));
+}
+
+#[test]
+fn material_key_counts_the_pieces() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K2N w - - 0 1").unwrap();
+    let moved = Board::from_fen("2k5/8/8/7N/8/8/8/3RK3 b - - 3 5").unwrap();
+    assert_eq!(board.material_key(), moved.material_key());
+
+    let knight_for_bishop = Board::from_fen("4k3/8/8/8/8/8/8/R3K2B w - - 0 1").unwrap();
+    let knights_swapped = Board::from_fen("4k2n/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+    assert_ne!(board.material_key(), knight_for_bishop.material_key());
+    assert_ne!(board.material_key(), knights_swapped.material_key());
+    assert_eq!(board.material_key(), zobrist::material_hash(&board));
+}
+
+#[test]
+fn unmake_restores_the_position() {
+    fn walk(board: &mut Board, depth: u32) {
+        if depth == 0 {
+            return;
+        }
+
+        for &mv in &legal_moves(board) {
+            let before = board.clone();
+            let state = board.state();
+
+            board.make_move(mv);
+            assert_eq!(board.pawn_key(), zobrist::pawn_hash(board), "{mv}");
+            assert_eq!(board.material_key(), zobrist::material_hash(board), "{mv}");
+
+            walk(board, depth - 1);
+            board.unmake_move(mv, state);
+
+            assert_eq!(*board, before, "{mv}");
+            assert_eq!(board.key(), before.key(), "{mv}");
+            assert_eq!(board.pawn_key(), before.pawn_key(), "{mv}");
+            assert_eq!(board.material_key(), before.material_key(), "{mv}");
+            assert_eq!(board.to_fen(), before.to_fen(), "{mv}");
+        }
+    }
+
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    ] {
+        walk(&mut Board::from_fen(fen).unwrap(), 3);
+    }
+}
+
+#[test]
+fn moves_from_a_single_square() {
+    for fen in [
+        STARTING_POSITION_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "8/2p5/3p4/KP5r/1R3pPk/8/4P3/8 b - g3 0 1",
+        "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let moves = legal_moves(&board);
+
+        for square in Bitboard::FULL {
+            let expected: Vec<_> = moves.from_square(square).collect();
+
+            assert_eq!(*moves_from(&board, square), expected, "{fen} {square}");
+        }
+    }
+}
+
+#[test]
+fn check_evasion_queries() {
+    let blocking = |fen| Board::from_fen(fen).unwrap().check_blocking_squares();
+    let must_move_king = |fen| Board::from_fen(fen).unwrap().must_move_king();
+
+    // A rook checking along the back rank can be blocked on the squares in between.
+    let fen = "4k3/8/8/8/8/8/8/r3K3 w - - 0 1";
+    assert_eq!(
+        blocking(fen),
+        Bitboard::from(Square::B1) | Square::C1 | Square::D1
+    );
+    assert!(!must_move_king(fen));
+
+    // Knight and contact checks cannot be blocked.
+    assert!(blocking("4k3/8/8/8/8/3n4/8/4K3 w - - 0 1").is_empty());
+    assert!(blocking("4k3/8/8/8/8/8/4q3/4K3 w - - 0 1").is_empty());
+    assert!(blocking(STARTING_POSITION_FEN).is_empty());
+
+    // A double check leaves only king moves.
+    let fen = "4k3/8/8/8/8/3n4/8/r3K3 w - - 0 1";
+    assert!(blocking(fen).is_empty());
+    assert!(must_move_king(fen));
+    assert!(legal_moves(&Board::from_fen(fen).unwrap())
+        .iter()
+        .all(|mv| mv.piece() == PieceKind::King));
+}
+
+#[test]
+fn pseudo_legality_of_moves_from_elsewhere() {
+    let board = Board::default();
+
+    assert!(board.is_pseudo_legal(Move::double_push(Square::E2, Square::E4)));
+    assert!(board.is_pseudo_legal(Move::new(Square::G1, Square::F3, PieceKind::Knight)));
+    // Missing the double push flag, on another piece's square, blocked or of the wrong side.
+    assert!(!board.is_pseudo_legal(Move::new(Square::E2, Square::E4, PieceKind::Pawn)));
+    assert!(!board.is_pseudo_legal(Move::new(Square::B1, Square::D2, PieceKind::Knight)));
+    assert!(!board.is_pseudo_legal(Move::new(Square::F1, Square::C4, PieceKind::Bishop)));
+    assert!(!board.is_pseudo_legal(Move::new(Square::E7, Square::E6, PieceKind::Pawn)));
+    assert!(!board.is_pseudo_legal(Move::new(Square::E2, Square::E3, PieceKind::Knight)));
+    assert!(!board.is_pseudo_legal(Move::castle(Square::E1, Square::G1)));
+
+    // A pinned knight's moves are pseudo-legal, and the captured piece must be the one there.
+    let board = Board::from_fen("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+    let capture = Move::new(Square::E2, Square::C3, PieceKind::Knight);
+    assert!(board.is_pseudo_legal(capture));
+    assert!(!board.is_pseudo_legal(capture.with_capture(PieceKind::Rook)));
+    assert!(!board.is_pseudo_legal(Move::new(Square::E1, Square::E7, PieceKind::King)));
+
+    let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    assert!(board.is_pseudo_legal(Move::en_passant(Square::E5, Square::D6)));
+    assert!(!board.is_pseudo_legal(Move::en_passant(Square::E5, Square::F6)));
+    assert!(!board.is_pseudo_legal(Move::new(Square::E5, Square::D6, PieceKind::Pawn)));
+
+    // Promotions must promote on the last rank, and only there.
+    let board = Board::from_fen("4k3/P7/8/8/8/8/1P6/4K3 w - - 0 1").unwrap();
+    let push = Move::new(Square::A7, Square::A8, PieceKind::Pawn);
+    assert!(board.is_pseudo_legal(push.with_promotion(PieceKind::Queen)));
+    assert!(!board.is_pseudo_legal(push));
+    assert!(!board.is_pseudo_legal(push.with_promotion(PieceKind::King)));
+    assert!(!board.is_pseudo_legal(
+        Move::new(Square::B2, Square::B3, PieceKind::Pawn).with_promotion(PieceKind::Queen)
+    ));
+
+    // Castling may not start in check or cross an attacked square.
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+    assert!(board.is_pseudo_legal(Move::castle(Square::E1, Square::G1)));
+    assert!(board.is_pseudo_legal(Move::castle(Square::E1, Square::C1)));
+    let board = Board::from_fen("4k3/8/8/8/8/8/5r2/R3K2R w KQ - 0 1").unwrap();
+    assert!(!board.is_pseudo_legal(Move::castle(Square::E1, Square::G1)));
+    assert!(board.is_pseudo_legal(Move::castle(Square::E1, Square::C1)));
+    let board = Board::from_fen("4k3/8/8/8/8/8/4r3/R3K2R w KQ - 0 1").unwrap();
+    assert!(!board.is_pseudo_legal(Move::castle(Square::E1, Square::C1)));
+}
+
+#[test]
+fn generated_moves_are_pseudo_legal() {
+    for fen in [
+        STARTING_POSITION_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3pPk/8/4P3/8 b - g3 0 1",
+        "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+
+        for &mv in &legal_moves(&board) {
+            assert!(board.is_pseudo_legal(mv), "{fen} {mv}");
+        }
+    }
+}
+
+#[test]
+fn pseudo_legality_agrees_with_the_generator() {
+    // Moves from every position of a small tree, tried in each of them, stand in for the
+    // moves a hash collision or a stale killer would bring.
+    fn collect(board: &mut Board, depth: u32, positions: &mut Vec<Board>, moves: &mut Vec<Move>) {
+        positions.push(board.clone());
+        if depth == 0 {
+            return;
+        }
+
+        for &mv in &legal_moves(board) {
+            moves.push(mv);
+
+            let state = board.state();
+            board.make_move(mv);
+            collect(board, depth - 1, positions, moves);
+            board.unmake_move(mv, state);
+        }
+    }
+
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    ] {
+        let (mut positions, mut moves) = (Vec::new(), Vec::new());
+        collect(
+            &mut Board::from_fen(fen).unwrap(),
+            2,
+            &mut positions,
+            &mut moves,
+        );
+        moves.sort_by_key(|mv| mv.to_string());
+        moves.dedup();
+
+        for board in &positions {
+            let legal = legal_moves(board);
+            let us = board.side_to_move();
+
+            for &mv in &moves {
+                let pseudo_legal = board.is_pseudo_legal(mv);
+                let keeps_king_safe = pseudo_legal && {
+                    let mut after = board.clone();
+                    after.make_move(mv);
+                    let king = after.king_square(us).unwrap();
+
+                    (after.attackers_to(king, after.occupied()) & after.colour(!us)).is_empty()
+                };
+
+                assert_eq!(legal.contains(&mv), keeps_king_safe, "{board:?} {mv}");
+                assert_eq!(
+                    mv.pack().unpack(board) == Some(mv),
+                    pseudo_legal,
+                    "{board:?} {mv}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn moves_pushed_to_each_target_share_their_flags() {
+    let mut list = MoveList::new();
+    let mv = Move::new(Square::B7, Square::B7, PieceKind::Pawn)
+        .with_capture(PieceKind::Rook)
+        .with_promotion(PieceKind::Queen);
+
+    list.push_each(mv, Bitboard::from(Square::A8) | Square::C8);
+
+    assert_eq!(
+        list.to_vec(),
+        [
+            Move::new(Square::B7, Square::A8, PieceKind::Pawn)
+                .with_capture(PieceKind::Rook)
+                .with_promotion(PieceKind::Queen),
+            Move::new(Square::B7, Square::C8, PieceKind::Pawn)
+                .with_capture(PieceKind::Rook)
+                .with_promotion(PieceKind::Queen),
+        ]
+    );
+}
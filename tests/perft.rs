@@ -0,0 +1,127 @@
+use gambit::{perft, perft_detailed, Board, PerftCounts, STARTING_POSITION_FEN};
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+const POSITION_3: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+const POSITION_4: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+const POSITION_5: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+const POSITION_6: &str = "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10";
+
+/// Builds the counts of one row of a published table: nodes, captures, en passants, castles,
+/// promotions, checks and checkmates.
+fn row(counts: [u64; 7]) -> PerftCounts {
+    let [nodes, captures, en_passants, castles, promotions, checks, checkmates] = counts;
+
+    PerftCounts {
+        nodes,
+        captures,
+        en_passants,
+        castles,
+        promotions,
+        checks,
+        checkmates,
+    }
+}
+
+#[test]
+fn standard_positions() {
+    for (fen, nodes) in [
+        (STARTING_POSITION_FEN, &[1, 20, 400, 8902, 197_281][..]),
+        (KIWIPETE, &[1, 48, 2039, 97_862]),
+        (POSITION_3, &[1, 14, 191, 2812, 43_238]),
+        (POSITION_4, &[1, 6, 264, 9467]),
+        (POSITION_5, &[1, 44, 1486, 62_379]),
+        (POSITION_6, &[1, 46, 2079, 89_890]),
+    ] {
+        let mut board = Board::from_fen(fen).unwrap();
+
+        for (depth, &expected) in nodes.iter().enumerate() {
+            assert_eq!(
+                perft(&mut board, depth as u32),
+                expected,
+                "{fen} at depth {depth}"
+            );
+        }
+
+        assert_eq!(board.to_fen(), fen, "perft left {fen} changed");
+    }
+}
+
+#[test]
+fn detailed_counts_match_the_published_tables() {
+    assert_eq!(
+        perft_detailed(&mut Board::default(), 4),
+        [
+            row([20, 0, 0, 0, 0, 0, 0]),
+            row([400, 0, 0, 0, 0, 0, 0]),
+            row([8902, 34, 0, 0, 0, 12, 0]),
+            row([197_281, 1576, 0, 0, 0, 469, 8]),
+        ]
+    );
+    assert_eq!(
+        perft_detailed(&mut Board::from_fen(KIWIPETE).unwrap(), 3),
+        [
+            row([48, 8, 0, 2, 0, 0, 0]),
+            row([2039, 351, 1, 91, 0, 3, 0]),
+            row([97_862, 17_102, 45, 3162, 0, 993, 1]),
+        ]
+    );
+    assert_eq!(
+        perft_detailed(&mut Board::from_fen(POSITION_3).unwrap(), 4),
+        [
+            row([14, 1, 0, 0, 0, 2, 0]),
+            row([191, 14, 0, 0, 0, 10, 0]),
+            row([2812, 209, 2, 0, 0, 267, 0]),
+            row([43_238, 3348, 123, 0, 0, 1680, 17]),
+        ]
+    );
+    assert_eq!(
+        perft_detailed(&mut Board::from_fen(POSITION_4).unwrap(), 3),
+        [
+            row([6, 0, 0, 0, 0, 0, 0]),
+            row([264, 87, 0, 6, 48, 10, 0]),
+            row([9467, 1021, 4, 0, 120, 38, 22]),
+        ]
+    );
+}
+
+/// Checks `fen` at the depth of the published tables. The six positions come to well over a
+/// billion nodes, so these only run with the `slow-tests` feature.
+fn full_depth(fen: &str, depth: u32, nodes: u64) {
+    assert_eq!(perft(&mut Board::from_fen(fen).unwrap(), depth), nodes);
+}
+
+#[test]
+#[cfg_attr(not(feature = "slow-tests"), ignore = "run with --features slow-tests")]
+fn starting_position_at_full_depth() {
+    full_depth(STARTING_POSITION_FEN, 6, 119_060_324);
+}
+
+#[test]
+#[cfg_attr(not(feature = "slow-tests"), ignore = "run with --features slow-tests")]
+fn kiwipete_at_full_depth() {
+    full_depth(KIWIPETE, 5, 193_690_690);
+}
+
+#[test]
+#[cfg_attr(not(feature = "slow-tests"), ignore = "run with --features slow-tests")]
+fn position_3_at_full_depth() {
+    full_depth(POSITION_3, 7, 178_633_661);
+}
+
+#[test]
+#[cfg_attr(not(feature = "slow-tests"), ignore = "run with --features slow-tests")]
+fn position_4_at_full_depth() {
+    full_depth(POSITION_4, 6, 706_045_033);
+}
+
+#[test]
+#[cfg_attr(not(feature = "slow-tests"), ignore = "run with --features slow-tests")]
+fn position_5_at_full_depth() {
+    full_depth(POSITION_5, 5, 89_941_194);
+}
+
+#[test]
+#[cfg_attr(not(feature = "slow-tests"), ignore = "run with --features slow-tests")]
+fn position_6_at_full_depth() {
+    full_depth(POSITION_6, 5, 164_075_551);
+}
@@ -0,0 +1,120 @@
+use gambit::pgn::{parse_games, write_game, Annotation};
+use gambit::{Board, PgnError, SanError};
+
+#[test]
+fn reads_tags_moves_and_results() {
+    let pgn = r#"
+[Event "Casual \"blitz\""]
+[White "A"]
+[Black "B"]
+
+1. e4 {king's pawn} e5 2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) 2... Nc6 $1 3.Bb5 a6!? ; the Morphy
+4. Ba4 Nf6 5. O-O 1-0
+
+[Event "Second"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1"]
+
+1... Kd7 2. e4 *
+"#;
+
+    let games = parse_games(pgn).unwrap();
+    assert_eq!(games.len(), 2);
+
+    let game = &games[0];
+    assert_eq!(game.tag("Event"), Some("Casual \"blitz\""));
+    assert_eq!(game.tags().count(), 3);
+    assert_eq!(game.result(), Some("1-0"));
+    assert_eq!(game.start(), &Board::default());
+
+    let moves: Vec<String> = game.moves().iter().map(ToString::to_string).collect();
+    assert_eq!(
+        moves,
+        ["e2e4", "e7e5", "g1f3", "b8c6", "f1b5", "a7a6", "b5a4", "g8f6", "e1g1"]
+    );
+
+    let positions: Vec<Board> = game.positions().map(|(board, _)| board).collect();
+    assert_eq!(positions[0], Board::default());
+    assert_eq!(positions.last().unwrap().san(game.moves()[8]), "O-O");
+
+    let game = &games[1];
+    assert_eq!(game.result(), Some("*"));
+    assert_eq!(game.moves().len(), 2);
+    assert_eq!(game.start().to_fen(), "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1");
+}
+
+#[test]
+fn reports_malformed_games() {
+    assert_eq!(
+        parse_games("1. e4 e5 2. Ke3").unwrap_err(),
+        PgnError::InvalidMove {
+            ply: 2,
+            san: "Ke3".to_owned(),
+            error: SanError::Illegal,
+        }
+    );
+    assert_eq!(
+        parse_games("[Event Casual]").unwrap_err(),
+        PgnError::InvalidTag
+    );
+    assert_eq!(
+        parse_games("1. e4 {unfinished").unwrap_err(),
+        PgnError::Unterminated
+    );
+    assert_eq!(
+        parse_games("1. e4 (1. d4 d5").unwrap_err(),
+        PgnError::Unterminated
+    );
+    assert!(matches!(
+        parse_games("[FEN \"8/8/8 w - - 0 1\"]\n1. e4"),
+        Err(PgnError::InvalidFen(_))
+    ));
+    assert!(parse_games("  \n").unwrap().is_empty());
+}
+
+#[test]
+fn writes_annotated_games_that_read_back() {
+    let games = parse_games(
+        "[Event \"Casual \\\"blitz\\\"\"]\n1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 4. Ba4 Nf6 5. O-O 1-0",
+    )
+    .unwrap();
+    let game = &games[0];
+
+    let annotations = game.moves().iter().enumerate().map(|(ply, &mv)| {
+        let annotation = match ply {
+            1 => Annotation {
+                nag: Some(6),
+                comment: None,
+            },
+            4 => Annotation {
+                nag: None,
+                comment: Some(
+                    "the Ruy Lopez, a long} [%cmd not split] comment that will not fit".to_owned(),
+                ),
+            },
+            _ => Annotation::default(),
+        };
+
+        (mv, annotation)
+    });
+    let pgn = write_game(game.tags(), game.start(), annotations, "1-0");
+
+    assert_eq!(
+        pgn,
+        "[Event \"Casual \\\"blitz\\\"\"]\n\n\
+         1. e4 e5 $6 2. Nf3 Nc6 3. Bb5 {the Ruy Lopez, a long [%cmd not split] comment\n\
+         that will not fit} 3... a6 4. Ba4 Nf6 5. O-O 1-0\n"
+    );
+
+    let read = parse_games(&pgn).unwrap();
+    assert_eq!(read[0].tag("Event"), game.tag("Event"));
+    assert_eq!(read[0].moves(), game.moves());
+    assert_eq!(read[0].result(), Some("1-0"));
+
+    let start = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 7").unwrap();
+    let mv = start.parse_san("Kd7").unwrap();
+    assert_eq!(
+        write_game([("SetUp", "1")], &start, [(mv, Annotation::default())], "*"),
+        "[SetUp \"1\"]\n\n7... Kd7 *\n"
+    );
+}
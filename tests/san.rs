@@ -0,0 +1,92 @@
+use gambit::movegen::legal_moves;
+use gambit::{Board, SanError};
+
+const POSITIONS: [&str; 4] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+];
+
+#[test]
+fn san_round_trips_every_legal_move() {
+    for fen in POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+
+        for &mv in &legal_moves(&board) {
+            assert_eq!(board.parse_san(&board.san(mv)), Ok(mv), "{fen} {mv}");
+        }
+    }
+}
+
+#[test]
+fn san_formatting() {
+    let board = Board::from_fen(POSITIONS[1]).unwrap();
+    let san = |uci: &str| {
+        let mv = legal_moves(&board)
+            .iter()
+            .copied()
+            .find(|mv| mv.to_string() == uci)
+            .unwrap();
+
+        board.san(mv)
+    };
+
+    assert_eq!(san("e1g1"), "O-O");
+    assert_eq!(san("e1c1"), "O-O-O");
+    assert_eq!(san("d5e6"), "dxe6");
+    assert_eq!(san("e5f7"), "Nxf7");
+    assert_eq!(san("c3b1"), "Nb1");
+    assert_eq!(san("f3f6"), "Qxf6");
+
+    let board = Board::from_fen(POSITIONS[2]).unwrap();
+    let mv = board.parse_san("dxc8=N").unwrap();
+
+    assert_eq!(mv.to_string(), "d7c8n");
+    assert_eq!(board.san(mv), "dxc8=N");
+}
+
+#[test]
+fn san_parsing_is_lenient_but_exact() {
+    let board = Board::default();
+
+    assert_eq!(board.parse_san("Nf3!?").unwrap().to_string(), "g1f3");
+    assert_eq!(board.parse_san("e4").unwrap().to_string(), "e2e4");
+    assert_eq!(board.parse_san("e5"), Err(SanError::Illegal));
+    assert_eq!(board.parse_san("O-O"), Err(SanError::Illegal));
+    assert_eq!(board.parse_san("Zf3"), Err(SanError::Invalid));
+    assert_eq!(board.parse_san(""), Err(SanError::Invalid));
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w - - 0 1").unwrap();
+
+    assert_eq!(board.parse_san("Rb2"), Err(SanError::Illegal));
+    assert_eq!(board.parse_san("Rd1").unwrap().to_string(), "a1d1");
+    assert_eq!(board.parse_san("Rf1").unwrap().to_string(), "h1f1");
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/4K3/R6R w - - 0 1").unwrap();
+
+    assert_eq!(board.parse_san("Rd1"), Err(SanError::Ambiguous));
+    assert_eq!(board.parse_san("Rad1").unwrap().to_string(), "a1d1");
+}
+
+#[test]
+fn san_for_checking_promotions_and_en_passant() {
+    for (fen, uci, san) in [
+        ("7r/6P1/6k1/8/8/8/8/K7 w - - 0 1", "g7h8n", "gxh8=N+"),
+        ("7r/6P1/6k1/8/8/8/8/K7 w - - 0 1", "g7g8q", "g8=Q+"),
+        ("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7a8r", "bxa8=R+"),
+        ("4k3/8/8/3pP3/8/8/8/K3R3 w - d6 0 2", "e5d6", "exd6+"),
+        ("6k1/8/8/3pP3/8/8/B7/K7 w - d6 0 2", "e5d6", "exd6+"),
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        let mv = board.parse_uci(uci).unwrap();
+
+        assert_eq!(board.san(mv), san);
+        assert_eq!(board.parse_san(san), Ok(mv));
+    }
+
+    let board = Board::from_fen("7r/6P1/6k1/8/8/8/8/K7 w - - 0 1").unwrap();
+
+    assert_eq!(board.parse_san("gxh8N"), board.parse_san("gxh8=N+"));
+    assert_eq!(board.parse_san("gxh8"), Err(SanError::Illegal));
+}
@@ -0,0 +1,66 @@
+//! Colour-symmetry checks over a corpus of positions. Anything computed from a position must
+//! agree with the same computation on the colour-flipped position.
+
+use gambit::{zobrist, Board, Colour};
+
+const POSITIONS: [&str; 8] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    "8/8/8/k2pP2R/8/8/8/4K3 w - d6 0 1",
+    "4r1k1/8/8/1q6/4N3/2B5/8/4K2R b K - 3 40",
+];
+
+fn corpus() -> impl Iterator<Item = Board> {
+    POSITIONS
+        .iter()
+        .map(|fen| Board::from_fen(fen).expect("corpus positions are valid"))
+}
+
+#[test]
+fn flipping_twice_restores_the_position() {
+    for board in corpus() {
+        let restored = board.flipped().flipped();
+
+        assert_eq!(restored.to_fen(), board.to_fen());
+        assert_eq!(restored.key(), board.key());
+    }
+}
+
+#[test]
+fn flipped_position_swaps_sides() {
+    for board in corpus() {
+        let flipped = board.flipped();
+
+        assert_eq!(flipped.side_to_move(), !board.side_to_move());
+        assert_eq!(
+            flipped.colour(Colour::White).count(),
+            board.colour(Colour::Black).count()
+        );
+        assert_eq!(
+            flipped.can_capture_en_passant(),
+            board.can_capture_en_passant()
+        );
+        assert_ne!(zobrist::hash(&flipped), zobrist::hash(&board));
+    }
+}
+
+#[test]
+fn pins_mirror_under_flipping() {
+    for board in corpus() {
+        let flipped = board.flipped();
+
+        for colour in Colour::ALL {
+            let pinned = board.pinned_pieces(colour).map(|square| square.flipped());
+            let discovered = board
+                .candidates_for_discovered_check(colour)
+                .map(|square| square.flipped());
+
+            assert!(pinned.eq(flipped.pinned_pieces(!colour)));
+            assert!(discovered.eq(flipped.candidates_for_discovered_check(!colour)));
+        }
+    }
+}